@@ -163,26 +163,21 @@ impl AppState {
     pub fn workspace_recent_files(&self) -> Vec<String> {
         self.editor
             .workspace_config()
-            .map(|config| {
-                config
-                    .recent_files()
-                    .map(|entry| entry.to_string())
-                    .collect()
-            })
+            .map(|config| config.recent_files().collect())
             .unwrap_or_default()
     }
 
     pub fn workspace_recent_debug_targets(&self) -> Vec<DebugTargetRecord> {
         self.editor
             .workspace_config()
-            .map(|config| config.recent_debug_targets().cloned().collect())
+            .map(|config| config.recent_debug_targets().collect())
             .unwrap_or_default()
     }
 
     pub fn workspace_last_debug_target(&self) -> Option<DebugTargetRecord> {
         self.editor
             .workspace_config()
-            .and_then(|config| config.last_debug_target().cloned())
+            .and_then(|config| config.last_debug_target())
     }
 
     pub fn record_recent_workspace_file(&mut self) -> Option<(String, WorkspaceConfig)> {
@@ -0,0 +1,234 @@
+use std::collections::HashMap;
+use std::ops::Range;
+use vedit_core::TextBuffer;
+
+/// One step of a line diff. `old` and `new` are line ranges into the two
+/// buffers; the side an `Insert` or `Delete` does not touch has an empty
+/// range marking where the change sits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffOp {
+    Equal {
+        old: Range<usize>,
+        new: Range<usize>,
+    },
+    Delete {
+        old: Range<usize>,
+        new: Range<usize>,
+    },
+    Insert {
+        old: Range<usize>,
+        new: Range<usize>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    Equal,
+    Delete,
+    Insert,
+}
+
+/// Line-level diff of `old` against `new` using Myers' algorithm, with
+/// lines split as by [`TextBuffer::lines`].
+///
+/// Each distinct line is stored once and compared by id, so two large,
+/// mostly equal documents are never held as full strings.
+pub fn diff(old: &TextBuffer, new: &TextBuffer) -> Vec<DiffOp> {
+    let mut ids: HashMap<String, usize> = HashMap::new();
+    let mut intern = |line: String| {
+        let next = ids.len();
+        *ids.entry(line).or_insert(next)
+    };
+    let old_lines: Vec<usize> = old.lines().map(&mut intern).collect();
+    let new_lines: Vec<usize> = new.lines().map(&mut intern).collect();
+    diff_ids(&old_lines, &new_lines)
+}
+
+fn diff_ids(old: &[usize], new: &[usize]) -> Vec<DiffOp> {
+    // Common ends need no search.
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut steps = vec![Step::Equal; prefix];
+    steps.extend(myers(
+        &old[prefix..old.len() - suffix],
+        &new[prefix..new.len() - suffix],
+    ));
+    steps.extend(std::iter::repeat_n(Step::Equal, suffix));
+    group_steps(&steps)
+}
+
+/// The shortest edit script turning `a` into `b`, one step per line.
+fn myers(a: &[usize], b: &[usize]) -> Vec<Step> {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let max = n + m;
+    if max == 0 {
+        return Vec::new();
+    }
+    let offset = max as usize;
+    let mut v = vec![0isize; 2 * offset + 2];
+    // trace[d] holds the furthest x of each diagonal -d..=d before round d.
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+    'search: for d in 0..=max {
+        trace.push(v[offset - d as usize..=offset + d as usize].to_vec());
+        for k in (-d..=d).step_by(2) {
+            let at = |k: isize| (offset as isize + k) as usize;
+            let mut x = if k == -d || (k != d && v[at(k - 1)] < v[at(k + 1)]) {
+                v[at(k + 1)]
+            } else {
+                v[at(k - 1)] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[at(k)] = x;
+            if x >= n && y >= m {
+                break 'search;
+            }
+        }
+    }
+
+    let mut steps = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for (d, round) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let get = |k: isize| round[(k + d) as usize];
+        let k = x - y;
+        let prev_k = if k == -d || (k != d && get(k - 1) < get(k + 1)) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let prev_x = if d == 0 { 0 } else { get(prev_k) };
+        let prev_y = prev_x - prev_k;
+        while x > prev_x && y > prev_y {
+            steps.push(Step::Equal);
+            x -= 1;
+            y -= 1;
+        }
+        if d > 0 {
+            steps.push(if x == prev_x {
+                Step::Insert
+            } else {
+                Step::Delete
+            });
+        }
+        x = prev_x;
+        y = prev_y;
+    }
+    steps.reverse();
+    steps
+}
+
+fn group_steps(steps: &[Step]) -> Vec<DiffOp> {
+    let mut ops = Vec::new();
+    let (mut old, mut new) = (0, 0);
+    for run in steps.chunk_by(|a, b| a == b) {
+        let len = run.len();
+        let (old_len, new_len) = match run[0] {
+            Step::Equal => (len, len),
+            Step::Delete => (len, 0),
+            Step::Insert => (0, len),
+        };
+        let old_range = old..old + old_len;
+        let new_range = new..new + new_len;
+        ops.push(match run[0] {
+            Step::Equal => DiffOp::Equal {
+                old: old_range,
+                new: new_range,
+            },
+            Step::Delete => DiffOp::Delete {
+                old: old_range,
+                new: new_range,
+            },
+            Step::Insert => DiffOp::Insert {
+                old: old_range,
+                new: new_range,
+            },
+        });
+        old += old_len;
+        new += new_len;
+    }
+    ops
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diffs_edited_and_inserted_lines() {
+        let old = TextBuffer::from_text("fn main() {\n    let a = 1;\n    run(a);\n}\n");
+        let new =
+            TextBuffer::from_text("fn main() {\n    let a = 2;\n    run(a);\n    log(a);\n}\n");
+        assert_eq!(
+            diff(&old, &new),
+            vec![
+                DiffOp::Equal {
+                    old: 0..1,
+                    new: 0..1
+                },
+                DiffOp::Delete {
+                    old: 1..2,
+                    new: 1..1
+                },
+                DiffOp::Insert {
+                    old: 2..2,
+                    new: 1..2
+                },
+                DiffOp::Equal {
+                    old: 2..3,
+                    new: 2..3
+                },
+                DiffOp::Insert {
+                    old: 3..3,
+                    new: 3..4
+                },
+                DiffOp::Equal {
+                    old: 3..4,
+                    new: 4..5
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn diffs_empty_and_identical_buffers() {
+        let text = TextBuffer::from_text("a\nb\n");
+        assert_eq!(
+            diff(&text, &text),
+            vec![DiffOp::Equal {
+                old: 0..2,
+                new: 0..2
+            }]
+        );
+        assert_eq!(
+            diff(&TextBuffer::new(), &text),
+            vec![DiffOp::Insert {
+                old: 0..0,
+                new: 0..2
+            }]
+        );
+        assert_eq!(diff(&TextBuffer::new(), &TextBuffer::new()), vec![]);
+    }
+
+    #[test]
+    fn myers_finds_shortest_script() {
+        // The classic example: ABCABBA -> CBABAC needs 5 edits.
+        let a = [0, 1, 2, 0, 1, 1, 0];
+        let b = [2, 1, 0, 1, 0, 2];
+        let steps = myers(&a, &b);
+        let edits = steps.iter().filter(|step| **step != Step::Equal).count();
+        assert_eq!(edits, 5);
+        let deleted = steps.iter().filter(|step| **step != Step::Insert).count();
+        let inserted = steps.iter().filter(|step| **step != Step::Delete).count();
+        assert_eq!((deleted, inserted), (a.len(), b.len()));
+    }
+}
@@ -0,0 +1,190 @@
+use std::io::{self, Read, Write};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+use vedit_core::{Language, TextBuffer};
+
+/// Reformats the text of a document.
+pub trait Formatter {
+    fn format(&self, lang: Language, text: &str) -> io::Result<String>;
+}
+
+/// A formatter that runs an external command (rustfmt, clang-format, ...),
+/// feeding the text on stdin and reading the formatted text from stdout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalFormatter {
+    pub program: String,
+    pub args: Vec<String>,
+    /// How long the command may run before it is killed.
+    pub timeout: Duration,
+}
+
+impl ExternalFormatter {
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+    pub fn new(
+        program: impl Into<String>,
+        args: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        Self {
+            program: program.into(),
+            args: args.into_iter().map(Into::into).collect(),
+            timeout: Self::DEFAULT_TIMEOUT,
+        }
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+impl Formatter for ExternalFormatter {
+    /// Runs the command. Exceeding the timeout yields a `TimedOut` error and
+    /// a nonzero exit an error carrying the command's stderr.
+    fn format(&self, _lang: Language, text: &str) -> io::Result<String> {
+        let mut child = Command::new(&self.program)
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        // Feed and drain the pipes on threads so a large document cannot
+        // deadlock against a formatter that writes before reading all input.
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        let input = text.to_string();
+        let writer = thread::spawn(move || stdin.write_all(input.as_bytes()));
+        let mut stdout = child.stdout.take().expect("stdout is piped");
+        let reader = thread::spawn(move || {
+            let mut output = String::new();
+            stdout.read_to_string(&mut output).map(|_| output)
+        });
+        let mut stderr = child.stderr.take().expect("stderr is piped");
+        let errors = thread::spawn(move || {
+            let mut output = String::new();
+            let _ = stderr.read_to_string(&mut output);
+            output
+        });
+
+        let deadline = Instant::now() + self.timeout;
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            if Instant::now() >= deadline {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    format!("{} timed out after {:?}", self.program, self.timeout),
+                ));
+            }
+            thread::sleep(Duration::from_millis(10));
+        };
+
+        // A formatter may exit without consuming its input; that is only an
+        // error when it also fails.
+        let written = writer.join().unwrap_or(Ok(()));
+        let output = reader
+            .join()
+            .unwrap_or_else(|_| Err(io::Error::other("stdout reader panicked")));
+        let errors = errors.join().unwrap_or_default();
+
+        if !status.success() {
+            return Err(io::Error::other(format!(
+                "{} failed ({status}): {}",
+                self.program,
+                errors.trim()
+            )));
+        }
+        written?;
+        output
+    }
+}
+
+/// Formats the whole buffer, replacing only the span that changed so
+/// positions before and after it are preserved. Returns whether the buffer
+/// was modified.
+pub fn format_buffer(
+    buffer: &mut TextBuffer,
+    formatter: &dyn Formatter,
+    lang: Language,
+) -> io::Result<bool> {
+    let original = buffer.to_string();
+    let formatted = formatter.format(lang, &original)?;
+    if formatted == original {
+        return Ok(false);
+    }
+
+    let prefix = common_prefix_len(&original, &formatted);
+    let suffix = common_suffix_len(&original[prefix..], &formatted[prefix..]);
+    buffer.replace(
+        prefix..original.len() - suffix,
+        &formatted[prefix..formatted.len() - suffix],
+    );
+    Ok(true)
+}
+
+/// Byte length of the longest common prefix, on a char boundary.
+fn common_prefix_len(a: &str, b: &str) -> usize {
+    a.char_indices()
+        .zip(b.chars())
+        .find(|((_, x), y)| x != y)
+        .map_or(a.len().min(b.len()), |((index, _), _)| index)
+}
+
+/// Byte length of the longest common suffix, on a char boundary.
+fn common_suffix_len(a: &str, b: &str) -> usize {
+    a.chars()
+        .rev()
+        .zip(b.chars().rev())
+        .take_while(|(x, y)| x == y)
+        .map(|(ch, _)| ch.len_utf8())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shell(script: &str) -> ExternalFormatter {
+        ExternalFormatter::new("sh", ["-c", script])
+    }
+
+    #[test]
+    fn external_formatter_updates_buffer() {
+        let mut buffer = TextBuffer::from_text("fn main() {}\n");
+        let changed = format_buffer(&mut buffer, &shell("tr a-z A-Z"), Language::Rust).unwrap();
+        assert!(changed);
+        assert_eq!(buffer.to_string(), "FN MAIN() {}\n");
+
+        let changed = format_buffer(&mut buffer, &shell("cat"), Language::Rust).unwrap();
+        assert!(!changed);
+    }
+
+    #[test]
+    fn external_formatter_failures_are_errors() {
+        let mut buffer = TextBuffer::from_text("text");
+        let err = format_buffer(
+            &mut buffer,
+            &shell("echo broken >&2; exit 3"),
+            Language::PlainText,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("broken"));
+
+        let slow = shell("sleep 5").with_timeout(Duration::from_millis(100));
+        let err = format_buffer(&mut buffer, &slow, Language::PlainText).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+        assert_eq!(buffer.to_string(), "text");
+    }
+
+    #[test]
+    fn splices_only_changed_span() {
+        assert_eq!(common_prefix_len("abcdef", "abXdef"), 2);
+        assert_eq!(common_suffix_len("cdef", "Xdef"), 3);
+        assert_eq!(common_prefix_len("é1", "é2"), 2);
+        assert_eq!(common_suffix_len("aé", "bé"), 2);
+    }
+}
@@ -0,0 +1,89 @@
+/// Destination parsed from the go-to-line prompt.
+///
+/// Both values are 1-based as typed by the user and are not validated against
+/// a document; callers clamp them when jumping. A missing line means "stay on
+/// the current line", a missing column means "start of the line".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GotoTarget {
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+}
+
+/// Parses `123`, `123:45` or `:45`, ignoring surrounding whitespace.
+pub fn parse_goto(input: &str) -> Option<GotoTarget> {
+    let input = input.trim();
+    let (line, column) = match input.split_once(':') {
+        Some((line, column)) => (line.trim(), Some(column.trim())),
+        None => (input, None),
+    };
+
+    let line = if line.is_empty() {
+        None
+    } else {
+        Some(line.parse().ok()?)
+    };
+    let column = match column {
+        Some(column) => Some(column.parse().ok()?),
+        None => None,
+    };
+
+    if line.is_none() && column.is_none() {
+        return None;
+    }
+    Some(GotoTarget { line, column })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_bare_line() {
+        assert_eq!(
+            parse_goto(" 123 "),
+            Some(GotoTarget {
+                line: Some(123),
+                column: None
+            })
+        );
+    }
+
+    #[test]
+    fn parses_line_and_column() {
+        assert_eq!(
+            parse_goto("123:45"),
+            Some(GotoTarget {
+                line: Some(123),
+                column: Some(45)
+            })
+        );
+        assert_eq!(
+            parse_goto("12 : 3"),
+            Some(GotoTarget {
+                line: Some(12),
+                column: Some(3)
+            })
+        );
+    }
+
+    #[test]
+    fn parses_column_only() {
+        assert_eq!(
+            parse_goto(":45"),
+            Some(GotoTarget {
+                line: None,
+                column: Some(45)
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        assert_eq!(parse_goto(""), None);
+        assert_eq!(parse_goto(":"), None);
+        assert_eq!(parse_goto("12:"), None);
+        assert_eq!(parse_goto("abc"), None);
+        assert_eq!(parse_goto("-3"), None);
+        assert_eq!(parse_goto("1:2:3"), None);
+    }
+}
@@ -1,9 +1,25 @@
 mod app_state;
 mod command_palette;
+mod diff;
+mod format;
+mod goto;
+mod line_ops;
+mod macros;
 mod quick_commands;
+mod search;
 mod settings;
+mod whitespace;
+mod wrap;
 
 pub use app_state::AppState;
 pub use command_palette::CommandPaletteState;
+pub use diff::{DiffOp, diff};
+pub use format::{ExternalFormatter, Formatter, format_buffer};
+pub use goto::{GotoTarget, parse_goto};
+pub use line_ops::{SortOptions, duplicate_range, sort_lines, toggle_comment};
+pub use macros::{Action, Cursor, MacroRecorder};
 pub use quick_commands::{QuickCommand, QuickCommandId, list as quick_commands};
+pub use search::{FileSearchResults, SearchHit, SearchResults};
 pub use settings::{SETTINGS_CATEGORIES, SettingsCategory, SettingsState};
+pub use whitespace::fix_whitespace;
+pub use wrap::wrap_line;
@@ -0,0 +1,384 @@
+use std::cmp::Ordering;
+use std::ops::Range;
+
+use vedit_core::{Language, TextBuffer};
+
+/// Inserts a copy of `range` directly after it and returns the range of the
+/// copy.
+///
+/// An empty range duplicates the line containing it. When the range spans
+/// whole lines but stops short of the final line break (or the buffer ends
+/// without one), a line break is inserted before the copy so it lands on its
+/// own line.
+pub fn duplicate_range(buffer: &mut TextBuffer, range: Range<usize>) -> Range<usize> {
+    let contents = buffer.to_string();
+    let start = range.start.min(contents.len());
+    let end = range.end.clamp(start, contents.len());
+    let (start, end) = if start == end {
+        (line_start(&contents, start), line_end(&contents, start))
+    } else {
+        (start, end)
+    };
+
+    let whole_lines = start == line_start(&contents, start)
+        && (contents[..end].ends_with('\n') || end == line_end(&contents, end));
+    let text = &contents[start..end];
+
+    if whole_lines && !text.ends_with('\n') {
+        let line_ending = if contents[end..].starts_with("\r\n") {
+            "\r\n"
+        } else {
+            "\n"
+        };
+        buffer.insert(end, &format!("{line_ending}{text}"));
+        let copy_start = end + line_ending.len();
+        copy_start..copy_start + text.len()
+    } else {
+        buffer.insert(end, text);
+        end..end + text.len()
+    }
+}
+
+/// How [`sort_lines`] orders lines.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SortOptions {
+    /// Compare lines ignoring case.
+    pub case_insensitive: bool,
+    /// Sort in descending order.
+    pub reverse: bool,
+    /// Drop lines that compare equal to the line before them after sorting.
+    pub unique: bool,
+    /// Compare by the number each line starts with. Lines without one sort
+    /// before all numbered lines.
+    pub numeric: bool,
+}
+
+/// Sorts the whole lines overlapping `range` and writes them back with a
+/// single replace.
+///
+/// The sort is stable. Lines are rejoined with the first line ending found in
+/// the block, and a trailing line break on the block is kept.
+pub fn sort_lines(buffer: &mut TextBuffer, range: Range<usize>, opts: SortOptions) {
+    let contents = buffer.to_string();
+    let (block_start, block_end) = line_block(&contents, range);
+    let block = &contents[block_start..block_end];
+
+    let line_ending = if block.contains("\r\n") { "\r\n" } else { "\n" };
+    let trailing_break = block.ends_with('\n');
+    let mut lines: Vec<&str> = block
+        .strip_suffix('\n')
+        .unwrap_or(block)
+        .split('\n')
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .collect();
+
+    let compare = |a: &&str, b: &&str| {
+        let ordering = if opts.numeric {
+            leading_number(a)
+                .zip(leading_number(b))
+                .map(|(a, b)| a.total_cmp(&b))
+                .unwrap_or_else(|| {
+                    leading_number(a)
+                        .is_some()
+                        .cmp(&leading_number(b).is_some())
+                })
+        } else if opts.case_insensitive {
+            a.to_lowercase().cmp(&b.to_lowercase())
+        } else {
+            a.cmp(b)
+        };
+        if opts.reverse {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    };
+    lines.sort_by(compare);
+    if opts.unique {
+        lines.dedup_by(|a, b| compare(a, b) == Ordering::Equal);
+    }
+
+    let mut sorted = lines.join(line_ending);
+    if trailing_break {
+        sorted.push_str(line_ending);
+    }
+    if sorted != block {
+        buffer.replace(block_start..block_end, &sorted);
+    }
+}
+
+/// Comments out the lines overlapping `range`, or uncomments them when every
+/// non-blank line already is.
+///
+/// Line comments are inserted at the smallest indentation among the lines,
+/// so the block keeps its shape; blank lines are left alone. Languages
+/// without a line comment wrap the block in a block comment instead, and
+/// languages with neither are left unchanged.
+pub fn toggle_comment(buffer: &mut TextBuffer, range: Range<usize>, lang: Language) {
+    let contents = buffer.to_string();
+    let (block_start, block_end) = line_block(&contents, range);
+    let block = &contents[block_start..block_end];
+
+    let lines: Vec<&str> = block.split_inclusive('\n').collect();
+    let content = |line: &'_ str| -> usize {
+        let line = line.strip_suffix('\n').unwrap_or(line);
+        line.strip_suffix('\r').unwrap_or(line).len()
+    };
+    let indent = |line: &str| line.len() - line.trim_start_matches([' ', '\t']).len();
+    let non_blank: Vec<usize> = (0..lines.len())
+        .filter(|&index| !lines[index][..content(lines[index])].trim().is_empty())
+        .collect();
+    let (Some(&first), Some(&last)) = (non_blank.first(), non_blank.last()) else {
+        return;
+    };
+
+    let toggled = if let Some(token) = lang.line_comment_prefix() {
+        let commented = non_blank
+            .iter()
+            .all(|&index| lines[index][indent(lines[index])..].starts_with(token));
+        let min_indent = non_blank
+            .iter()
+            .map(|&index| indent(lines[index]))
+            .min()
+            .unwrap_or(0);
+
+        let mut toggled = String::with_capacity(block.len() + lines.len() * (token.len() + 1));
+        for (index, line) in lines.iter().enumerate() {
+            if !non_blank.contains(&index) {
+                toggled.push_str(line);
+            } else if commented {
+                let (head, rest) = line.split_at(indent(line));
+                let rest = &rest[token.len()..];
+                toggled.push_str(head);
+                toggled.push_str(rest.strip_prefix(' ').unwrap_or(rest));
+            } else {
+                let (head, rest) = line.split_at(min_indent);
+                toggled.push_str(head);
+                toggled.push_str(token);
+                toggled.push(' ');
+                toggled.push_str(rest);
+            }
+        }
+        toggled
+    } else if let Some((open, close)) = lang.block_comment() {
+        let offset = |index: usize| lines[..index].iter().map(|line| line.len()).sum::<usize>();
+        let start = offset(first) + indent(lines[first]);
+        let end = offset(last) + lines[last][..content(lines[last])].trim_end().len();
+        let inner = &block[start..end];
+
+        let body = match inner
+            .strip_prefix(open)
+            .and_then(|inner| inner.strip_suffix(close))
+        {
+            Some(body) => {
+                let body = body.strip_prefix(' ').unwrap_or(body);
+                body.strip_suffix(' ').unwrap_or(body).to_string()
+            }
+            None => format!("{open} {inner} {close}"),
+        };
+        format!("{}{body}{}", &block[..start], &block[end..])
+    } else {
+        return;
+    };
+
+    if toggled != block {
+        buffer.replace(block_start..block_end, &toggled);
+    }
+}
+
+/// Byte range of the whole lines overlapping `range`, including the last
+/// line's break.
+fn line_block(contents: &str, range: Range<usize>) -> (usize, usize) {
+    let start = range.start.min(contents.len());
+    let end = range.end.clamp(start, contents.len());
+
+    // A selection ending right after a line break does not include the next
+    // line.
+    let last = if end > start && contents[..end].ends_with('\n') {
+        end - 1
+    } else {
+        end
+    };
+    let block_end = contents[last..]
+        .find('\n')
+        .map_or(contents.len(), |index| last + index + 1);
+    (line_start(contents, start), block_end)
+}
+
+/// The number at the start of `line`, ignoring leading whitespace.
+fn leading_number(line: &str) -> Option<f64> {
+    let line = line.trim_start();
+    let sign = usize::from(line.starts_with(['-', '+']));
+    let mut end = sign;
+    let mut seen_dot = false;
+    for (index, ch) in line[sign..].char_indices() {
+        match ch {
+            '0'..='9' => end = sign + index + 1,
+            '.' if !seen_dot => seen_dot = true,
+            _ => break,
+        }
+    }
+    line[..end].parse().ok()
+}
+
+/// Byte offset of the start of the line containing `offset`.
+fn line_start(text: &str, offset: usize) -> usize {
+    text[..offset].rfind('\n').map_or(0, |index| index + 1)
+}
+
+/// Byte offset of the end of the line containing `offset`, excluding the
+/// line break.
+fn line_end(text: &str, offset: usize) -> usize {
+    let end = text[offset..]
+        .find('\n')
+        .map_or(text.len(), |index| offset + index);
+    if text[..end].ends_with('\r') {
+        end - 1
+    } else {
+        end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toggles_rust_line_comments() {
+        let source = "fn main() {\n    let a = 1;\n\n        nested();\n}\n";
+        let mut buffer = TextBuffer::from_text(source);
+        // From the middle of `let` to right after the `nested();` line.
+        let range = 18..source.find('}').unwrap();
+
+        toggle_comment(&mut buffer, range.clone(), Language::Rust);
+        assert_eq!(
+            buffer.to_string(),
+            "fn main() {\n    // let a = 1;\n\n    //     nested();\n}\n"
+        );
+
+        toggle_comment(&mut buffer, range.start..range.end + 6, Language::Rust);
+        assert_eq!(buffer.to_string(), source);
+    }
+
+    #[test]
+    fn comments_partially_commented_block() {
+        let mut buffer = TextBuffer::from_text("\t// a();\r\n\tb();\r\n");
+        let len = buffer.len();
+        toggle_comment(&mut buffer, 0..len, Language::Rust);
+        assert_eq!(buffer.to_string(), "\t// // a();\r\n\t// b();\r\n");
+    }
+
+    #[test]
+    fn falls_back_to_block_comments() {
+        let mut buffer = TextBuffer::from_text("<ul>\n  <li>one</li>\n  <li>two</li>\n</ul>\n");
+        toggle_comment(&mut buffer, 8..30, Language::Html);
+        assert_eq!(
+            buffer.to_string(),
+            "<ul>\n  <!-- <li>one</li>\n  <li>two</li> -->\n</ul>\n"
+        );
+        toggle_comment(&mut buffer, 8..30, Language::Html);
+        assert_eq!(
+            buffer.to_string(),
+            "<ul>\n  <li>one</li>\n  <li>two</li>\n</ul>\n"
+        );
+
+        let mut buffer = TextBuffer::from_text("{}\n");
+        toggle_comment(&mut buffer, 0..2, Language::Json);
+        assert_eq!(buffer.to_string(), "{}\n");
+    }
+
+    #[test]
+    fn duplicates_mid_line_selection() {
+        let mut buffer = TextBuffer::from_text("let value = 1;\n");
+        let copy = duplicate_range(&mut buffer, 4..9);
+        assert_eq!(buffer.to_string(), "let valuevalue = 1;\n");
+        assert_eq!(copy, 9..14);
+    }
+
+    #[test]
+    fn duplicates_full_line() {
+        let mut buffer = TextBuffer::from_text("first\nsecond\nthird");
+
+        // Selection including the line break.
+        let copy = duplicate_range(&mut buffer, 6..13);
+        assert_eq!(buffer.to_string(), "first\nsecond\nsecond\nthird");
+        assert_eq!(copy, 13..20);
+
+        // Selection of the line text only, on the last line.
+        let mut buffer = TextBuffer::from_text("first\nsecond\nthird");
+        let copy = duplicate_range(&mut buffer, 13..18);
+        assert_eq!(buffer.to_string(), "first\nsecond\nthird\nthird");
+        assert_eq!(&buffer.to_string()[copy], "third");
+    }
+
+    #[test]
+    fn empty_range_duplicates_current_line() {
+        let mut buffer = TextBuffer::from_text("a\r\nbc\r\n");
+        let copy = duplicate_range(&mut buffer, 4..4);
+        assert_eq!(buffer.to_string(), "a\r\nbc\r\nbc\r\n");
+        assert_eq!(copy, 7..9);
+    }
+
+    #[test]
+    fn sorts_lines_case_insensitively() {
+        let mut buffer = TextBuffer::from_text("header\r\nbeta\r\nAlpha\r\ngamma\r\n");
+        let options = SortOptions {
+            case_insensitive: true,
+            ..SortOptions::default()
+        };
+        // Starts mid-line and ends right after a line break.
+        sort_lines(&mut buffer, 10..27, options);
+        assert_eq!(buffer.to_string(), "header\r\nAlpha\r\nbeta\r\ngamma\r\n");
+
+        let mut buffer = TextBuffer::from_text("b\nA\nc");
+        sort_lines(
+            &mut buffer,
+            0..5,
+            SortOptions {
+                reverse: true,
+                ..SortOptions::default()
+            },
+        );
+        assert_eq!(buffer.to_string(), "c\nb\nA");
+    }
+
+    #[test]
+    fn sorts_lines_numerically() {
+        let mut buffer = TextBuffer::from_text("10 ten\n9 nine\n-1.5\nnone\n100\n");
+        let options = SortOptions {
+            numeric: true,
+            ..SortOptions::default()
+        };
+        let len = buffer.len();
+        sort_lines(&mut buffer, 0..len, options);
+        assert_eq!(buffer.to_string(), "none\n-1.5\n9 nine\n10 ten\n100\n");
+    }
+
+    #[test]
+    fn removes_duplicate_lines() {
+        let mut buffer = TextBuffer::from_text("b\na\nB\nb\na");
+        let len = buffer.len();
+        sort_lines(
+            &mut buffer,
+            0..len,
+            SortOptions {
+                unique: true,
+                ..SortOptions::default()
+            },
+        );
+        assert_eq!(buffer.to_string(), "B\na\nb");
+
+        let mut buffer = TextBuffer::from_text("b\na\nB\nb\na");
+        let len = buffer.len();
+        sort_lines(
+            &mut buffer,
+            0..len,
+            SortOptions {
+                unique: true,
+                case_insensitive: true,
+                ..SortOptions::default()
+            },
+        );
+        assert_eq!(buffer.to_string(), "a\nb");
+    }
+}
@@ -0,0 +1,219 @@
+use vedit_core::TextBuffer;
+
+/// Caret position in a buffer, as a byte offset on a char boundary.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Cursor {
+    pub offset: usize,
+}
+
+impl Cursor {
+    pub fn new(offset: usize) -> Self {
+        Self { offset }
+    }
+}
+
+/// An editor action a macro can replay.
+///
+/// Edits are the primitive buffer operations an action resolved to (the text
+/// actually inserted, the number of chars actually removed) rather than the
+/// keystrokes that caused them, so playback does not depend on auto-indent,
+/// auto-closing brackets or other editor state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    /// Insert text at the cursor and move past it.
+    Insert(String),
+    /// Remove up to this many chars before the cursor.
+    DeleteBackward(usize),
+    /// Remove up to this many chars after the cursor.
+    DeleteForward(usize),
+    /// Move the cursor this many chars left, crossing line breaks.
+    MoveLeft(usize),
+    /// Move the cursor this many chars right, crossing line breaks.
+    MoveRight(usize),
+    /// Move the cursor up a line, keeping its column where possible.
+    MoveUp,
+    /// Move the cursor down a line, keeping its column where possible.
+    MoveDown,
+    MoveLineStart,
+    MoveLineEnd,
+}
+
+impl Action {
+    /// Apply the action to `buffer` at `cursor`.
+    pub fn apply(&self, buffer: &mut TextBuffer, cursor: &mut Cursor) {
+        cursor.offset = cursor.offset.min(buffer.len());
+        match self {
+            Action::Insert(text) => {
+                buffer.insert(cursor.offset, text);
+                cursor.offset += text.len();
+            }
+            Action::DeleteBackward(count) => {
+                let start = (0..*count).fold(cursor.offset, |offset, _| left_of(buffer, offset));
+                buffer.delete(start..cursor.offset);
+                cursor.offset = start;
+            }
+            Action::DeleteForward(count) => {
+                let end = (0..*count).fold(cursor.offset, |offset, _| right_of(buffer, offset));
+                buffer.delete(cursor.offset..end);
+            }
+            Action::MoveLeft(count) => {
+                cursor.offset =
+                    (0..*count).fold(cursor.offset, |offset, _| left_of(buffer, offset));
+            }
+            Action::MoveRight(count) => {
+                cursor.offset =
+                    (0..*count).fold(cursor.offset, |offset, _| right_of(buffer, offset));
+            }
+            Action::MoveUp => {
+                let (line, column) = buffer.position_at(cursor.offset);
+                if line > 0 {
+                    cursor.offset = buffer.offset_at(line - 1, column).unwrap_or(cursor.offset);
+                }
+            }
+            Action::MoveDown => {
+                let (line, column) = buffer.position_at(cursor.offset);
+                if let Some(offset) = buffer.offset_at(line + 1, column) {
+                    cursor.offset = offset;
+                }
+            }
+            Action::MoveLineStart => {
+                let (line, _) = buffer.position_at(cursor.offset);
+                cursor.offset = buffer.offset_at(line, 0).unwrap_or(cursor.offset);
+            }
+            Action::MoveLineEnd => {
+                let (line, _) = buffer.position_at(cursor.offset);
+                cursor.offset = buffer.offset_at(line, usize::MAX).unwrap_or(cursor.offset);
+            }
+        }
+    }
+}
+
+/// Offset of the char before `offset`, or `offset` at the buffer start.
+fn left_of(buffer: &TextBuffer, offset: usize) -> usize {
+    let (line, column) = buffer.position_at(offset);
+    match (line, column) {
+        (0, 0) => 0,
+        (line, 0) => buffer.offset_at(line - 1, usize::MAX).unwrap_or(offset),
+        (line, column) => buffer.offset_at(line, column - 1).unwrap_or(offset),
+    }
+}
+
+/// Offset of the char after `offset`, or `offset` at the buffer end.
+fn right_of(buffer: &TextBuffer, offset: usize) -> usize {
+    let (line, column) = buffer.position_at(offset);
+    match buffer.offset_at(line, column + 1) {
+        Some(next) if next != offset => next,
+        // At the end of a line: step over its line break.
+        _ => buffer.offset_at(line + 1, 0).unwrap_or(offset),
+    }
+}
+
+/// Records editor actions into a keyboard macro and replays them.
+#[derive(Debug, Clone, Default)]
+pub struct MacroRecorder {
+    actions: Vec<Action>,
+    recording: bool,
+}
+
+impl MacroRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start a new recording, discarding the previous macro.
+    pub fn start(&mut self) {
+        self.actions.clear();
+        self.recording = true;
+    }
+
+    pub fn stop(&mut self) {
+        self.recording = false;
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    /// Append `action` to the macro while recording; otherwise a no-op.
+    /// Consecutive insertions are merged.
+    pub fn record(&mut self, action: Action) {
+        if !self.recording {
+            return;
+        }
+        match (self.actions.last_mut(), action) {
+            (Some(Action::Insert(previous)), Action::Insert(text)) => previous.push_str(&text),
+            (_, action) => self.actions.push(action),
+        }
+    }
+
+    pub fn actions(&self) -> &[Action] {
+        &self.actions
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+
+    /// Replay the recorded actions at `cursor`. The edits of one playback
+    /// form a single undo group.
+    pub fn play(&self, buffer: &mut TextBuffer, cursor: &mut Cursor) {
+        buffer.commit_group();
+        for action in &self.actions {
+            action.apply(buffer, cursor);
+        }
+        buffer.commit_group();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replays_recorded_actions() {
+        let mut buffer = TextBuffer::from_text("abc\nd");
+        let mut cursor = Cursor::new(0);
+        let mut recorder = MacroRecorder::new();
+        recorder.start();
+        for action in [Action::Insert("X".to_string()), Action::MoveRight(1)] {
+            action.apply(&mut buffer, &mut cursor);
+            recorder.record(action);
+        }
+        recorder.stop();
+        recorder.record(Action::MoveLineEnd);
+        assert_eq!(recorder.actions().len(), 2);
+        assert_eq!(buffer.to_string(), "Xabc\nd");
+        assert_eq!(cursor.offset, 2);
+
+        recorder.play(&mut buffer, &mut cursor);
+        recorder.play(&mut buffer, &mut cursor);
+        assert_eq!(buffer.to_string(), "XaXbXc\nd");
+        assert_eq!(cursor.offset, 6);
+
+        // Moves cross line breaks.
+        recorder.play(&mut buffer, &mut cursor);
+        assert_eq!(buffer.to_string(), "XaXbXcX\nd");
+        assert_eq!(cursor.offset, 8);
+    }
+
+    #[test]
+    fn applies_deletes_and_vertical_moves() {
+        let mut buffer = TextBuffer::from_text("héllo\nab\nworld");
+        let mut cursor = Cursor::new(3);
+        Action::DeleteBackward(1).apply(&mut buffer, &mut cursor);
+        assert_eq!(buffer.to_string(), "hllo\nab\nworld");
+        assert_eq!(cursor.offset, 1);
+
+        Action::MoveDown.apply(&mut buffer, &mut cursor);
+        Action::MoveDown.apply(&mut buffer, &mut cursor);
+        assert_eq!(cursor.offset, 9);
+        Action::DeleteForward(10).apply(&mut buffer, &mut cursor);
+        assert_eq!(buffer.to_string(), "hllo\nab\nw");
+
+        Action::MoveUp.apply(&mut buffer, &mut cursor);
+        Action::MoveLineEnd.apply(&mut buffer, &mut cursor);
+        assert_eq!(cursor.offset, 7);
+        Action::MoveLeft(3).apply(&mut buffer, &mut cursor);
+        assert_eq!(cursor.offset, 4);
+    }
+}
@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// A single match found by a project search.
+///
+/// `line` and `column` are 0-based; `column` is a byte offset into the line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchHit {
+    pub path: PathBuf,
+    pub line: usize,
+    pub column: usize,
+    /// The matched line, for display in the results list.
+    pub line_text: String,
+}
+
+/// The hits of one file, in the order they were pushed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileSearchResults {
+    pub path: PathBuf,
+    pub hits: Vec<SearchHit>,
+}
+
+impl FileSearchResults {
+    pub fn match_count(&self) -> usize {
+        self.hits.len()
+    }
+}
+
+/// Search hits grouped by file.
+///
+/// Hits can be pushed as they stream in; files keep the order in which their
+/// first hit arrived so the list does not reshuffle while a search runs.
+#[derive(Debug, Clone, Default)]
+pub struct SearchResults {
+    files: Vec<FileSearchResults>,
+    index: HashMap<PathBuf, usize>,
+    total: usize,
+}
+
+impl SearchResults {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, hit: SearchHit) {
+        let slot = match self.index.get(&hit.path) {
+            Some(&slot) => slot,
+            None => {
+                self.index.insert(hit.path.clone(), self.files.len());
+                self.files.push(FileSearchResults {
+                    path: hit.path.clone(),
+                    hits: Vec::new(),
+                });
+                self.files.len() - 1
+            }
+        };
+        self.files[slot].hits.push(hit);
+        self.total += 1;
+    }
+
+    pub fn clear(&mut self) {
+        self.files.clear();
+        self.index.clear();
+        self.total = 0;
+    }
+
+    pub fn files(&self) -> &[FileSearchResults] {
+        &self.files
+    }
+
+    pub fn file(&self, path: &Path) -> Option<&FileSearchResults> {
+        self.index.get(path).map(|&slot| &self.files[slot])
+    }
+
+    /// All hits, file by file.
+    pub fn iter(&self) -> impl Iterator<Item = &SearchHit> + '_ {
+        self.files.iter().flat_map(|file| file.hits.iter())
+    }
+
+    pub fn total_matches(&self) -> usize {
+        self.total
+    }
+
+    pub fn file_count(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total == 0
+    }
+}
+
+impl Extend<SearchHit> for SearchResults {
+    fn extend<I: IntoIterator<Item = SearchHit>>(&mut self, hits: I) {
+        for hit in hits {
+            self.push(hit);
+        }
+    }
+}
+
+impl FromIterator<SearchHit> for SearchResults {
+    fn from_iter<I: IntoIterator<Item = SearchHit>>(hits: I) -> Self {
+        let mut results = Self::new();
+        results.extend(hits);
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hit(path: &str, line: usize) -> SearchHit {
+        SearchHit {
+            path: PathBuf::from(path),
+            line,
+            column: 0,
+            line_text: format!("line {line}"),
+        }
+    }
+
+    #[test]
+    fn groups_hits_by_file() {
+        let mut results = SearchResults::new();
+        assert!(results.is_empty());
+
+        results.push(hit("src/main.rs", 3));
+        results.push(hit("src/lib.rs", 1));
+        results.extend([hit("src/main.rs", 7), hit("src/main.rs", 9)]);
+
+        assert_eq!(results.total_matches(), 4);
+        assert_eq!(results.file_count(), 2);
+        let files: Vec<_> = results
+            .files()
+            .iter()
+            .map(|file| (file.path.to_str().unwrap(), file.match_count()))
+            .collect();
+        assert_eq!(files, vec![("src/main.rs", 3), ("src/lib.rs", 1)]);
+        assert_eq!(
+            results.file(Path::new("src/lib.rs")).unwrap().hits,
+            vec![hit("src/lib.rs", 1)]
+        );
+
+        let lines: Vec<_> = results.iter().map(|hit| hit.line).collect();
+        assert_eq!(lines, vec![3, 7, 9, 1]);
+
+        results.clear();
+        assert_eq!(results.file_count(), 0);
+        assert!(results.iter().next().is_none());
+    }
+}
@@ -0,0 +1,110 @@
+use vedit_core::TextBuffer;
+
+/// Strips trailing whitespace and normalizes the end of the file.
+///
+/// When `strip_trailing` is set, spaces and tabs preceding each line break (and
+/// at the very end of the buffer) are removed. When `ensure_final_newline` is
+/// set, the buffer is made to end with exactly one line break, reusing the
+/// document's line ending style; trailing blank lines are collapsed into it.
+///
+/// The fixer works purely on lines and has no knowledge of the language, so
+/// whitespace that is significant inside multiline string literals (or
+/// heredocs, markdown hard breaks, ...) is stripped as well.
+pub fn fix_whitespace(buffer: &mut TextBuffer, strip_trailing: bool, ensure_final_newline: bool) {
+    if !strip_trailing && !ensure_final_newline {
+        return;
+    }
+
+    let contents = buffer.to_string();
+    let mut edits: Vec<(usize, usize, &str)> = Vec::new();
+
+    if strip_trailing {
+        let mut offset = 0usize;
+        for line in contents.split_inclusive('\n') {
+            let body = line.trim_end_matches(['\n', '\r']);
+            let stripped = body.trim_end_matches([' ', '\t']);
+            if stripped.len() < body.len() {
+                edits.push((offset + stripped.len(), offset + body.len(), ""));
+            }
+            offset += line.len();
+        }
+    }
+
+    if ensure_final_newline && !contents.is_empty() {
+        let line_ending = if contents.contains("\r\n") {
+            "\r\n"
+        } else {
+            "\n"
+        };
+        let tail_start = contents
+            .trim_end_matches(|ch: char| {
+                ch == '\n' || ch == '\r' || (strip_trailing && (ch == ' ' || ch == '\t'))
+            })
+            .len();
+
+        // Whitespace on the trailing lines is replaced by the tail edit below.
+        edits.retain(|(start, _, _)| *start < tail_start);
+
+        let replacement = if tail_start == 0 { "" } else { line_ending };
+        if contents[tail_start..] != *replacement {
+            edits.push((tail_start, contents.len(), replacement));
+        }
+    }
+
+    // Apply back to front so earlier offsets stay valid.
+    for (start, end, text) in edits.into_iter().rev() {
+        buffer.replace(start..end, text);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_trailing_spaces_and_tabs() {
+        let mut buffer = TextBuffer::from_text("fn main() {  \n\tlet x = 1;\t \n}\n");
+        fix_whitespace(&mut buffer, true, false);
+        assert_eq!(buffer.to_string(), "fn main() {\n\tlet x = 1;\n}\n");
+    }
+
+    #[test]
+    fn strips_before_crlf() {
+        let mut buffer = TextBuffer::from_text("a \r\nb\t\r\n");
+        fix_whitespace(&mut buffer, true, false);
+        assert_eq!(buffer.to_string(), "a\r\nb\r\n");
+    }
+
+    #[test]
+    fn inserts_missing_final_newline() {
+        let mut buffer = TextBuffer::from_text("line one\nline two");
+        fix_whitespace(&mut buffer, false, true);
+        assert_eq!(buffer.to_string(), "line one\nline two\n");
+
+        let mut crlf = TextBuffer::from_text("a\r\nb");
+        fix_whitespace(&mut crlf, false, true);
+        assert_eq!(crlf.to_string(), "a\r\nb\r\n");
+    }
+
+    #[test]
+    fn collapses_trailing_blank_lines() {
+        let mut buffer = TextBuffer::from_text("content\n\n\n");
+        fix_whitespace(&mut buffer, false, true);
+        assert_eq!(buffer.to_string(), "content\n");
+
+        let mut with_spaces = TextBuffer::from_text("content  \n   \n\t\n");
+        fix_whitespace(&mut with_spaces, true, true);
+        assert_eq!(with_spaces.to_string(), "content\n");
+    }
+
+    #[test]
+    fn leaves_clean_buffers_untouched() {
+        let mut buffer = TextBuffer::from_text("clean\n");
+        fix_whitespace(&mut buffer, true, true);
+        assert_eq!(buffer.to_string(), "clean\n");
+
+        let mut empty = TextBuffer::new();
+        fix_whitespace(&mut empty, true, true);
+        assert!(empty.is_empty());
+    }
+}
@@ -0,0 +1,101 @@
+use std::ops::Range;
+
+/// Splits a single line into the byte ranges of its visual rows when soft
+/// wrapping at `width` columns.
+///
+/// Rows break after runs of spaces or tabs when possible; a token longer than
+/// the available width is hard-broken at the column limit. Whitespace is
+/// allowed to hang past the limit so that rows never start with the gap that
+/// caused the wrap. Tabs expand to the next multiple of `tab_width`, measured
+/// from the start of the visual row, and every other `char` counts as one
+/// column. A `width` of zero disables wrapping.
+pub fn wrap_line(text: &str, width: usize, tab_width: usize) -> Vec<Range<usize>> {
+    let mut rows = Vec::new();
+    if width == 0 || text.is_empty() {
+        rows.push(0..text.len());
+        return rows;
+    }
+
+    let tab_width = tab_width.max(1);
+    let mut row_start = 0usize;
+    let mut column = 0usize;
+    let mut break_at: Option<usize> = None;
+
+    for (index, ch) in text.char_indices() {
+        if ch == ' ' || ch == '\t' {
+            column += char_columns(ch, column, tab_width);
+            break_at = Some(index + ch.len_utf8());
+            continue;
+        }
+
+        if column + 1 > width && index > row_start {
+            let split = match break_at {
+                Some(split) if split > row_start => split,
+                _ => index,
+            };
+            rows.push(row_start..split);
+            row_start = split;
+            break_at = None;
+            column = text[row_start..index]
+                .chars()
+                .fold(0, |column, ch| column + char_columns(ch, column, tab_width));
+        }
+        column += 1;
+    }
+
+    rows.push(row_start..text.len());
+    rows
+}
+
+fn char_columns(ch: char, column: usize, tab_width: usize) -> usize {
+    if ch == '\t' {
+        tab_width - column % tab_width
+    } else {
+        1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows<'a>(text: &'a str, ranges: &[Range<usize>]) -> Vec<&'a str> {
+        ranges.iter().map(|range| &text[range.clone()]).collect()
+    }
+
+    #[test]
+    fn wraps_at_word_boundaries() {
+        let text = "the quick brown fox jumps";
+        let ranges = wrap_line(text, 10, 4);
+        assert_eq!(
+            rows(text, &ranges),
+            vec!["the quick ", "brown fox ", "jumps"]
+        );
+    }
+
+    #[test]
+    fn hard_breaks_overlong_token() {
+        let text = "abcdefghijklmnopq";
+        let ranges = wrap_line(text, 5, 4);
+        assert_eq!(rows(text, &ranges), vec!["abcde", "fghij", "klmno", "pq"]);
+
+        let text = "ok abcdefghijkl end";
+        let ranges = wrap_line(text, 6, 4);
+        assert_eq!(rows(text, &ranges), vec!["ok ", "abcdef", "ghijkl ", "end"]);
+    }
+
+    #[test]
+    fn expands_tabs() {
+        let text = "\tab\tcd ef";
+        let ranges = wrap_line(text, 8, 4);
+        assert_eq!(rows(text, &ranges), vec!["\tab\t", "cd ef"]);
+    }
+
+    #[test]
+    fn short_or_unbounded_lines_stay_whole() {
+        assert_eq!(rows("", &wrap_line("", 10, 4)), vec![""]);
+        assert_eq!(rows("fits", &wrap_line("fits", 10, 4)), vec!["fits"]);
+        let text = "no limit at all";
+        assert_eq!(rows(text, &wrap_line(text, 0, 4)), vec![text]);
+    }
+}
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
+use std::env;
 use std::fmt;
 use std::fs;
 use std::io;
@@ -48,6 +49,14 @@ impl DebugTargetRecord {
     fn is_valid(&self) -> bool {
         !self.name.trim().is_empty() && !self.executable.trim().is_empty()
     }
+
+    /// The record with environment references in the executable expanded.
+    fn expanded(&self) -> Self {
+        Self {
+            name: self.name.clone(),
+            executable: expand_vars(&self.executable),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -105,20 +114,29 @@ impl WorkspaceConfig {
         Ok(())
     }
 
-    pub fn ignored_directories(&self) -> impl Iterator<Item = &str> {
-        self.ignored_directories.iter().map(|entry| entry.as_str())
+    /// Path-like entries are stored as written and expanded by the
+    /// accessors below, so `$VAR`, `${VAR}` and `%VAR%` references survive a
+    /// load and save. Variables that are not set are left as written.
+    pub fn ignored_directories(&self) -> impl Iterator<Item = String> + '_ {
+        self.ignored_directories
+            .iter()
+            .map(|entry| expand_vars(entry))
     }
 
-    pub fn recent_files(&self) -> impl Iterator<Item = &str> {
-        self.recent_files.iter().map(|entry| entry.as_str())
+    pub fn recent_files(&self) -> impl Iterator<Item = String> + '_ {
+        self.recent_files.iter().map(|entry| expand_vars(entry))
     }
 
-    pub fn recent_debug_targets(&self) -> impl Iterator<Item = &DebugTargetRecord> {
-        self.recent_debug_targets.iter()
+    pub fn recent_debug_targets(&self) -> impl Iterator<Item = DebugTargetRecord> + '_ {
+        self.recent_debug_targets
+            .iter()
+            .map(DebugTargetRecord::expanded)
     }
 
-    pub fn last_debug_target(&self) -> Option<&DebugTargetRecord> {
-        self.last_debug_target.as_ref()
+    pub fn last_debug_target(&self) -> Option<DebugTargetRecord> {
+        self.last_debug_target
+            .as_ref()
+            .map(DebugTargetRecord::expanded)
     }
 
     pub fn record_recent_file(&mut self, file: impl AsRef<Path>) -> bool {
@@ -131,14 +149,18 @@ impl WorkspaceConfig {
             return false;
         }
 
-        if let Some(pos) = self.recent_files.iter().position(|entry| entry == &display) {
-            if pos == 0 {
-                return false;
-            }
-            self.recent_files.remove(pos);
-        }
+        // An existing entry is kept as written, references included.
+        let entry = match self
+            .recent_files
+            .iter()
+            .position(|entry| expand_vars(entry) == display)
+        {
+            Some(0) => return false,
+            Some(pos) => self.recent_files.remove(pos).unwrap_or(display),
+            None => display,
+        };
 
-        self.recent_files.push_front(display);
+        self.recent_files.push_front(entry);
         while self.recent_files.len() > MAX_RECENT_FILES {
             self.recent_files.pop_back();
         }
@@ -146,9 +168,18 @@ impl WorkspaceConfig {
     }
 
     pub fn record_debug_target(&mut self, name: &str, executable: impl AsRef<Path>) -> bool {
-        let Some(record) = DebugTargetRecord::normalized(name, executable.as_ref()) else {
+        let Some(mut record) = DebugTargetRecord::normalized(name, executable.as_ref()) else {
             return false;
         };
+        // An existing record is kept as written, references included.
+        if let Some(existing) = self
+            .recent_debug_targets
+            .iter()
+            .chain(&self.last_debug_target)
+            .find(|entry| entry.expanded() == record)
+        {
+            record = existing.clone();
+        }
 
         let mut changed = false;
 
@@ -178,6 +209,27 @@ impl WorkspaceConfig {
         changed
     }
 
+    /// Drops recent files and debug targets whose paths no longer exist,
+    /// resolving relative entries against `root`. Returns how many entries
+    /// were removed.
+    pub fn prune_missing(&mut self, root: &Path) -> usize {
+        let exists = |entry: &str| root.join(expand_vars(entry)).exists();
+        let before = self.recent_files.len() + self.recent_debug_targets.len();
+
+        self.recent_files.retain(|entry| exists(entry));
+        self.recent_debug_targets
+            .retain(|target| exists(&target.executable));
+        if self
+            .last_debug_target
+            .as_ref()
+            .is_some_and(|last| !exists(&last.executable))
+        {
+            self.last_debug_target = None;
+        }
+
+        before - (self.recent_files.len() + self.recent_debug_targets.len())
+    }
+
     fn normalize(&mut self) {
         self.ignored_directories
             .iter_mut()
@@ -253,14 +305,27 @@ pub struct ConsoleWorkspaceState {
     pub active_shell: Option<usize>,
 }
 
+/// Window and panel layout restored when the workspace is reopened.
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
-pub struct WorkspaceMetadata {
+pub struct LayoutState {
+    /// Window geometry as `(width, height, x, y)`.
     #[serde(default)]
-    pub sticky_notes: Vec<StickyNoteRecord>,
+    pub window: Option<(u32, u32, i32, i32)>,
+    #[serde(default)]
+    pub sidebar_width: Option<u32>,
     #[serde(default)]
     pub console: ConsoleWorkspaceState,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct WorkspaceMetadata {
+    #[serde(default)]
+    pub sticky_notes: Vec<StickyNoteRecord>,
+    /// Stored at the top level of the metadata file, next to the notes.
+    #[serde(flatten)]
+    pub layout: LayoutState,
+}
+
 impl WorkspaceMetadata {
     pub fn load(root: impl AsRef<Path>) -> Result<Self, WorkspaceMetadataError> {
         let path = metadata_path(root);
@@ -289,6 +354,19 @@ impl WorkspaceMetadata {
         Ok(())
     }
 
+    pub fn layout(&self) -> &LayoutState {
+        &self.layout
+    }
+
+    /// Replace the stored layout, returning whether it changed.
+    pub fn set_layout(&mut self, layout: LayoutState) -> bool {
+        if self.layout == layout {
+            return false;
+        }
+        self.layout = layout;
+        true
+    }
+
     pub fn notes_for_file(&self, file: &str) -> Vec<StickyNoteRecord> {
         self.sticky_notes
             .iter()
@@ -363,6 +441,53 @@ fn metadata_path(root: impl AsRef<Path>) -> PathBuf {
         .join(WORKSPACE_METADATA_FILE)
 }
 
+/// Expands environment variable references in `value` from the process
+/// environment. See [`expand_env`].
+fn expand_vars(value: &str) -> String {
+    expand_env(value, |name| env::var(name).ok())
+}
+
+/// Replaces `$VAR`, `${VAR}` and `%VAR%` references in `value` with the
+/// values `lookup` returns, keeping unresolved references verbatim.
+fn expand_env(value: &str, lookup: impl Fn(&str) -> Option<String>) -> String {
+    let is_name = |name: &str| {
+        !name.is_empty()
+            && !name.starts_with(|c: char| c.is_ascii_digit())
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    };
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find(['$', '%']) {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let reference = if rest[start..].starts_with('%') {
+            after.find('%').map(|end| (&after[..end], end + 2))
+        } else if let Some(braced) = after.strip_prefix('{') {
+            braced.find('}').map(|end| (&braced[..end], end + 3))
+        } else {
+            let end = after
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(after.len());
+            Some((&after[..end], end + 1))
+        };
+        match reference.filter(|(name, _)| is_name(name)) {
+            Some((name, len)) => {
+                match lookup(name) {
+                    Some(expanded) => result.push_str(&expanded),
+                    None => result.push_str(&rest[start..start + len]),
+                }
+                rest = &rest[start + len..];
+            }
+            None => {
+                result.push_str(&rest[start..start + 1]);
+                rest = after;
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
 fn normalize_path(path: &Path) -> String {
     let display = path.to_string_lossy().to_string();
     if cfg!(windows) {
@@ -416,6 +541,59 @@ mod tests {
         assert_eq!(config.last_debug_target().unwrap().name, "tool3");
     }
 
+    #[test]
+    fn expands_environment_references() {
+        let lookup = |name: &str| match name {
+            "HOME" => Some("/home/dev".to_string()),
+            "USERPROFILE" => Some("C:/Users/dev".to_string()),
+            _ => None,
+        };
+        assert_eq!(expand_env("$HOME/bin/app", lookup), "/home/dev/bin/app");
+        assert_eq!(expand_env("${HOME}x", lookup), "/home/devx");
+        assert_eq!(
+            expand_env("%USERPROFILE%/tools", lookup),
+            "C:/Users/dev/tools"
+        );
+        assert_eq!(expand_env("$MISSING/%NOPE%/a", lookup), "$MISSING/%NOPE%/a");
+        assert_eq!(expand_env("50% of $5 $", lookup), "50% of $5 $");
+    }
+
+    #[test]
+    fn keeps_environment_references_on_save() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join(WORKSPACE_DIR)).unwrap();
+        fs::write(
+            config_path(dir.path()),
+            r#"ignored_directories = ["%UNSET_VEDIT_VAR%/cache"]
+recent_files = ["$HOME/notes.txt"]
+
+[last_debug_target]
+name = "app"
+executable = "${HOME}/bin/app"
+"#,
+        )
+        .unwrap();
+
+        let mut config = WorkspaceConfig::load(dir.path()).unwrap();
+        let executable = expand_vars("${HOME}/bin/app");
+        assert_eq!(config.last_debug_target().unwrap().executable, executable);
+        assert_eq!(
+            config.ignored_directories().collect::<Vec<_>>(),
+            vec!["%UNSET_VEDIT_VAR%/cache"]
+        );
+
+        // Recording the expanded path reuses the entry written with references.
+        assert!(!config.record_debug_target("app", &executable));
+        assert!(!config.record_recent_file(expand_vars("$HOME/notes.txt")));
+
+        config.save(dir.path()).unwrap();
+        let saved = fs::read_to_string(config_path(dir.path())).unwrap();
+        assert!(saved.contains("${HOME}/bin/app"), "{saved}");
+        assert!(saved.contains("$HOME/notes.txt"), "{saved}");
+        assert!(saved.contains("%UNSET_VEDIT_VAR%/cache"), "{saved}");
+        assert_eq!(WorkspaceConfig::load(dir.path()).unwrap(), config);
+    }
+
     #[test]
     fn load_and_save_round_trip() {
         let dir = tempdir().unwrap();
@@ -435,6 +613,37 @@ mod tests {
         fs::remove_dir_all(dir).ok();
     }
 
+    #[test]
+    fn prune_missing_removes_dead_entries() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/main.rs"), "").unwrap();
+        fs::write(root.join("src/old.rs"), "").unwrap();
+        fs::write(root.join("tool"), "").unwrap();
+        fs::write(root.join("gone"), "").unwrap();
+
+        let mut config = WorkspaceConfig::default();
+        config.record_recent_file("src/main.rs");
+        config.record_recent_file("src/old.rs");
+        config.record_debug_target("tool", root.join("tool"));
+        config.record_debug_target("gone", root.join("gone"));
+
+        fs::remove_file(root.join("src/old.rs")).unwrap();
+        fs::remove_file(root.join("gone")).unwrap();
+
+        assert_eq!(config.prune_missing(root), 2);
+        assert_eq!(
+            config.recent_files().collect::<Vec<_>>(),
+            vec!["src/main.rs"]
+        );
+        let targets: Vec<_> = config.recent_debug_targets().map(|t| t.name).collect();
+        assert_eq!(targets, vec!["tool"]);
+        assert!(config.last_debug_target().is_none());
+
+        assert_eq!(config.prune_missing(root), 0);
+    }
+
     #[test]
     fn workspace_metadata_round_trip() {
         let dir = tempdir().unwrap();
@@ -450,4 +659,37 @@ mod tests {
 
         fs::remove_dir_all(dir).ok();
     }
+
+    #[test]
+    fn workspace_layout_round_trip() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+
+        let mut metadata = WorkspaceMetadata::default();
+        let layout = LayoutState {
+            window: Some((1600, 900, -8, 24)),
+            sidebar_width: Some(280),
+            console: ConsoleWorkspaceState {
+                visible: true,
+                shell_tabs: 2,
+                active_shell: Some(1),
+            },
+        };
+        assert!(metadata.set_layout(layout.clone()));
+        assert!(!metadata.set_layout(layout.clone()));
+        metadata.save(root).unwrap();
+
+        let loaded = WorkspaceMetadata::load(root).unwrap();
+        assert_eq!(loaded.layout(), &layout);
+
+        // Files written before the layout existed keep their console state.
+        fs::write(
+            metadata_path(root),
+            r#"{"sticky_notes": [], "console": {"visible": true, "shell_tabs": 1}}"#,
+        )
+        .unwrap();
+        let legacy = WorkspaceMetadata::load(root).unwrap();
+        assert!(legacy.layout().console.visible);
+        assert_eq!(legacy.layout().window, None);
+    }
 }
@@ -11,13 +11,14 @@ pub use vedit_config::StickyNote;
 // Re-export from new focused crates
 pub use vedit_config::{DebugTargetRecord, WorkspaceConfig};
 pub use vedit_keybinds::{
-    Key, KeyCombination, KeyEvent, Keymap, KeymapError, QUICK_COMMAND_MENU_ACTION, SAVE_ACTION,
+    ChordMatch, Key, KeyChord, KeyCombination, KeyEvent, Keymap, KeymapError, LayeredKeymap,
+    QUICK_COMMAND_MENU_ACTION, SAVE_ACTION,
 };
 pub use vedit_syntax::Language;
-pub use vedit_text::TextBuffer;
+pub use vedit_text::{BufferSnapshot, EditError, TextBuffer, TextStats};
 pub use vedit_workspace::{
-    DirEntryMeta, FileMeta, FilterState, FsWorkspaceProvider, GitStatus, Node, NodeId, NodeKind,
-    WorkspaceProvider, WorkspaceTree,
+    DirEntryMeta, FileMeta, FilterState, FsWorkspaceProvider, GitIgnore, GitStatus,
+    GitStatusProvider, Node, NodeId, NodeKind, WorkspaceProvider, WorkspaceTree,
 };
 
 // Re-export document types from vedit-document
@@ -1,4 +1,4 @@
-use crossbeam_channel::{Receiver, Sender, unbounded};
+use crossbeam_channel::{Receiver, Sender, bounded, never, select, unbounded};
 use iced_x86::{Decoder, DecoderOptions, Formatter, Instruction, NasmFormatter};
 use nix::sys::ptrace;
 use nix::sys::signal::{Signal, kill};
@@ -31,6 +31,74 @@ pub struct Breakpoint {
     pub address: u64,
     pub original_byte: u8,
     pub enabled: bool,
+    /// Only stop when this holds; the target is continued silently otherwise.
+    pub condition: Option<Condition>,
+}
+
+/// General purpose registers a [`Condition`] can inspect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Register {
+    Rax,
+    Rbx,
+    Rcx,
+    Rdx,
+    Rsi,
+    Rdi,
+    Rbp,
+    Rsp,
+    R8,
+    R9,
+    R10,
+    R11,
+    R12,
+    R13,
+    R14,
+    R15,
+    Rip,
+}
+
+/// The value a [`Condition`] compares.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operand {
+    Register(Register),
+    /// `size` bytes (1 to 8, little endian) at `address`.
+    Memory {
+        address: u64,
+        size: u8,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+/// Compares a register or memory value against a constant (unsigned).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Condition {
+    pub operand: Operand,
+    pub comparison: Comparison,
+    pub value: u64,
+}
+
+impl Condition {
+    /// Evaluates the condition, fetching the operand through `read`.
+    pub fn evaluate<E>(&self, read: impl FnOnce(Operand) -> Result<u64, E>) -> Result<bool, E> {
+        let actual = read(self.operand)?;
+        Ok(match self.comparison {
+            Comparison::Equal => actual == self.value,
+            Comparison::NotEqual => actual != self.value,
+            Comparison::Less => actual < self.value,
+            Comparison::LessOrEqual => actual <= self.value,
+            Comparison::Greater => actual > self.value,
+            Comparison::GreaterOrEqual => actual >= self.value,
+        })
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -46,23 +114,43 @@ pub enum DebuggerCommand {
     Continue,
     Step,
     Kill,
-    ReadMemory(u64, usize),  // address, size
+    ReadMemory(u64, usize), // address, size
+    /// Read the NUL-terminated string at `address`, up to `max_len` bytes.
+    ReadCString {
+        address: u64,
+        max_len: usize,
+    },
     Disassemble(u64, usize), // address, instruction count
     AddBreakpoint(u64),      // address
-    RemoveBreakpoint(u64),   // address
+    /// Add a breakpoint, or change the condition of an existing one.
+    AddConditionalBreakpoint(u64, Condition),
+    RemoveBreakpoint(u64), // address
     ListBreakpoints,
 }
 
 #[derive(Debug, Clone)]
 pub enum DebuggerEvent {
     Started,
-    Stopped { reason: StopReason },
+    /// The command thread is running and commands sent from now on are
+    /// processed. Emitted once, after [`DebuggerEvent::Started`].
+    Ready,
+    Stopped {
+        reason: StopReason,
+    },
     Exited(i32),
     Error(String),
     MemoryRead(Vec<u8>),
+    /// Reply to [`DebuggerCommand::ReadCString`], decoded as lossy UTF-8.
+    CString(String),
     Disassembly(Vec<String>),
-    BreakpointAdded { address: u64, success: bool },
-    BreakpointRemoved { address: u64, success: bool },
+    BreakpointAdded {
+        address: u64,
+        success: bool,
+    },
+    BreakpointRemoved {
+        address: u64,
+        success: bool,
+    },
     BreakpointList(Vec<Breakpoint>),
 }
 
@@ -94,83 +182,101 @@ impl VeditSession {
     }
 }
 
+/// Launch `config.executable` under ptrace and start the session threads.
+///
+/// The process is stopped at its first instruction. Callers should wait for
+/// [`DebuggerEvent::Ready`] before sending commands such as
+/// [`DebuggerCommand::Continue`].
 pub fn spawn_session(config: LaunchConfig) -> Result<VeditSession, DebuggerError> {
     let (command_sender, command_receiver) = unbounded();
     let (event_sender, event_receiver) = unbounded();
-
-    let child_pid = unsafe {
-        match fork()? {
-            ForkResult::Parent { child } => child,
-            ForkResult::Child => {
-                // In child process
-                ptrace::traceme().map_err(|e| {
-                    eprintln!("traceme failed: {:?}", e);
-                    e
-                })?;
-
-                // Set up the command
-                let mut cmd = Command::new(&config.executable);
-                cmd.args(&config.arguments)
-                    .current_dir(&config.working_directory)
-                    .stdin(Stdio::null())
-                    .stdout(Stdio::null())
-                    .stderr(Stdio::null());
-
-                // Use exec to replace the process
-                let err = cmd.exec();
-                eprintln!("exec failed: {:?}", err);
-                std::process::exit(1);
+    // The wait thread reports stops to the command thread, which alone may
+    // issue ptrace requests, and waits until it has resumed the target.
+    let (stop_sender, stop_receiver) = unbounded::<Signal>();
+    let (resumed_sender, resumed_receiver) = unbounded::<()>();
+
+    // ptrace requests are only accepted from the thread that traced the
+    // child, so the command thread launches the process itself. The session
+    // is handed out once it is receiving commands.
+    let (ready_sender, ready_receiver) = bounded(1);
+    let command_event_sender = event_sender.clone();
+    let breakpoints_for_commands: Breakpoints = Arc::new(Mutex::new(HashMap::new()));
+    thread::spawn(move || {
+        let child_pid = match launch(&config) {
+            Ok(pid) => pid,
+            Err(err) => {
+                let _ = ready_sender.send(Err(err));
+                return;
+            }
+        };
+
+        // Set up breakpoints
+        for addr in &config.breakpoints {
+            if let Ok(original) = set_breakpoint(child_pid, *addr) {
+                breakpoints_for_commands.lock().unwrap().insert(
+                    *addr,
+                    Breakpoint {
+                        address: *addr,
+                        original_byte: original,
+                        enabled: true,
+                        condition: None,
+                    },
+                );
             }
         }
-    };
-
-    // Wait for the child to stop after traceme
-    match waitpid(child_pid, Some(WaitPidFlag::WSTOPPED))? {
-        WaitStatus::Stopped(_, Signal::SIGTRAP) => {
-            // Good, child is stopped
-        }
-        _status => {
-            return Err(DebuggerError::ProcessExited);
-        }
-    }
 
-    let breakpoints = Arc::new(Mutex::new(HashMap::new()));
+        let _ = command_event_sender.send(DebuggerEvent::Started);
+        let _ = command_event_sender.send(DebuggerEvent::Ready);
+        let _ = ready_sender.send(Ok(child_pid));
 
-    // Set up breakpoints
-    for addr in &config.breakpoints {
-        if let Ok(original) = set_breakpoint(child_pid, *addr) {
-            breakpoints.lock().unwrap().insert(
-                *addr,
-                Breakpoint {
-                    address: *addr,
-                    original_byte: original,
-                    enabled: true,
+        let mut stops = stop_receiver;
+        // Whether the wait thread is blocked until the target is resumed.
+        let mut stopped = false;
+        let resumed = |stopped: &mut bool| {
+            if std::mem::take(stopped) {
+                let _ = resumed_sender.send(());
+            }
+        };
+        loop {
+            let command = select! {
+                recv(command_receiver) -> command => match command {
+                    Ok(command) => command,
+                    Err(_) => break,
                 },
-            );
-        }
-    }
-
-    let event_sender_clone = event_sender.clone();
-    thread::spawn(move || {
-        let _ = event_sender_clone.send(DebuggerEvent::Started);
-    });
-
-    let command_event_sender = event_sender.clone();
-    let breakpoints_for_commands = breakpoints.clone();
-    thread::spawn(move || {
-        while let Ok(command) = command_receiver.recv() {
+                recv(stops) -> signal => {
+                    let Ok(signal) = signal else {
+                        // The target is gone; keep serving commands.
+                        stops = never();
+                        continue;
+                    };
+                    stopped = true;
+                    match handle_stop(child_pid, signal, &breakpoints_for_commands) {
+                        Ok(Some(reason)) => {
+                            let _ = command_event_sender.send(DebuggerEvent::Stopped { reason });
+                        }
+                        Ok(None) => resumed(&mut stopped),
+                        Err(err) => {
+                            let _ = command_event_sender.send(DebuggerEvent::Error(err.to_string()));
+                            break;
+                        }
+                    }
+                    continue;
+                },
+            };
             match command {
                 DebuggerCommand::Continue => {
                     if let Err(err) = ptrace::cont(child_pid, None) {
                         let _ = command_event_sender.send(DebuggerEvent::Error(err.to_string()));
                         break;
                     }
+                    resumed(&mut stopped);
                 }
                 DebuggerCommand::Step => {
                     if let Err(err) = ptrace::step(child_pid, None) {
                         let _ = command_event_sender.send(DebuggerEvent::Error(err.to_string()));
                         break;
                     }
+                    resumed(&mut stopped);
                 }
                 DebuggerCommand::Kill => {
                     let _ = kill(child_pid, Signal::SIGKILL);
@@ -187,6 +293,18 @@ pub fn spawn_session(config: LaunchConfig) -> Result<VeditSession, DebuggerError
                         }
                     }
                 }
+                DebuggerCommand::ReadCString { address, max_len } => {
+                    let read = |addr, size| read_memory(child_pid, addr, size);
+                    match read_c_string(read, address, max_len) {
+                        Ok(string) => {
+                            let _ = command_event_sender.send(DebuggerEvent::CString(string));
+                        }
+                        Err(err) => {
+                            let _ =
+                                command_event_sender.send(DebuggerEvent::Error(err.to_string()));
+                        }
+                    }
+                }
                 DebuggerCommand::Disassemble(addr, count) => {
                     match disassemble_memory(child_pid, addr, count) {
                         Ok(instructions) => {
@@ -200,41 +318,22 @@ pub fn spawn_session(config: LaunchConfig) -> Result<VeditSession, DebuggerError
                     }
                 }
                 DebuggerCommand::AddBreakpoint(addr) => {
-                    let mut bps = breakpoints_for_commands.lock().unwrap();
-                    if bps.contains_key(&addr) {
-                        // Breakpoint already exists at this address
-                        let _ = command_event_sender.send(DebuggerEvent::BreakpointAdded {
-                            address: addr,
-                            success: true,
-                        });
-                    } else {
-                        match set_breakpoint(child_pid, addr) {
-                            Ok(original_byte) => {
-                                bps.insert(
-                                    addr,
-                                    Breakpoint {
-                                        address: addr,
-                                        original_byte,
-                                        enabled: true,
-                                    },
-                                );
-                                let _ = command_event_sender.send(DebuggerEvent::BreakpointAdded {
-                                    address: addr,
-                                    success: true,
-                                });
-                            }
-                            Err(err) => {
-                                let _ = command_event_sender.send(DebuggerEvent::Error(format!(
-                                    "Failed to set breakpoint at 0x{:x}: {}",
-                                    addr, err
-                                )));
-                                let _ = command_event_sender.send(DebuggerEvent::BreakpointAdded {
-                                    address: addr,
-                                    success: false,
-                                });
-                            }
-                        }
-                    }
+                    add_breakpoint(
+                        child_pid,
+                        &breakpoints_for_commands,
+                        &command_event_sender,
+                        addr,
+                        None,
+                    );
+                }
+                DebuggerCommand::AddConditionalBreakpoint(addr, condition) => {
+                    add_breakpoint(
+                        child_pid,
+                        &breakpoints_for_commands,
+                        &command_event_sender,
+                        addr,
+                        Some(condition),
+                    );
                 }
                 DebuggerCommand::RemoveBreakpoint(addr) => {
                     let mut bps = breakpoints_for_commands.lock().unwrap();
@@ -278,8 +377,11 @@ pub fn spawn_session(config: LaunchConfig) -> Result<VeditSession, DebuggerError
         }
     });
 
+    let child_pid = ready_receiver
+        .recv()
+        .map_err(|_| DebuggerError::ProcessExited)??;
+
     let wait_sender = event_sender.clone();
-    let breakpoints_for_wait = breakpoints.clone();
     thread::spawn(move || {
         loop {
             match waitpid(child_pid, None) {
@@ -288,45 +390,13 @@ pub fn spawn_session(config: LaunchConfig) -> Result<VeditSession, DebuggerError
                     break;
                 }
                 Ok(WaitStatus::Stopped(_, signal)) => {
-                    let reason = match signal {
-                        Signal::SIGTRAP => {
-                            // Check if we hit a breakpoint
-                            if let Ok(pc) = get_program_counter(child_pid) {
-                                if let Some(bp) =
-                                    breakpoints_for_wait.lock().unwrap().get(&(pc - 1))
-                                {
-                                    // Restore original byte and step back
-                                    if let Err(_) = restore_breakpoint(child_pid, bp) {
-                                        let _ = wait_sender.send(DebuggerEvent::Error(
-                                            "Failed to restore breakpoint".to_string(),
-                                        ));
-                                        break;
-                                    }
-                                    // Step to execute the original instruction
-                                    if let Err(_) = ptrace::step(child_pid, None) {
-                                        let _ = wait_sender.send(DebuggerEvent::Error(
-                                            "Failed to step".to_string(),
-                                        ));
-                                        break;
-                                    }
-                                    // Re-set the breakpoint
-                                    if let Err(_) = set_breakpoint(child_pid, bp.address) {
-                                        let _ = wait_sender.send(DebuggerEvent::Error(
-                                            "Failed to re-set breakpoint".to_string(),
-                                        ));
-                                        break;
-                                    }
-                                    StopReason::Breakpoint
-                                } else {
-                                    StopReason::Step
-                                }
-                            } else {
-                                StopReason::Signal(signal)
-                            }
-                        }
-                        _ => StopReason::Signal(signal),
-                    };
-                    let _ = wait_sender.send(DebuggerEvent::Stopped { reason });
+                    // Waiting again before the command thread resumes the
+                    // target would race with its own waits while stepping
+                    // over a breakpoint. Once it is gone (e.g. after `Kill`)
+                    // only reaping the target remains.
+                    if stop_sender.send(signal).is_ok() {
+                        let _ = resumed_receiver.recv();
+                    }
                 }
                 Ok(WaitStatus::Signaled(_, signal, _)) => {
                     let _ = wait_sender.send(DebuggerEvent::Exited(signal as i32));
@@ -348,6 +418,131 @@ pub fn spawn_session(config: LaunchConfig) -> Result<VeditSession, DebuggerError
     })
 }
 
+/// Fork and exec the target with tracing enabled, returning once it is
+/// stopped at its first instruction.
+fn launch(config: &LaunchConfig) -> Result<Pid, DebuggerError> {
+    let child_pid = unsafe {
+        match fork()? {
+            ForkResult::Parent { child } => child,
+            ForkResult::Child => {
+                // In child process
+                ptrace::traceme().map_err(|e| {
+                    eprintln!("traceme failed: {:?}", e);
+                    e
+                })?;
+
+                // Set up the command
+                let mut cmd = Command::new(&config.executable);
+                cmd.args(&config.arguments)
+                    .current_dir(&config.working_directory)
+                    .stdin(Stdio::null())
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null());
+
+                // Use exec to replace the process
+                let err = cmd.exec();
+                eprintln!("exec failed: {:?}", err);
+                std::process::exit(1);
+            }
+        }
+    };
+
+    // Wait for the child to stop after traceme
+    match waitpid(child_pid, Some(WaitPidFlag::WSTOPPED))? {
+        WaitStatus::Stopped(_, Signal::SIGTRAP) => {
+            // Good, child is stopped
+            Ok(child_pid)
+        }
+        _status => Err(DebuggerError::ProcessExited),
+    }
+}
+
+type Breakpoints = Arc<Mutex<HashMap<u64, Breakpoint>>>;
+
+/// Work out why the target stopped. Returns `None` when it hit a breakpoint
+/// whose condition does not hold and was continued silently.
+fn handle_stop(
+    pid: Pid,
+    signal: Signal,
+    breakpoints: &Breakpoints,
+) -> Result<Option<StopReason>, nix::errno::Errno> {
+    if signal != Signal::SIGTRAP {
+        return Ok(Some(StopReason::Signal(signal)));
+    }
+    let Ok(pc) = get_program_counter(pid) else {
+        return Ok(Some(StopReason::Signal(signal)));
+    };
+    let hit = breakpoints
+        .lock()
+        .unwrap()
+        .get(&pc.wrapping_sub(1))
+        .cloned();
+    let Some(bp) = hit else {
+        return Ok(Some(StopReason::Step));
+    };
+
+    // An unreadable operand stops, so the breakpoint is never lost silently.
+    let stop = bp.condition.is_none_or(|condition| {
+        condition
+            .evaluate(|operand| read_operand(pid, operand))
+            .unwrap_or(true)
+    });
+    step_over_breakpoint(pid, &bp)?;
+    if stop {
+        Ok(Some(StopReason::Breakpoint))
+    } else {
+        ptrace::cont(pid, None)?;
+        Ok(None)
+    }
+}
+
+fn add_breakpoint(
+    pid: Pid,
+    breakpoints: &Breakpoints,
+    events: &Sender<DebuggerEvent>,
+    addr: u64,
+    condition: Option<Condition>,
+) {
+    let mut bps = breakpoints.lock().unwrap();
+    if let Some(bp) = bps.get_mut(&addr) {
+        // Breakpoint already exists at this address; a plain breakpoint
+        // drops any condition.
+        bp.condition = condition;
+        let _ = events.send(DebuggerEvent::BreakpointAdded {
+            address: addr,
+            success: true,
+        });
+        return;
+    }
+    match set_breakpoint(pid, addr) {
+        Ok(original_byte) => {
+            bps.insert(
+                addr,
+                Breakpoint {
+                    address: addr,
+                    original_byte,
+                    enabled: true,
+                    condition,
+                },
+            );
+            let _ = events.send(DebuggerEvent::BreakpointAdded {
+                address: addr,
+                success: true,
+            });
+        }
+        Err(err) => {
+            let _ = events.send(DebuggerEvent::Error(format!(
+                "Failed to set breakpoint at 0x{:x}: {}",
+                addr, err
+            )));
+            let _ = events.send(DebuggerEvent::BreakpointAdded {
+                address: addr,
+                success: false,
+            });
+        }
+    }
+}
+
 fn set_breakpoint(pid: Pid, addr: u64) -> Result<u8, nix::errno::Errno> {
     let original_word: i64 = ptrace::read(pid, addr as *mut _)?;
     let original_byte = (original_word & 0xFF) as u8;
@@ -363,6 +558,72 @@ fn restore_breakpoint(pid: Pid, bp: &Breakpoint) -> Result<(), nix::errno::Errno
     Ok(())
 }
 
+/// Rewind to the trapped breakpoint, execute the original instruction and
+/// re-arm the breakpoint, leaving the target stopped after it.
+fn step_over_breakpoint(pid: Pid, bp: &Breakpoint) -> Result<(), nix::errno::Errno> {
+    #[cfg(target_arch = "x86_64")]
+    {
+        let mut regs = ptrace::getregs(pid)?;
+        regs.rip = bp.address;
+        ptrace::setregs(pid, regs)?;
+        restore_breakpoint(pid, bp)?;
+        ptrace::step(pid, None)?;
+        waitpid(pid, None)?;
+        set_breakpoint(pid, bp.address)?;
+        Ok(())
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    {
+        let _ = bp;
+        Err(nix::errno::Errno::ENOTSUP)
+    }
+}
+
+fn read_operand(pid: Pid, operand: Operand) -> Result<u64, nix::errno::Errno> {
+    match operand {
+        Operand::Register(register) => read_register(pid, register),
+        Operand::Memory { address, size } => {
+            let bytes = read_memory(pid, address, usize::from(size.clamp(1, 8)))?;
+            Ok(bytes
+                .iter()
+                .rev()
+                .fold(0, |value, &byte| (value << 8) | u64::from(byte)))
+        }
+    }
+}
+
+fn read_register(pid: Pid, register: Register) -> Result<u64, nix::errno::Errno> {
+    #[cfg(target_arch = "x86_64")]
+    {
+        let regs = ptrace::getregs(pid)?;
+        Ok(match register {
+            Register::Rax => regs.rax,
+            Register::Rbx => regs.rbx,
+            Register::Rcx => regs.rcx,
+            Register::Rdx => regs.rdx,
+            Register::Rsi => regs.rsi,
+            Register::Rdi => regs.rdi,
+            Register::Rbp => regs.rbp,
+            Register::Rsp => regs.rsp,
+            Register::R8 => regs.r8,
+            Register::R9 => regs.r9,
+            Register::R10 => regs.r10,
+            Register::R11 => regs.r11,
+            Register::R12 => regs.r12,
+            Register::R13 => regs.r13,
+            Register::R14 => regs.r14,
+            Register::R15 => regs.r15,
+            // The trap has already executed the 0xCC.
+            Register::Rip => regs.rip - 1,
+        })
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    {
+        let _ = register;
+        Err(nix::errno::Errno::ENOTSUP)
+    }
+}
+
 fn get_program_counter(pid: Pid) -> Result<u64, nix::errno::Errno> {
     #[cfg(target_arch = "x86_64")]
     {
@@ -375,15 +636,54 @@ fn get_program_counter(pid: Pid) -> Result<u64, nix::errno::Errno> {
     }
 }
 
+/// Read `size` bytes at `addr` a word at a time. Words are aligned, so no
+/// read touches a page beyond the requested range.
 fn read_memory(pid: Pid, addr: u64, size: usize) -> Result<Vec<u8>, nix::errno::Errno> {
+    const WORD: u64 = size_of::<libc::c_long>() as u64;
+    let end = addr.saturating_add(size as u64);
     let mut data = Vec::with_capacity(size);
-    for i in 0..size {
-        let word: i64 = ptrace::read(pid, (addr + i as u64) as *mut _)?;
-        data.push(word as u8);
+    let mut word_addr = addr - addr % WORD;
+    while word_addr < end {
+        let word = ptrace::read(pid, word_addr as *mut _)?;
+        let bytes = word.to_ne_bytes();
+        let from = addr.saturating_sub(word_addr) as usize;
+        let to = (end - word_addr).min(WORD) as usize;
+        data.extend_from_slice(&bytes[from..to]);
+        word_addr += WORD;
     }
     Ok(data)
 }
 
+/// Read the NUL-terminated string at `address` through `read`, which
+/// returns the bytes of an `(address, size)` range. Stops at the terminator
+/// or after `max_len` bytes and decodes the bytes as lossy UTF-8.
+///
+/// Reads are chunked and aligned so that none extends past the page holding
+/// the terminator.
+fn read_c_string<E>(
+    mut read: impl FnMut(u64, usize) -> Result<Vec<u8>, E>,
+    address: u64,
+    max_len: usize,
+) -> Result<String, E> {
+    const CHUNK: u64 = 64;
+    let mut bytes = Vec::new();
+    let mut cursor = address;
+    while bytes.len() < max_len {
+        let chunk_len = (CHUNK - cursor % CHUNK) as usize;
+        let chunk = read(cursor, chunk_len.min(max_len - bytes.len()))?;
+        if let Some(nul) = chunk.iter().position(|&byte| byte == 0) {
+            bytes.extend_from_slice(&chunk[..nul]);
+            break;
+        }
+        if chunk.is_empty() {
+            break;
+        }
+        cursor += chunk.len() as u64;
+        bytes.extend(chunk);
+    }
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
 fn disassemble_memory(pid: Pid, addr: u64, count: usize) -> Result<Vec<String>, nix::errno::Errno> {
     // Read some memory around the address
     let memory_size = 1024; // Read 1KB for disassembly
@@ -415,3 +715,206 @@ fn disassemble_memory(pid: Pid, addr: u64, count: usize) -> Result<Vec<String>,
 
     Ok(instructions)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn continue_after_ready_is_processed() {
+        let session = spawn_session(LaunchConfig {
+            executable: PathBuf::from("/bin/true"),
+            working_directory: std::env::temp_dir(),
+            arguments: Vec::new(),
+            breakpoints: Vec::new(),
+        })
+        .unwrap();
+        let events = session.event_receiver();
+        let next = || events.recv_timeout(Duration::from_secs(5)).unwrap();
+
+        assert!(matches!(next(), DebuggerEvent::Started));
+        assert!(matches!(next(), DebuggerEvent::Ready));
+
+        session
+            .command_sender()
+            .send(DebuggerCommand::Continue)
+            .unwrap();
+        let event = next();
+        assert!(matches!(event, DebuggerEvent::Exited(0)), "{event:?}");
+    }
+
+    /// Launch `/bin/echo marker` and return the session with the entry point
+    /// of the program, read from the stopped child's auxiliary vector.
+    fn launch_echo(marker: &str) -> (VeditSession, u64) {
+        const AT_ENTRY: u64 = 9;
+        let session = spawn_session(LaunchConfig {
+            executable: PathBuf::from("/bin/echo"),
+            working_directory: std::env::temp_dir(),
+            arguments: vec![marker.to_string()],
+            breakpoints: Vec::new(),
+        })
+        .unwrap();
+
+        let children = std::fs::read_dir("/proc/self/task")
+            .unwrap()
+            .filter_map(|task| std::fs::read_to_string(task.ok()?.path().join("children")).ok())
+            .collect::<String>();
+        let pid = children
+            .split_whitespace()
+            .find(|pid| {
+                std::fs::read(format!("/proc/{pid}/cmdline"))
+                    .is_ok_and(|cmdline| cmdline.ends_with(format!("{marker}\0").as_bytes()))
+            })
+            .expect("traced child");
+        let auxv = std::fs::read(format!("/proc/{pid}/auxv")).unwrap();
+        let entry = auxv
+            .chunks_exact(16)
+            .map(|pair| {
+                let word = |bytes: &[u8]| u64::from_ne_bytes(bytes.try_into().unwrap());
+                (word(&pair[..8]), word(&pair[8..]))
+            })
+            .find(|(key, _)| *key == AT_ENTRY)
+            .map(|(_, value)| value)
+            .unwrap();
+        (session, entry)
+    }
+
+    #[test]
+    fn conditional_breakpoints_skip_or_stop() {
+        let rip = |comparison, value| Condition {
+            operand: Operand::Register(Register::Rip),
+            comparison,
+            value,
+        };
+
+        let (session, entry) = launch_echo("vedit-conditional-skip");
+        let events = session.event_receiver();
+        let next = || events.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(matches!(next(), DebuggerEvent::Started));
+        assert!(matches!(next(), DebuggerEvent::Ready));
+        let commands = session.command_sender();
+        commands
+            .send(DebuggerCommand::AddConditionalBreakpoint(
+                entry,
+                rip(Comparison::NotEqual, entry),
+            ))
+            .unwrap();
+        assert!(matches!(
+            next(),
+            DebuggerEvent::BreakpointAdded { success: true, .. }
+        ));
+        commands.send(DebuggerCommand::Continue).unwrap();
+        let event = next();
+        assert!(matches!(event, DebuggerEvent::Exited(0)), "{event:?}");
+
+        // A plain breakpoint replaces the condition and always stops.
+        let (session, entry) = launch_echo("vedit-conditional-stop");
+        let events = session.event_receiver();
+        let next = || events.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(matches!(next(), DebuggerEvent::Started));
+        assert!(matches!(next(), DebuggerEvent::Ready));
+        let commands = session.command_sender();
+        commands
+            .send(DebuggerCommand::AddConditionalBreakpoint(
+                entry,
+                rip(Comparison::NotEqual, entry),
+            ))
+            .unwrap();
+        commands
+            .send(DebuggerCommand::AddBreakpoint(entry))
+            .unwrap();
+        commands.send(DebuggerCommand::ListBreakpoints).unwrap();
+        next();
+        next();
+        match next() {
+            DebuggerEvent::BreakpointList(list) => {
+                assert_eq!(list.len(), 1);
+                assert!(list[0].condition.is_none());
+            }
+            event => panic!("{event:?}"),
+        }
+        commands.send(DebuggerCommand::Continue).unwrap();
+        let event = next();
+        assert!(
+            matches!(
+                event,
+                DebuggerEvent::Stopped {
+                    reason: StopReason::Breakpoint
+                }
+            ),
+            "{event:?}"
+        );
+        commands.send(DebuggerCommand::Continue).unwrap();
+        let event = next();
+        assert!(matches!(event, DebuggerEvent::Exited(0)), "{event:?}");
+    }
+
+    #[test]
+    fn reads_c_strings_from_memory() {
+        const BASE: u64 = 0x1000;
+        let mut memory = vec![0xAAu8; 0x100];
+        memory[0x3A..0x4C].copy_from_slice(b"hello, \xFFworld\0tail");
+        let reads = std::cell::RefCell::new(Vec::new());
+        let read = |address: u64, size: usize| {
+            reads.borrow_mut().push((address, size));
+            let start = (address - BASE) as usize;
+            memory
+                .get(start..start + size)
+                .map(<[u8]>::to_vec)
+                .ok_or("unmapped")
+        };
+
+        let string = read_c_string(&read, BASE + 0x3A, 256).unwrap();
+        assert_eq!(string, "hello, \u{FFFD}world");
+        assert_eq!(*reads.borrow(), vec![(BASE + 0x3A, 6), (BASE + 0x40, 64)]);
+
+        assert_eq!(read_c_string(&read, BASE + 0x3A, 5).unwrap(), "hello");
+        assert_eq!(read_c_string(&read, BASE + 0x48, 0).unwrap(), "");
+        assert_eq!(read_c_string(&read, BASE + 0xF0, 256), Err("unmapped"));
+    }
+
+    #[test]
+    fn evaluates_breakpoint_conditions() {
+        let registers = |operand| match operand {
+            Operand::Register(Register::Rax) => Ok(42),
+            Operand::Register(Register::Rdi) => Ok(u64::MAX),
+            _ => Err("unreadable"),
+        };
+        let condition = |operand, comparison, value| Condition {
+            operand,
+            comparison,
+            value,
+        };
+        let rax = Operand::Register(Register::Rax);
+
+        assert_eq!(
+            condition(rax, Comparison::Equal, 42).evaluate(registers),
+            Ok(true)
+        );
+        assert_eq!(
+            condition(rax, Comparison::NotEqual, 42).evaluate(registers),
+            Ok(false)
+        );
+        assert_eq!(
+            condition(rax, Comparison::Less, 43).evaluate(registers),
+            Ok(true)
+        );
+        assert_eq!(
+            condition(rax, Comparison::GreaterOrEqual, 43).evaluate(registers),
+            Ok(false)
+        );
+        assert_eq!(
+            condition(Operand::Register(Register::Rdi), Comparison::Greater, 0).evaluate(registers),
+            Ok(true)
+        );
+        let memory = Operand::Memory {
+            address: 0x1000,
+            size: 4,
+        };
+        assert_eq!(
+            condition(memory, Comparison::Equal, 0).evaluate(registers),
+            Err("unreadable")
+        );
+    }
+}
@@ -1,8 +1,10 @@
 use crate::mapped::MappedDocument;
+use crate::source::ContentSource;
 use std::cmp;
 use std::fs;
 use std::hash::{Hash, Hasher};
-use std::io;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use vedit_config::{StickyNote, StickyNoteRecord};
@@ -29,6 +31,14 @@ impl Clone for MmapCache {
     }
 }
 
+/// The part of a file a partial document holds, see [`Document::from_path_window`].
+#[derive(Debug, Clone)]
+struct FileWindow {
+    path: PathBuf,
+    /// Byte range of the file held in the buffer, on line boundaries.
+    range: Range<u64>,
+}
+
 /// Core document structure representing a file or buffer
 #[derive(Debug, Clone)]
 pub struct Document {
@@ -42,8 +52,12 @@ pub struct Document {
     pub fingerprint: Option<u64>,
     /// Sticky notes attached to the document
     pub sticky_notes: Vec<StickyNote>,
+    /// Description of the non-file source the content was loaded from
+    pub source: Option<String>,
     /// Cached memory-mapped document for large files (avoids re-opening/re-indexing)
     mmap_cache: Option<Arc<MmapCache>>,
+    /// Set when the document holds only a byte range of a file
+    window: Option<FileWindow>,
 }
 
 impl Document {
@@ -56,7 +70,9 @@ impl Document {
             is_modified: false,
             fingerprint,
             sticky_notes: Vec::new(),
+            source: None,
             mmap_cache: None,
+            window: None,
         }
     }
 
@@ -69,7 +85,9 @@ impl Document {
             is_modified: false,
             fingerprint,
             sticky_notes: Vec::new(),
+            source: None,
             mmap_cache: Some(Arc::new(cache)),
+            window: None,
         }
     }
 
@@ -133,6 +151,73 @@ impl Document {
         ))
     }
 
+    /// Load a memory-backed document from a non-file [`ContentSource`].
+    ///
+    /// The document has no path; its source description is kept for display.
+    pub fn from_source(source: &(impl ContentSource + ?Sized)) -> io::Result<Self> {
+        let contents = source.read_all()?;
+        let mut document = Self::new(None, contents);
+        document.source = Some(source.description());
+        Ok(document)
+    }
+
+    /// Load only the `range` bytes of a file, e.g. the tail of a huge log.
+    ///
+    /// The range is narrowed to whole lines: a partial first line and a
+    /// partial last line (unless it ends the file) are dropped. The result is
+    /// a memory-backed partial view without a path, so it cannot be saved
+    /// over the file; use [`Document::extend_window`] to see more of it.
+    pub fn from_path_window(path: impl AsRef<Path>, range: Range<u64>) -> io::Result<Self> {
+        let path_buf = path.as_ref().to_path_buf();
+        let (range, contents) = read_window(&path_buf, range)?;
+        let mut document = Self::new(None, contents);
+        document.source = Some(path_buf.to_string_lossy().to_string());
+        document.window = Some(FileWindow {
+            path: path_buf,
+            range,
+        });
+        Ok(document)
+    }
+
+    /// Whether the document holds only part of a file.
+    pub fn is_partial(&self) -> bool {
+        self.window.is_some()
+    }
+
+    /// Byte range of the file a partial document holds.
+    pub fn window(&self) -> Option<Range<u64>> {
+        self.window.as_ref().map(|window| window.range.clone())
+    }
+
+    /// Grow the window of a partial document by up to `before` bytes
+    /// towards the start of the file and `after` bytes towards its end,
+    /// reloading the buffer.
+    ///
+    /// Fails for documents that are not partial views or have been modified.
+    pub fn extend_window(&mut self, before: u64, after: u64) -> io::Result<()> {
+        let Some(window) = &self.window else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "document is not a partial view",
+            ));
+        };
+        if self.is_modified {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "cannot extend the window of a modified document",
+            ));
+        }
+
+        let requested =
+            window.range.start.saturating_sub(before)..window.range.end.saturating_add(after);
+        let (range, contents) = read_window(&window.path, requested)?;
+        self.buffer = TextBuffer::from_text(contents);
+        if let Some(window) = &mut self.window {
+            window.range = range;
+        }
+        Ok(())
+    }
+
     /// Open a document with automatic memory-mapping for large files.
     ///
     /// This is the recommended way to open files as it:
@@ -242,6 +327,8 @@ impl Document {
                 .file_name()
                 .and_then(|name| name.to_str())
                 .unwrap_or(path)
+        } else if let Some(source) = &self.source {
+            source
         } else {
             "(scratch)"
         }
@@ -251,7 +338,8 @@ impl Document {
     pub fn language(&self) -> Language {
         self.path
             .as_deref()
-            .map(detect_language_from_path)
+            .or(self.source.as_deref())
+            .map(|path| Language::from_path(Path::new(path)))
             .unwrap_or(Language::PlainText)
     }
 
@@ -431,6 +519,48 @@ impl Default for Document {
     }
 }
 
+/// Read `range` of a file, narrowed to whole lines. Returns the range read.
+fn read_window(path: &Path, range: Range<u64>) -> io::Result<(Range<u64>, String)> {
+    let mut file = fs::File::open(path)?;
+    let file_len = file.metadata()?.len();
+    let start = range.start.min(file_len);
+    let end = range.end.clamp(start, file_len);
+
+    // Include the byte before the range to tell whether it starts a line.
+    let read_from = start.saturating_sub(1);
+    let mut bytes = Vec::with_capacity((end - read_from) as usize);
+    file.seek(SeekFrom::Start(read_from))?;
+    file.by_ref()
+        .take(end - read_from)
+        .read_to_end(&mut bytes)?;
+
+    let mut first = 0;
+    if start > 0 {
+        first = match bytes.iter().position(|&byte| byte == b'\n') {
+            Some(newline) => newline + 1,
+            None => bytes.len(),
+        };
+    }
+    let mut last = bytes.len();
+    if end < file_len {
+        last = bytes[first..]
+            .iter()
+            .rposition(|&byte| byte == b'\n')
+            .map_or(first, |newline| first + newline + 1);
+    }
+
+    let window = read_from + first as u64..read_from + last as u64;
+    bytes.truncate(last);
+    bytes.drain(..first);
+    let contents = String::from_utf8(bytes).map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("File contains invalid UTF-8: {}", e),
+        )
+    })?;
+    Ok((window, contents))
+}
+
 fn compute_fingerprint(path: &str) -> u64 {
     let resolved = canonicalize_lossy(path);
     let mut hasher = std::collections::hash_map::DefaultHasher::new();
@@ -446,74 +576,6 @@ fn canonicalize_lossy(path: &str) -> String {
         .to_string()
 }
 
-fn detect_language_from_path(path: &str) -> Language {
-    let path = Path::new(path);
-
-    if let Some(name) = path.file_name().and_then(|name| name.to_str()) {
-        let lower = name.to_ascii_lowercase();
-        match lower.as_str() {
-            "makefile" => return Language::Makefile,
-            "dockerfile" => return Language::Dockerfile,
-            "cmakelists.txt" => return Language::CMake,
-            _ => {}
-        }
-    }
-
-    match path
-        .extension()
-        .and_then(|ext| ext.to_str())
-        .map(|ext| ext.to_ascii_lowercase())
-    {
-        Some(ext) => match ext.as_str() {
-            "rs" => Language::Rust,
-            "c" => Language::C,
-            "h" => Language::CHeader,
-            "hh" | "hpp" | "hxx" | "h++" => Language::CppHeader,
-            "cpp" | "cc" | "cxx" | "c++" => Language::Cpp,
-            "m" => Language::ObjectiveC,
-            "mm" => Language::ObjectiveCpp,
-            "swift" => Language::Swift,
-            "java" => Language::Java,
-            "kt" | "kts" => Language::Kotlin,
-            "cs" => Language::CSharp,
-            "go" => Language::Go,
-            "py" => Language::Python,
-            "rb" => Language::Ruby,
-            "php" => Language::Php,
-            "hs" => Language::Haskell,
-            "erl" | "hrl" => Language::Erlang,
-            "ex" | "exs" => Language::Elixir,
-            "js" => Language::JavaScript,
-            "jsx" => Language::Jsx,
-            "ts" => Language::TypeScript,
-            "tsx" => Language::Tsx,
-            "json" => Language::Json,
-            "toml" => Language::Toml,
-            "yaml" | "yml" => Language::Yaml,
-            "ini" => Language::Ini,
-            "md" | "markdown" => Language::Markdown,
-            "sql" => Language::Sql,
-            "html" | "htm" => Language::Html,
-            "css" => Language::Css,
-            "scss" | "sass" => Language::Scss,
-            "less" => Language::Less,
-            "lua" => Language::Lua,
-            "zig" => Language::Zig,
-            "dart" => Language::Dart,
-            "scala" => Language::Scala,
-            "sh" | "bash" => Language::Shell,
-            "fish" => Language::Fish,
-            "ps1" => Language::PowerShell,
-            "bat" => Language::Batch,
-            "vue" => Language::Vue,
-            "svelte" => Language::Svelte,
-            "nix" => Language::Nix,
-            _ => Language::PlainText,
-        },
-        None => Language::PlainText,
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -569,6 +631,66 @@ mod tests {
         assert_eq!(doc.language(), Language::Rust);
     }
 
+    #[test]
+    fn loads_from_content_source() {
+        use crate::source::FnSource;
+
+        let source = FnSource::new("git:HEAD:src/main.rs", || Ok("fn main() {}\n".to_string()));
+        let doc = Document::from_source(&source).unwrap();
+        assert_eq!(doc.content(), "fn main() {}\n");
+        assert_eq!(doc.path(), None);
+        assert_eq!(doc.source.as_deref(), Some("git:HEAD:src/main.rs"));
+        assert_eq!(doc.display_name(), "git:HEAD:src/main.rs");
+        assert_eq!(doc.language(), Language::Rust);
+        assert!(!doc.is_modified());
+
+        let failing = FnSource::new("remote:gone", || {
+            Err(io::Error::new(io::ErrorKind::NotFound, "missing"))
+        });
+        assert!(Document::from_source(&failing).is_err());
+    }
+
+    #[test]
+    fn loads_tail_window_of_large_file() {
+        let temp_dir = tempdir().unwrap();
+        let file_path = temp_dir.path().join("big.log");
+        create_test_file(file_path.to_str().unwrap(), 20_000).unwrap();
+        let contents = std::fs::read_to_string(&file_path).unwrap();
+        let len = contents.len() as u64;
+
+        let mut doc = Document::from_path_window(&file_path, len - 4096..len).unwrap();
+        assert!(doc.is_partial());
+        assert_eq!(doc.path(), None);
+        let window = doc.window().unwrap();
+        assert_eq!(window.end, len);
+        assert!(window.start >= len - 4096);
+        assert_eq!(&contents[window.start as usize - 1..][..1], "\n");
+        assert_eq!(
+            doc.content(),
+            &contents[window.start as usize..window.end as usize]
+        );
+        assert!(
+            doc.content()
+                .ends_with("Line number 20000 contains some sample text.\n")
+        );
+
+        doc.extend_window(4096, 0).unwrap();
+        let extended = doc.window().unwrap();
+        assert!(extended.start < window.start && extended.end == len);
+        assert_eq!(
+            doc.content(),
+            &contents[extended.start as usize..extended.end as usize]
+        );
+
+        // A middle window drops the partial lines at both ends.
+        let middle = Document::from_path_window(&file_path, 1000..5000).unwrap();
+        let window = middle.window().unwrap();
+        assert!(window.start >= 1000 && window.end <= 5000);
+        assert!(middle.content().starts_with("Line "));
+        assert!(middle.content().ends_with('\n'));
+        assert!(!Document::from_path(&file_path).unwrap().is_partial());
+    }
+
     #[test]
     fn test_small_file_uses_regular_loading() {
         let temp_dir = tempdir().unwrap();
@@ -2,6 +2,7 @@
 //!
 //! This crate provides core document functionality including:
 //! - File-backed and in-memory documents
+//! - Documents loaded from other content sources (git objects, archives, ...)
 //! - Memory-mapped file support for large files
 //! - Line indexing and navigation
 //! - Viewport management for rendering
@@ -12,6 +13,7 @@ pub mod indexing;
 pub mod line_index;
 pub mod mapped;
 pub mod search;
+pub mod source;
 pub mod viewport;
 
 // Re-export main types for convenience
@@ -22,4 +24,5 @@ pub use mapped::{
     load_viewport_content_with_index,
 };
 pub use search::{BoyerMooreSearcher, contains_pattern, find_pattern, search_pattern};
+pub use source::{ContentSource, FnSource};
 pub use viewport::Viewport;
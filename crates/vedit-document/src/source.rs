@@ -0,0 +1,46 @@
+//! Content providers for documents that are not backed by a local file.
+
+use std::io;
+
+/// A source of document text other than a file on disk, such as a git object,
+/// an archive entry or a remote resource.
+pub trait ContentSource {
+    /// Read the complete contents of the source.
+    fn read_all(&self) -> io::Result<String>;
+
+    /// Human-readable identifier shown in place of a path,
+    /// e.g. `git:HEAD:src/main.rs`.
+    fn description(&self) -> String;
+}
+
+/// A [`ContentSource`] backed by a closure.
+pub struct FnSource<F> {
+    description: String,
+    read: F,
+}
+
+impl<F> FnSource<F>
+where
+    F: Fn() -> io::Result<String>,
+{
+    /// Create a source that calls `read` to produce its contents.
+    pub fn new(description: impl Into<String>, read: F) -> Self {
+        Self {
+            description: description.into(),
+            read,
+        }
+    }
+}
+
+impl<F> ContentSource for FnSource<F>
+where
+    F: Fn() -> io::Result<String>,
+{
+    fn read_all(&self) -> io::Result<String> {
+        (self.read)()
+    }
+
+    fn description(&self) -> String {
+        self.description.clone()
+    }
+}
@@ -568,7 +568,7 @@ impl DebuggerState {
         for project_path in vcx_projects {
             match VcxProject::from_path(&project_path) {
                 Ok(project) => {
-                    if !project.produces_executable {
+                    if !project.executable_in_any_config {
                         continue;
                     }
 
@@ -1056,45 +1056,55 @@ impl DebuggerRuntime {
                 },
                 GdbEvent::Error(err) => DebuggerUiEvent::SessionError { message: err },
             }),
-            Self::Vedit { events, .. } => events.try_recv().ok().map(|event| match event {
-                VeditEvent::Started => DebuggerUiEvent::SessionStarted { target: None },
-                VeditEvent::Stopped { reason } => DebuggerUiEvent::SessionError {
-                    message: format!("stopped: {:?}", reason),
-                },
-                VeditEvent::Exited(code) => DebuggerUiEvent::SessionError {
-                    message: format!("exited with code {}", code),
-                },
-                VeditEvent::Error(err) => DebuggerUiEvent::SessionError { message: err },
-                VeditEvent::MemoryRead(_) => DebuggerUiEvent::SessionError {
-                    message: "memory read".to_string(),
-                },
-                VeditEvent::Disassembly(_) => DebuggerUiEvent::SessionError {
-                    message: "disassembly".to_string(),
-                },
-                VeditEvent::BreakpointAdded { address, success } => DebuggerUiEvent::SessionError {
-                    message: format!(
-                        "breakpoint {}: 0x{:x}",
-                        if success { "added" } else { "failed to add" },
-                        address
-                    ),
-                },
-                VeditEvent::BreakpointRemoved { address, success } => {
-                    DebuggerUiEvent::SessionError {
-                        message: format!(
-                            "breakpoint {}: 0x{:x}",
-                            if success {
-                                "removed"
-                            } else {
-                                "failed to remove"
-                            },
-                            address
-                        ),
+            Self::Vedit { events, .. } => loop {
+                let ui_event = match events.try_recv().ok()? {
+                    VeditEvent::Started => DebuggerUiEvent::SessionStarted { target: None },
+                    // Only signals that commands are accepted; `Started`
+                    // already reported the launch.
+                    VeditEvent::Ready => continue,
+                    // Nothing in the UI issues `ReadCString` yet.
+                    VeditEvent::CString(_) => continue,
+                    VeditEvent::Stopped { reason } => DebuggerUiEvent::SessionError {
+                        message: format!("stopped: {:?}", reason),
+                    },
+                    VeditEvent::Exited(code) => DebuggerUiEvent::SessionError {
+                        message: format!("exited with code {}", code),
+                    },
+                    VeditEvent::Error(err) => DebuggerUiEvent::SessionError { message: err },
+                    VeditEvent::MemoryRead(_) => DebuggerUiEvent::SessionError {
+                        message: "memory read".to_string(),
+                    },
+                    VeditEvent::Disassembly(_) => DebuggerUiEvent::SessionError {
+                        message: "disassembly".to_string(),
+                    },
+                    VeditEvent::BreakpointAdded { address, success } => {
+                        DebuggerUiEvent::SessionError {
+                            message: format!(
+                                "breakpoint {}: 0x{:x}",
+                                if success { "added" } else { "failed to add" },
+                                address
+                            ),
+                        }
                     }
-                }
-                VeditEvent::BreakpointList(breakpoints) => DebuggerUiEvent::SessionError {
-                    message: format!("active breakpoints: {}", breakpoints.len()),
-                },
-            }),
+                    VeditEvent::BreakpointRemoved { address, success } => {
+                        DebuggerUiEvent::SessionError {
+                            message: format!(
+                                "breakpoint {}: 0x{:x}",
+                                if success {
+                                    "removed"
+                                } else {
+                                    "failed to remove"
+                                },
+                                address
+                            ),
+                        }
+                    }
+                    VeditEvent::BreakpointList(breakpoints) => DebuggerUiEvent::SessionError {
+                        message: format!("active breakpoints: {}", breakpoints.len()),
+                    },
+                };
+                return Some(ui_event);
+            },
         }
     }
 }
@@ -33,6 +33,11 @@ fn map_key(key: IcedKey) -> Option<Key> {
             key::Named::Tab => Some(Key::Tab),
             key::Named::Backspace => Some(Key::Backspace),
             key::Named::Delete => Some(Key::Delete),
+            key::Named::Home => Some(Key::Home),
+            key::Named::End => Some(Key::End),
+            key::Named::PageUp => Some(Key::PageUp),
+            key::Named::PageDown => Some(Key::PageDown),
+            key::Named::Insert => Some(Key::Insert),
             key::Named::F1 => Some(Key::Function(1)),
             key::Named::F2 => Some(Key::Function(2)),
             key::Named::F3 => Some(Key::Function(3)),
@@ -2427,17 +2427,17 @@ impl EditorState {
             return Ok(());
         };
 
-        for _ in 0..metadata.console.shell_tabs {
+        for _ in 0..metadata.layout.console.shell_tabs {
             self.console.spawn_shell_tab()?;
         }
 
-        if let Some(active_shell) = metadata.console.active_shell {
+        if let Some(active_shell) = metadata.layout.console.active_shell {
             if active_shell < self.console.shell_tab_count() {
                 self.console.select_shell_at(active_shell);
             }
         }
 
-        self.console.set_visible(metadata.console.visible);
+        self.console.set_visible(metadata.layout.console.visible);
         Ok(())
     }
 
@@ -2464,7 +2464,7 @@ impl EditorState {
         let mut mark_dirty = false;
         {
             if let Some(metadata) = editor.workspace_metadata_mut() {
-                let previous_active = metadata.console.active_shell;
+                let previous_active = metadata.layout.console.active_shell;
                 if active_shell.is_none() {
                     if let Some(prev) = previous_active {
                         if prev < shell_count {
@@ -2473,9 +2473,9 @@ impl EditorState {
                     }
                 }
 
-                metadata.console.visible = visible;
-                metadata.console.shell_tabs = shell_count;
-                metadata.console.active_shell = active_shell;
+                metadata.layout.console.visible = visible;
+                metadata.layout.console.shell_tabs = shell_count;
+                metadata.layout.console.active_shell = active_shell;
                 mark_dirty = true;
             }
         }
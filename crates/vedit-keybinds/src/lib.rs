@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt;
 use std::fs;
 use std::io;
@@ -24,6 +24,11 @@ pub enum Key {
     ArrowRight,
     Backspace,
     Delete,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Insert,
 }
 
 impl Key {
@@ -40,6 +45,11 @@ impl Key {
             "RIGHT" | "ARROWRIGHT" => Ok(Self::ArrowRight),
             "BACKSPACE" | "BKSP" => Ok(Self::Backspace),
             "DELETE" | "DEL" => Ok(Self::Delete),
+            "HOME" => Ok(Self::Home),
+            "END" => Ok(Self::End),
+            "PAGEUP" | "PGUP" => Ok(Self::PageUp),
+            "PAGEDOWN" | "PGDN" => Ok(Self::PageDown),
+            "INSERT" | "INS" => Ok(Self::Insert),
             other if other.starts_with('F') && other.len() <= 3 => {
                 let number = other[1..]
                     .parse::<u8>()
@@ -71,6 +81,11 @@ impl fmt::Display for Key {
             Key::ArrowRight => write!(f, "ArrowRight"),
             Key::Backspace => write!(f, "Backspace"),
             Key::Delete => write!(f, "Delete"),
+            Key::Home => write!(f, "Home"),
+            Key::End => write!(f, "End"),
+            Key::PageUp => write!(f, "PageUp"),
+            Key::PageDown => write!(f, "PageDown"),
+            Key::Insert => write!(f, "Insert"),
         }
     }
 }
@@ -190,6 +205,59 @@ impl fmt::Display for KeyCombination {
     }
 }
 
+/// Sequence of combinations pressed one after another, such as
+/// `ctrl+k ctrl+c`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyChord {
+    pub strokes: Vec<KeyCombination>,
+}
+
+impl KeyChord {
+    /// Parse a space-separated list of combinations. Spaces around a `+`
+    /// stay part of their combination, so `ctrl + k c` is two strokes.
+    pub fn parse(spec: &str) -> Result<Self, ParseKeyCombinationError> {
+        let mut parts: Vec<String> = Vec::new();
+        for token in spec.split_whitespace() {
+            match parts.last_mut() {
+                Some(last) if last.ends_with('+') || token.starts_with('+') => last.push_str(token),
+                _ => parts.push(token.to_string()),
+            }
+        }
+        if parts.is_empty() {
+            return Err(ParseKeyCombinationError::MissingKey(spec.to_string()));
+        }
+
+        let strokes = parts
+            .iter()
+            .map(|part| KeyCombination::parse(part))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { strokes })
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, stroke) in self.strokes.iter().enumerate() {
+            if index > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", stroke)?;
+        }
+        Ok(())
+    }
+}
+
+/// Outcome of feeding a key event to [`Keymap::match_chord`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChordMatch {
+    /// The event continues at least one chord; wait for the next stroke.
+    Pending,
+    /// The event completed the binding of this action.
+    Matched(String),
+    /// Nothing is bound to the event (or the sequence it ended).
+    None,
+}
+
 /// Error raised while parsing a key combination specification.
 #[derive(Debug)]
 pub enum ParseKeyCombinationError {
@@ -214,6 +282,15 @@ impl std::error::Error for ParseKeyCombinationError {}
 #[derive(Debug, Clone)]
 pub struct Keymap {
     bindings: HashMap<String, KeyCombination>,
+    /// `when` context required for an action's binding to apply.
+    contexts: HashMap<String, String>,
+    /// Actions bound to a sequence of two or more combinations.
+    chords: HashMap<String, KeyChord>,
+    /// Strokes of the chord typed so far.
+    pending: Vec<KeyEvent>,
+    /// Actions explicitly unbound. These tombstones mask the binding of a
+    /// keymap this one is merged or layered over.
+    removed: HashSet<String>,
 }
 
 impl Default for Keymap {
@@ -309,7 +386,13 @@ impl Default for Keymap {
                 key: Key::ArrowDown,
             },
         );
-        Self { bindings }
+        Self {
+            bindings,
+            contexts: HashMap::new(),
+            chords: HashMap::new(),
+            pending: Vec::new(),
+            removed: HashSet::new(),
+        }
     }
 }
 
@@ -318,26 +401,200 @@ impl Keymap {
         self.bindings.get(action)
     }
 
+    /// Context the binding of `action` requires, if any.
+    pub fn when(&self, action: &str) -> Option<&str> {
+        self.contexts.get(action).map(String::as_str)
+    }
+
+    /// Chord bound to `action`, if it is bound to a sequence of strokes.
+    pub fn chord(&self, action: &str) -> Option<&KeyChord> {
+        self.chords.get(action)
+    }
+
+    /// Whether `action` was explicitly unbound, as opposed to never bound.
+    pub fn is_removed(&self, action: &str) -> bool {
+        self.removed.contains(action)
+    }
+
+    /// Apply `other` on top of this keymap. Its bindings replace ours and
+    /// the actions it explicitly unbound lose their binding here too.
     pub fn merge(&mut self, other: Keymap) {
+        for action in other
+            .bindings
+            .keys()
+            .chain(other.chords.keys())
+            .chain(&other.removed)
+        {
+            self.contexts.remove(action);
+            self.bindings.remove(action);
+            self.chords.remove(action);
+            self.removed.remove(action);
+        }
         self.bindings.extend(other.bindings);
+        self.chords.extend(other.chords);
+        self.contexts.extend(other.contexts);
+        self.removed.extend(other.removed);
     }
 
     pub fn set_binding(&mut self, action: impl Into<String>, combination: Option<KeyCombination>) {
         let action = action.into();
+        self.chords.remove(&action);
         if let Some(combination) = combination {
+            self.removed.remove(&action);
             self.bindings.insert(action, combination);
         } else {
+            self.contexts.remove(&action);
             self.bindings.remove(&action);
+            self.removed.insert(action);
         }
     }
 
+    /// Bind `action` to a chord, replacing any single combination, or unbind
+    /// it. A one-stroke chord is stored as a plain binding.
+    pub fn set_chord(&mut self, action: impl Into<String>, chord: Option<KeyChord>) {
+        let action = action.into();
+        match chord {
+            Some(chord) if chord.strokes.len() == 1 => {
+                let combination = chord.strokes.into_iter().next();
+                self.set_binding(action, combination);
+            }
+            Some(chord) => {
+                self.bindings.remove(&action);
+                self.removed.remove(&action);
+                self.chords.insert(action, chord);
+            }
+            None => self.set_binding(action, None),
+        }
+    }
+
+    /// Restrict the binding of `action` to a context, or lift the restriction.
+    pub fn set_when(&mut self, action: impl Into<String>, when: Option<String>) {
+        let action = action.into();
+        match when {
+            Some(when) => {
+                self.contexts.insert(action, when);
+            }
+            None => {
+                self.contexts.remove(&action);
+            }
+        }
+    }
+
+    /// The action bound to `event` outside of any `when` context.
+    ///
+    /// When several actions share the combination, the one with the
+    /// smallest action id wins, so the result does not depend on map order.
+    /// Contextual bindings are ignored; see [`Keymap::action_for_in_context`].
+    pub fn action_for(&self, event: &KeyEvent) -> Option<&str> {
+        self.action_for_in_context(event, &[])
+    }
+
+    /// The action bound to `event` while `contexts` are active.
+    ///
+    /// Bindings with a `when` context only match when it is one of
+    /// `contexts`; bindings without one always match. A contextual binding
+    /// wins over an unconditional one for the same combination.
+    pub fn action_for_in_context(&self, event: &KeyEvent, contexts: &[&str]) -> Option<&str> {
+        self.bindings
+            .iter()
+            .filter(|(_, combination)| combination.matches(event))
+            .filter_map(|(action, _)| match self.contexts.get(action) {
+                Some(when) if contexts.contains(&when.as_str()) => Some((true, action)),
+                Some(_) => None,
+                None => Some((false, action)),
+            })
+            // Prefer contextual bindings; break ties by name for determinism.
+            .min_by(|(a_ctx, a), (b_ctx, b)| b_ctx.cmp(a_ctx).then_with(|| a.cmp(b)))
+            .map(|(_, action)| action.as_str())
+    }
+
+    /// Feed one key event to the chord state machine.
+    ///
+    /// Returns [`ChordMatch::Pending`] while the strokes so far are a prefix
+    /// of some chord, and the bound action once one is complete. A stroke
+    /// that continues no chord ends the sequence with [`ChordMatch::None`];
+    /// it is not retried as the start of a new one. Outside a sequence,
+    /// single combinations resolve like [`Keymap::action_for`], with chord
+    /// prefixes taking precedence. Contextual bindings are ignored.
+    ///
+    /// The keymap does not track time: call [`Keymap::reset_chord`] when a
+    /// pending sequence should be abandoned.
+    pub fn match_chord(&mut self, event: &KeyEvent) -> ChordMatch {
+        let typed = self.pending.len();
+        let mut continues = false;
+        let mut completed: Option<&String> = None;
+        for (action, chord) in &self.chords {
+            if self.contexts.contains_key(action) || chord.strokes.len() <= typed {
+                continue;
+            }
+            let prefix_matches = chord
+                .strokes
+                .iter()
+                .zip(self.pending.iter().chain([event]))
+                .all(|(stroke, event)| stroke.matches(event));
+            if !prefix_matches {
+                continue;
+            }
+            if chord.strokes.len() == typed + 1 {
+                if completed.is_none_or(|current| action < current) {
+                    completed = Some(action);
+                }
+            } else {
+                continues = true;
+            }
+        }
+
+        if let Some(action) = completed {
+            let action = action.clone();
+            self.pending.clear();
+            return ChordMatch::Matched(action);
+        }
+        if continues {
+            self.pending.push(*event);
+            return ChordMatch::Pending;
+        }
+        if typed > 0 {
+            self.pending.clear();
+            return ChordMatch::None;
+        }
+        match self.action_for(event) {
+            Some(action) => ChordMatch::Matched(action.to_string()),
+            None => ChordMatch::None,
+        }
+    }
+
+    /// Abandon a partially typed chord.
+    pub fn reset_chord(&mut self) {
+        self.pending.clear();
+    }
+
     pub fn to_toml_string(&self) -> Result<String, toml::ser::Error> {
         let mut raw = RawKeymap::default();
         raw.bindings = self
             .bindings
             .iter()
-            .map(|(action, combo)| (action.clone(), combo.to_string()))
+            .map(|(action, combo)| (action, combo.to_string()))
+            .chain(
+                self.chords
+                    .iter()
+                    .map(|(action, chord)| (action, chord.to_string())),
+            )
+            .map(|(action, keys)| {
+                let binding = match self.contexts.get(action) {
+                    Some(when) => RawBinding::WithContext {
+                        keys,
+                        when: Some(when.clone()),
+                    },
+                    None => RawBinding::Keys(keys),
+                };
+                (action.clone(), binding)
+            })
             .collect();
+        raw.bindings.extend(
+            self.removed
+                .iter()
+                .map(|action| (action.clone(), RawBinding::Keys(String::new()))),
+        );
         toml::to_string(&raw)
     }
 
@@ -346,36 +603,142 @@ impl Keymap {
         Self::from_toml_str(&contents)
     }
 
+    /// Like [`Keymap::from_toml_str`], but rejects keymaps where two actions
+    /// share a combination with [`KeymapError::Conflict`]. See
+    /// [`Keymap::conflicts`].
+    pub fn from_toml_str_strict(toml_src: &str) -> Result<Self, KeymapError> {
+        let keymap = Self::from_toml_str(toml_src)?;
+        match keymap.conflicts().into_iter().next() {
+            Some((first, second, combination)) => Err(KeymapError::Conflict {
+                first,
+                second,
+                combination,
+            }),
+            None => Ok(keymap),
+        }
+    }
+
     pub fn from_toml_str(toml_src: &str) -> Result<Self, KeymapError> {
         let parsed: RawKeymap = toml::from_str(toml_src)?;
         let mut bindings = HashMap::new();
-
-        for (action, spec) in parsed.bindings.into_iter() {
-            let combination = KeyCombination::parse(&spec).map_err(|err| KeymapError::Parse {
+        let mut contexts = HashMap::new();
+        let mut chords = HashMap::new();
+        let mut removed = HashSet::new();
+
+        for (action, binding) in parsed.bindings.into_iter() {
+            let (spec, when) = match binding {
+                RawBinding::Keys(spec) => (spec, None),
+                RawBinding::WithContext { keys, when } => (keys, when),
+            };
+            if spec.trim().is_empty() {
+                removed.insert(action);
+                continue;
+            }
+            let mut chord = KeyChord::parse(&spec).map_err(|err| KeymapError::Parse {
                 action: action.clone(),
                 source: err,
             })?;
-            bindings.insert(action, combination);
+            if let Some(when) = when {
+                contexts.insert(action.clone(), when);
+            }
+            if chord.strokes.len() == 1 {
+                bindings.insert(action, chord.strokes.remove(0));
+            } else {
+                chords.insert(action, chord);
+            }
         }
 
-        Ok(Self { bindings })
+        Ok(Self {
+            bindings,
+            contexts,
+            chords,
+            pending: Vec::new(),
+            removed,
+        })
     }
 
     pub fn bindings(&self) -> &HashMap<String, KeyCombination> {
         &self.bindings
     }
+
+    /// Pairs of actions bound to the same combination in the same `when`
+    /// context, of which only one can ever fire, sorted by action id.
+    ///
+    /// A contextual binding does not conflict with an unconditional one: it
+    /// takes precedence inside its context only.
+    pub fn conflicts(&self) -> Vec<(String, String, KeyCombination)> {
+        let mut actions: Vec<_> = self.bindings.iter().collect();
+        actions.sort_by(|a, b| a.0.cmp(b.0));
+
+        let mut conflicts = Vec::new();
+        for (index, (first, combination)) in actions.iter().enumerate() {
+            for (second, other) in &actions[index + 1..] {
+                if combination == other && self.contexts.get(*first) == self.contexts.get(*second) {
+                    conflicts.push(((*first).clone(), (*second).clone(), (*combination).clone()));
+                }
+            }
+        }
+        conflicts
+    }
+}
+
+/// A keymap layered over a base keymap, e.g. workspace bindings over the
+/// global ones, without modifying either.
+#[derive(Debug, Clone, Default)]
+pub struct LayeredKeymap {
+    pub base: Keymap,
+    pub overrides: Keymap,
 }
 
+impl LayeredKeymap {
+    pub fn new(base: Keymap, overrides: Keymap) -> Self {
+        Self { base, overrides }
+    }
+
+    /// The combination `action` is bound to: the override binding if there
+    /// is one, nothing if the override layer unbinds the action (or binds it
+    /// to a chord), and the base binding otherwise.
+    pub fn binding(&self, action: &str) -> Option<&KeyCombination> {
+        if self.overrides.is_removed(action) || self.overrides.chord(action).is_some() {
+            return None;
+        }
+        self.overrides
+            .binding(action)
+            .or_else(|| self.base.binding(action))
+    }
+
+    /// Flatten the layers into a single keymap.
+    pub fn effective(&self) -> Keymap {
+        let mut keymap = self.base.clone();
+        keymap.merge(self.overrides.clone());
+        keymap.pending.clear();
+        keymap
+    }
+}
+
+/// On-disk form of a [`Keymap`]. Bindings are ordered by action id so
+/// saving the same keymap always produces the same file.
 #[derive(Debug, Deserialize, Serialize)]
 struct RawKeymap {
     #[serde(default)]
-    bindings: HashMap<String, String>,
+    bindings: BTreeMap<String, RawBinding>,
+}
+
+/// A binding is either a plain combination (`"Ctrl+S"`) or a table with the
+/// combination and the context it applies in
+/// (`{ keys = "Ctrl+S", when = "editorFocus" }`). An empty combination
+/// (`""`) explicitly unbinds the action.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+enum RawBinding {
+    Keys(String),
+    WithContext { keys: String, when: Option<String> },
 }
 
 impl Default for RawKeymap {
     fn default() -> Self {
         Self {
-            bindings: HashMap::new(),
+            bindings: BTreeMap::new(),
         }
     }
 }
@@ -389,6 +752,12 @@ pub enum KeymapError {
         action: String,
         source: ParseKeyCombinationError,
     },
+    /// Two actions share a combination (strict loading only).
+    Conflict {
+        first: String,
+        second: String,
+        combination: KeyCombination,
+    },
 }
 
 impl fmt::Display for KeymapError {
@@ -399,6 +768,15 @@ impl fmt::Display for KeymapError {
             Self::Parse { action, source } => {
                 write!(f, "Invalid binding for '{}': {}", action, source)
             }
+            Self::Conflict {
+                first,
+                second,
+                combination,
+            } => write!(
+                f,
+                "'{}' and '{}' are both bound to {}",
+                first, second, combination
+            ),
         }
     }
 }
@@ -409,6 +787,7 @@ impl std::error::Error for KeymapError {
             Self::Io(err) => Some(err),
             Self::Toml(err) => Some(err),
             Self::Parse { source, .. } => Some(source),
+            Self::Conflict { .. } => None,
         }
     }
 }
@@ -481,6 +860,23 @@ mod tests {
         assert_eq!(Key::parse("del").unwrap(), Key::Delete);
     }
 
+    #[test]
+    fn key_parsing_navigation() {
+        assert_eq!(Key::parse("home").unwrap(), Key::Home);
+        assert_eq!(Key::parse("END").unwrap(), Key::End);
+        assert_eq!(Key::parse("pageup").unwrap(), Key::PageUp);
+        assert_eq!(Key::parse("PgUp").unwrap(), Key::PageUp);
+        assert_eq!(Key::parse("pagedown").unwrap(), Key::PageDown);
+        assert_eq!(Key::parse("pgdn").unwrap(), Key::PageDown);
+        assert_eq!(Key::parse("insert").unwrap(), Key::Insert);
+        assert_eq!(Key::parse("ins").unwrap(), Key::Insert);
+
+        let combo = KeyCombination::parse("ctrl+home").unwrap();
+        assert_eq!(combo.key, Key::Home);
+        assert_eq!(combo.to_string(), "Ctrl+Home");
+        assert_eq!(KeyCombination::parse(&combo.to_string()).unwrap(), combo);
+    }
+
     #[test]
     fn key_parsing_errors() {
         assert!(matches!(
@@ -508,6 +904,7 @@ mod tests {
         assert_eq!(format!("{}", Key::ArrowUp), "ArrowUp");
         assert_eq!(format!("{}", Key::Backspace), "Backspace");
         assert_eq!(format!("{}", Key::Delete), "Delete");
+        assert_eq!(format!("{}", Key::PageDown), "PageDown");
     }
 
     #[test]
@@ -740,6 +1137,45 @@ mod tests {
         assert_eq!(binding.key, Key::Character('X'));
     }
 
+    #[test]
+    fn keymap_context_bindings() {
+        let keymap = Keymap::from_toml_str(
+            r#"
+[bindings]
+"editor.indent" = { keys = "tab", when = "editorFocus" }
+"palette.next" = { keys = "tab", when = "paletteOpen" }
+"focus.next" = "tab"
+"#,
+        )
+        .unwrap();
+        assert_eq!(keymap.when("editor.indent"), Some("editorFocus"));
+        assert_eq!(keymap.when("focus.next"), None);
+
+        let tab = KeyEvent::new(Key::Tab, false, false, false, false);
+        assert_eq!(
+            keymap.action_for_in_context(&tab, &["editorFocus"]),
+            Some("editor.indent")
+        );
+        assert_eq!(
+            keymap.action_for_in_context(&tab, &["paletteOpen"]),
+            Some("palette.next")
+        );
+        assert_eq!(
+            keymap.action_for_in_context(&tab, &["terminalFocus"]),
+            Some("focus.next")
+        );
+        let shift_tab = KeyEvent::new(Key::Tab, false, true, false, false);
+        assert_eq!(
+            keymap.action_for_in_context(&shift_tab, &["editorFocus"]),
+            None
+        );
+
+        // Contexts survive a round trip.
+        let reparsed = Keymap::from_toml_str(&keymap.to_toml_string().unwrap()).unwrap();
+        assert_eq!(reparsed.when("palette.next"), Some("paletteOpen"));
+        assert_eq!(reparsed.when("focus.next"), None);
+    }
+
     #[test]
     fn keymap_toml_parsing_errors() {
         let invalid_toml = "invalid toml content [";
@@ -796,6 +1232,240 @@ bindings = { "test.action" = "invalid+key+combination" }
         assert!(error_str.contains("Multiple keys specified"));
     }
 
+    #[test]
+    fn resolves_action_for_event() {
+        let keymap = Keymap::default();
+        let mac = cfg!(target_os = "macos");
+        let save = KeyEvent::new(Key::Character('S'), !mac, false, false, mac);
+        assert_eq!(keymap.action_for(&save), Some(SAVE_ACTION));
+        if !mac {
+            assert_eq!(keymap.action_for(&save), Some("file.save"));
+        }
+
+        let unbound = KeyEvent::new(Key::Character('Q'), true, true, true, false);
+        assert_eq!(keymap.action_for(&unbound), None);
+
+        let keymap = Keymap::from_toml_str(
+            r#"
+[bindings]
+"b.second" = "ctrl+k"
+"a.first" = "ctrl+k"
+"c.scoped" = { keys = "ctrl+l", when = "editorFocus" }
+"#,
+        )
+        .unwrap();
+        let ctrl = |c| KeyEvent::new(Key::Character(c), true, false, false, false);
+        assert_eq!(keymap.action_for(&ctrl('K')), Some("a.first"));
+        assert_eq!(keymap.action_for(&ctrl('L')), None);
+    }
+
+    #[test]
+    fn reports_conflicting_bindings() {
+        let toml = r#"
+[bindings]
+"editor.kill_line" = "ctrl+k"
+"workspace.open" = "Ctrl+K"
+"editor.peek" = { keys = "ctrl+k", when = "editorFocus" }
+"editor.save" = "ctrl+shift+k"
+"#;
+        let keymap = Keymap::from_toml_str(toml).unwrap();
+        let ctrl_k = KeyCombination::parse("ctrl+k").unwrap();
+        assert_eq!(
+            keymap.conflicts(),
+            vec![(
+                "editor.kill_line".to_string(),
+                "workspace.open".to_string(),
+                ctrl_k
+            )]
+        );
+
+        let err = Keymap::from_toml_str_strict(toml).unwrap_err();
+        assert!(matches!(err, KeymapError::Conflict { .. }));
+        assert!(err.to_string().contains("editor.kill_line"));
+
+        assert!(Keymap::default().conflicts().is_empty());
+        assert!(Keymap::from_toml_str_strict("[bindings]\nsave = \"ctrl+s\"\n").is_ok());
+    }
+
+    #[test]
+    fn matches_two_stroke_chord() {
+        let mut keymap = Keymap::from_toml_str(
+            r#"
+[bindings]
+"editor.comment" = "ctrl+k ctrl+c"
+"editor.uncomment" = "ctrl+k ctrl+u"
+"editor.save" = "ctrl+s"
+"#,
+        )
+        .unwrap();
+        let chord = keymap.chord("editor.comment").unwrap();
+        assert_eq!(chord.to_string(), "Ctrl+K Ctrl+C");
+        assert_eq!(KeyChord::parse(&chord.to_string()).unwrap(), *chord);
+        assert_eq!(KeyChord::parse("ctrl + k c").unwrap().strokes.len(), 2);
+
+        let ctrl = |c| KeyEvent::new(Key::Character(c), true, false, false, false);
+        assert_eq!(keymap.match_chord(&ctrl('K')), ChordMatch::Pending);
+        assert_eq!(
+            keymap.match_chord(&ctrl('C')),
+            ChordMatch::Matched("editor.comment".to_string())
+        );
+        assert_eq!(
+            keymap.match_chord(&ctrl('S')),
+            ChordMatch::Matched("editor.save".to_string())
+        );
+
+        assert_eq!(keymap.match_chord(&ctrl('K')), ChordMatch::Pending);
+        keymap.reset_chord();
+        assert_eq!(keymap.match_chord(&ctrl('U')), ChordMatch::None);
+
+        let reloaded = Keymap::from_toml_str(&keymap.to_toml_string().unwrap()).unwrap();
+        assert_eq!(
+            reloaded.chord("editor.uncomment"),
+            keymap.chord("editor.uncomment")
+        );
+    }
+
+    #[test]
+    fn mismatched_chord_stroke_resets_state() {
+        let mut keymap = Keymap::default();
+        keymap.set_chord(
+            "editor.comment",
+            Some(KeyChord::parse("ctrl+k ctrl+c").unwrap()),
+        );
+        let ctrl = |c| KeyEvent::new(Key::Character(c), true, false, false, false);
+
+        assert_eq!(keymap.match_chord(&ctrl('K')), ChordMatch::Pending);
+        assert_eq!(keymap.match_chord(&ctrl('X')), ChordMatch::None);
+        // The sequence starts over: a lone second stroke matches nothing.
+        assert_eq!(keymap.match_chord(&ctrl('C')), ChordMatch::None);
+        assert_eq!(keymap.match_chord(&ctrl('K')), ChordMatch::Pending);
+        assert_eq!(
+            keymap.match_chord(&ctrl('C')),
+            ChordMatch::Matched("editor.comment".to_string())
+        );
+    }
+
+    #[test]
+    fn serializes_in_stable_order() {
+        let mut keymap = Keymap::default();
+        keymap.set_binding(
+            "zeta.action",
+            Some(KeyCombination::parse("ctrl+z").unwrap()),
+        );
+        keymap.set_binding(
+            "alpha.action",
+            Some(KeyCombination::parse("ctrl+a").unwrap()),
+        );
+        let first = keymap.to_toml_string().unwrap();
+        let second = keymap.clone().to_toml_string().unwrap();
+        assert_eq!(first, second);
+
+        let reloaded = Keymap::from_toml_str(&first).unwrap();
+        assert_eq!(reloaded.to_toml_string().unwrap(), first);
+        let alpha = first.find("alpha.action").unwrap();
+        let zeta = first.find("zeta.action").unwrap();
+        assert!(alpha < zeta);
+    }
+
+    #[test]
+    fn preserves_unknown_actions() {
+        let mut keymap = Keymap::default();
+        keymap.merge(
+            Keymap::from_toml_str(
+                r#"
+[bindings]
+"my_plugin.do_thing" = "ctrl+alt+t"
+"my_plugin.scoped" = { keys = "ctrl+alt+y", when = "terminalFocus" }
+"#,
+            )
+            .unwrap(),
+        );
+
+        let saved = keymap.to_toml_string().unwrap();
+        let reloaded = Keymap::from_toml_str(&saved).unwrap();
+        assert_eq!(
+            reloaded.binding("my_plugin.do_thing"),
+            Some(&KeyCombination::parse("ctrl+alt+t").unwrap())
+        );
+        assert_eq!(reloaded.when("my_plugin.scoped"), Some("terminalFocus"));
+        assert_eq!(reloaded.bindings().len(), keymap.bindings().len());
+    }
+
+    #[test]
+    fn override_layer_wins() {
+        let mut overrides = Keymap::from_toml_str("[bindings]\n").unwrap();
+        overrides.set_binding(
+            SAVE_ACTION,
+            Some(KeyCombination::parse("ctrl+alt+s").unwrap()),
+        );
+        let layered = LayeredKeymap::new(Keymap::default(), overrides);
+
+        assert_eq!(
+            layered.binding(SAVE_ACTION),
+            Some(&KeyCombination::parse("ctrl+alt+s").unwrap())
+        );
+        assert_eq!(
+            layered.binding("sidebar.toggle"),
+            layered.base.binding("sidebar.toggle")
+        );
+        assert_eq!(layered.binding("unknown.action"), None);
+    }
+
+    #[test]
+    fn override_tombstone_masks_base() {
+        let mut overrides = Keymap::from_toml_str("[bindings]\n").unwrap();
+        assert!(!overrides.is_removed("sidebar.toggle"));
+        overrides.set_binding("sidebar.toggle", None);
+        assert!(overrides.is_removed("sidebar.toggle"));
+        let layered = LayeredKeymap::new(Keymap::default(), overrides);
+
+        assert!(layered.base.binding("sidebar.toggle").is_some());
+        assert_eq!(layered.binding("sidebar.toggle"), None);
+        assert_eq!(layered.effective().binding("sidebar.toggle"), None);
+
+        // Tombstones survive a save and reload.
+        let saved = layered.overrides.to_toml_string().unwrap();
+        assert!(saved.contains("\"sidebar.toggle\" = \"\""), "{saved}");
+        let reloaded = Keymap::from_toml_str(&saved).unwrap();
+        assert!(reloaded.is_removed("sidebar.toggle"));
+        assert_eq!(reloaded.binding("sidebar.toggle"), None);
+
+        // Rebinding lifts the tombstone.
+        let mut overrides = layered.overrides.clone();
+        overrides.set_binding("sidebar.toggle", Some(KeyCombination::parse("f9").unwrap()));
+        assert!(!overrides.is_removed("sidebar.toggle"));
+    }
+
+    #[test]
+    fn flattened_keymap_matches_layers() {
+        let mut overrides = Keymap::from_toml_str(
+            r#"
+[bindings]
+"file.save" = "ctrl+alt+s"
+"workspace.custom" = "ctrl+shift+u"
+"#,
+        )
+        .unwrap();
+        overrides.set_binding("terminal.toggle", None);
+        let layered = LayeredKeymap::new(Keymap::default(), overrides);
+        let effective = layered.effective();
+
+        let actions: HashSet<&String> = layered
+            .base
+            .bindings()
+            .keys()
+            .chain(layered.overrides.bindings().keys())
+            .collect();
+        for action in actions {
+            assert_eq!(
+                effective.binding(action),
+                layered.binding(action),
+                "{action}"
+            );
+        }
+        assert_eq!(effective.bindings().len(), layered.base.bindings().len());
+    }
+
     #[test]
     fn keymap_error_chaining() {
         let parse_error = ParseKeyCombinationError::UnknownKey("test".to_string());
@@ -1,4 +1,4 @@
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, HashMap};
 use std::fs;
 use std::io;
 use std::path::{Component, Path, PathBuf};
@@ -23,6 +23,8 @@ pub struct Makefile {
     pub name: String,
     pub path: PathBuf,
     pub files: Vec<MakefileItem>,
+    /// Rule targets in the order they first appear.
+    pub targets: Vec<MakefileTarget>,
 }
 
 /// A referenced file within a Makefile.
@@ -32,15 +34,61 @@ pub struct MakefileItem {
     pub full_path: PathBuf,
 }
 
+/// A rule target within a Makefile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MakefileTarget {
+    pub name: String,
+    /// Prerequisites with literal variables expanded; other references are
+    /// kept as written.
+    pub prerequisites: Vec<String>,
+    /// Recipe lines without their leading tab.
+    pub recipe: Vec<String>,
+    /// Whether the target is a pattern rule such as `%.o: %.c`.
+    pub is_pattern: bool,
+    /// Whether the target is declared `.PHONY`.
+    pub is_phony: bool,
+    /// The file the rule produces (what `$@` names), resolved against the
+    /// Makefile's directory. `None` for phony and pattern targets and when the
+    /// target does not look like a file.
+    pub output_path: Option<PathBuf>,
+}
+
 impl Makefile {
     /// Parse a Makefile from disk.
+    ///
+    /// Conditionals (`ifeq`, `ifdef`, ...) are not evaluated: every branch is
+    /// included. See [`Makefile::from_path_with_vars`].
     pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
         let path = path.as_ref();
-        let contents = fs::read_to_string(path).map_err(|source| MakefileError::Io {
-            path: path.to_path_buf(),
-            source,
-        })?;
+        let contents = read_makefile(path)?;
+        Self::parse(path, &contents, collect_variables(&contents))
+    }
+
+    /// Parse a Makefile from disk, evaluating `ifeq`/`ifneq`/`ifdef`/`ifndef`
+    /// conditionals against `vars` and the variables assigned literally
+    /// before them in included lines, with `vars` taking precedence like
+    /// command-line variables.
+    ///
+    /// Branches that evaluate false are skipped. Conditionals that cannot be
+    /// decided (computed variables, function calls) include their branch.
+    pub fn from_path_with_vars(
+        path: impl AsRef<Path>,
+        vars: &HashMap<String, String>,
+    ) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = read_makefile(path)?;
+
+        let contents = evaluate_conditionals(&contents, vars);
+
+        let mut variables = collect_variables(&contents);
+        variables.extend(
+            vars.iter()
+                .map(|(name, value)| (name.clone(), value.clone())),
+        );
+        Self::parse(path, &contents, variables)
+    }
 
+    fn parse(path: &Path, contents: &str, variables: HashMap<String, String>) -> Result<Self> {
         let name = path
             .file_name()
             .and_then(|name| name.to_str())
@@ -54,28 +102,43 @@ impl Makefile {
         let mut files = Vec::new();
         let mut seen = BTreeSet::new();
 
-        for token in extract_references(&contents) {
-            let include = PathBuf::from(&token);
+        let search_paths = collect_search_paths(contents, &variables);
+        let (references, wildcards) = extract_references(contents, &variables);
+        let expanded = wildcards
+            .iter()
+            .flat_map(|pattern| expand_wildcard(&base_dir, pattern));
+
+        for include in references.into_iter().map(PathBuf::from).chain(expanded) {
             if !seen.insert(include.clone()) {
                 continue;
             }
 
-            let full_path = resolve_path(&base_dir, &include);
-            match fs::metadata(&full_path) {
-                Ok(metadata) => {
-                    if metadata.is_file() {
-                        files.push(MakefileItem {
-                            include,
-                            full_path: normalize_path(&full_path),
-                        });
+            // Like make, fall back to the VPATH/vpath directories for
+            // files missing from the makefile directory.
+            let search_dirs = search_paths
+                .iter()
+                .filter(|search| include.is_relative() && search.matches(&include))
+                .flat_map(|search| &search.dirs)
+                .map(|dir| resolve_path(&base_dir, dir));
+            for directory in std::iter::once(base_dir.clone()).chain(search_dirs) {
+                let full_path = resolve_path(&directory, &include);
+                match fs::metadata(&full_path) {
+                    Ok(metadata) => {
+                        if metadata.is_file() {
+                            files.push(MakefileItem {
+                                include,
+                                full_path: normalize_path(&full_path),
+                            });
+                        }
+                        break;
                     }
-                }
-                Err(err) => {
-                    if err.kind() != io::ErrorKind::NotFound {
-                        return Err(MakefileError::Io {
-                            path: full_path,
-                            source: err,
-                        });
+                    Err(err) => {
+                        if err.kind() != io::ErrorKind::NotFound {
+                            return Err(MakefileError::Io {
+                                path: full_path,
+                                source: err,
+                            });
+                        }
                     }
                 }
             }
@@ -83,16 +146,228 @@ impl Makefile {
 
         files.sort_by(|a, b| a.include.cmp(&b.include));
 
+        let mut targets = extract_targets(contents, &variables);
+        for target in &mut targets {
+            if !target.is_phony && !target.is_pattern && looks_like_file(&base_dir, &target.name) {
+                target.output_path = Some(resolve_path(&base_dir, Path::new(&target.name)));
+            }
+        }
+
         Ok(Makefile {
             name,
             path: normalize_path(path),
             files,
+            targets,
         })
     }
 }
 
-fn extract_references(contents: &str) -> Vec<String> {
+fn read_makefile(path: &Path) -> Result<String> {
+    fs::read_to_string(path).map_err(|source| MakefileError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// Drops the lines of conditional branches that evaluate false, along with
+/// the conditional directives themselves.
+///
+/// Lines are read in order, like make does: a conditional sees the
+/// assignments included before it, never those in skipped branches or
+/// further down. Assignments to variables in `overrides` are ignored.
+fn evaluate_conditionals(contents: &str, overrides: &HashMap<String, String>) -> String {
+    struct Frame {
+        /// Whether the enclosing block is included.
+        outer: bool,
+        /// Whether the enclosing block is certainly included.
+        outer_certain: bool,
+        /// Whether the current branch is included.
+        active: bool,
+        /// Whether the current branch is certainly included.
+        certain: bool,
+        /// Whether an earlier branch was certainly taken.
+        taken: bool,
+        /// Whether an earlier branch may have been taken.
+        maybe_taken: bool,
+    }
+
+    let mut variables = overrides.clone();
+    let mut computed = BTreeSet::new();
+    let mut frames: Vec<Frame> = Vec::new();
+    let mut output = String::with_capacity(contents.len());
+    let state = |frames: &[Frame]| {
+        frames
+            .last()
+            .map_or((true, true), |frame| (frame.active, frame.certain))
+    };
+
+    for line in logical_lines(contents) {
+        let directive = strip_comment(&line).trim();
+        let (keyword, rest) = directive
+            .split_once(char::is_whitespace)
+            .map_or((directive, ""), |(keyword, rest)| (keyword, rest.trim()));
+
+        match keyword {
+            "ifeq" | "ifneq" | "ifdef" | "ifndef" => {
+                let (outer, outer_certain) = state(&frames);
+                let condition = evaluate_condition(keyword, rest, &variables, &computed);
+                frames.push(Frame {
+                    outer,
+                    outer_certain,
+                    active: outer && condition != Some(false),
+                    certain: outer_certain && condition == Some(true),
+                    taken: condition == Some(true),
+                    maybe_taken: condition != Some(false),
+                });
+            }
+            "else" if !frames.is_empty() => {
+                let condition = match rest.split_once(char::is_whitespace) {
+                    Some((keyword, rest)) => {
+                        evaluate_condition(keyword, rest.trim(), &variables, &computed)
+                    }
+                    None if rest.is_empty() => Some(true),
+                    None => evaluate_condition(rest, "", &variables, &computed),
+                };
+                let frame = frames.last_mut().expect("checked above");
+                frame.active = frame.outer && !frame.taken && condition != Some(false);
+                frame.certain =
+                    frame.outer_certain && !frame.maybe_taken && condition == Some(true);
+                frame.taken |= condition == Some(true);
+                frame.maybe_taken |= condition != Some(false);
+            }
+            "endif" if !frames.is_empty() => {
+                frames.pop();
+            }
+            _ => {
+                let (active, certain) = state(&frames);
+                if !active {
+                    continue;
+                }
+                output.push_str(&line);
+                output.push('\n');
+
+                let stripped = strip_comment(&line);
+                if stripped.starts_with('\t') {
+                    continue;
+                }
+                let Some((name, operator, value)) = split_assignment(stripped) else {
+                    continue;
+                };
+                if overrides.contains_key(name) {
+                    continue;
+                }
+                if certain {
+                    apply_assignment(&mut variables, &mut computed, name, operator, value);
+                } else {
+                    // The value depends on a conditional that cannot be
+                    // decided.
+                    variables.remove(name);
+                    computed.insert(name.to_string());
+                }
+            }
+        }
+    }
+
+    output
+}
+
+/// Evaluates one conditional; `None` when it cannot be decided.
+fn evaluate_condition(
+    keyword: &str,
+    arguments: &str,
+    variables: &HashMap<String, String>,
+    computed: &BTreeSet<String>,
+) -> Option<bool> {
+    // Variables never assigned expand to nothing, as in make.
+    let expand = |text: &str| -> Option<String> {
+        let expanded = expand_variables(text, variables);
+        if !expanded.contains('$') {
+            return Some(expanded);
+        }
+        let mut known = variables.clone();
+        let mut rest = expanded.as_str();
+        while let Some(start) = rest.find("$(").or_else(|| rest.find("${")) {
+            let close = if rest[start + 1..].starts_with('(') {
+                ')'
+            } else {
+                '}'
+            };
+            let end = rest[start + 2..].find(close)?;
+            let name = &rest[start + 2..start + 2 + end];
+            if computed.contains(name) || !is_variable_name(name) {
+                return None;
+            }
+            known.insert(name.to_string(), String::new());
+            rest = &rest[start + 3 + end..];
+        }
+        let expanded = expand_variables(&expanded, &known);
+        (!expanded.contains('$')).then_some(expanded)
+    };
+
+    match keyword {
+        "ifdef" | "ifndef" => {
+            let name = expand(arguments)?;
+            let name = name.trim();
+            if computed.contains(name) {
+                return None;
+            }
+            let defined = variables.get(name).is_some_and(|value| !value.is_empty());
+            Some(defined == (keyword == "ifdef"))
+        }
+        "ifeq" | "ifneq" => {
+            let (left, right) = split_comparison(arguments)?;
+            let equal = expand(left)?.trim() == expand(right)?.trim();
+            Some(equal == (keyword == "ifeq"))
+        }
+        _ => None,
+    }
+}
+
+/// Splits the arguments of `ifeq`/`ifneq` in either the `(a,b)` or the
+/// `"a" "b"` form.
+fn split_comparison(arguments: &str) -> Option<(&str, &str)> {
+    if let Some(inner) = arguments
+        .strip_prefix('(')
+        .and_then(|inner| inner.strip_suffix(')'))
+    {
+        // Split at the top-level comma, skipping those inside references.
+        let mut depth = 0usize;
+        for (index, ch) in inner.char_indices() {
+            match ch {
+                '(' | '{' => depth += 1,
+                ')' | '}' => depth = depth.saturating_sub(1),
+                ',' if depth == 0 => return Some((&inner[..index], &inner[index + 1..])),
+                _ => {}
+            }
+        }
+        return None;
+    }
+
+    let unquote = |text: &str| -> Option<(usize, usize)> {
+        let quote = text.chars().next().filter(|ch| matches!(ch, '"' | '\''))?;
+        let end = text[1..].find(quote)?;
+        Some((1, 1 + end))
+    };
+    let (start, end) = unquote(arguments)?;
+    let left = &arguments[start..end];
+    let rest = arguments[end + 1..].trim_start();
+    let (start, end) = unquote(rest)?;
+    Some((left, &rest[start..end]))
+}
+
+fn is_variable_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.contains(|ch: char| ch.is_whitespace() || matches!(ch, '$' | ':' | ','))
+}
+
+/// Collects plain file references and, separately, the patterns of
+/// `$(wildcard ...)` calls found in include directives.
+fn extract_references(
+    contents: &str,
+    variables: &HashMap<String, String>,
+) -> (Vec<String>, Vec<String>) {
     let mut references = Vec::new();
+    let mut wildcards = Vec::new();
 
     for line in logical_lines(contents) {
         let stripped = strip_comment(&line);
@@ -107,24 +382,474 @@ fn extract_references(contents: &str) -> Vec<String> {
         let trimmed = stripped.trim();
 
         if let Some(rest) = directive_arguments(trimmed) {
+            wildcards.extend(wildcard_patterns(rest));
             for token in rest.split_whitespace() {
-                if let Some(clean) = sanitize_token(token) {
-                    references.push(clean);
-                }
+                references.extend(sanitize_token(token, variables));
             }
             continue;
         }
 
         if let Some(rest) = split_after_separator(trimmed) {
             for token in rest.split_whitespace() {
-                if let Some(clean) = sanitize_token(token) {
-                    references.push(clean);
+                references.extend(sanitize_token(token, variables));
+            }
+        }
+    }
+
+    (references, wildcards)
+}
+
+/// Targets of rule lines with their prerequisites and the tab-indented
+/// recipe lines that follow. Special targets (`.PHONY`, `.SUFFIXES`, ...) are
+/// not listed; `.PHONY` marks the targets it names.
+fn extract_targets(contents: &str, variables: &HashMap<String, String>) -> Vec<MakefileTarget> {
+    let mut targets: Vec<MakefileTarget> = Vec::new();
+    let mut phony = BTreeSet::new();
+    // Indices of the targets the recipe lines being read belong to.
+    let mut current: Vec<usize> = Vec::new();
+
+    for line in logical_lines(contents) {
+        if let Some(command) = line.strip_prefix('\t') {
+            let command = command.trim();
+            if !command.is_empty() {
+                for &index in &current {
+                    targets[index].recipe.push(command.to_string());
                 }
             }
+            continue;
+        }
+
+        current.clear();
+        let stripped = strip_comment(&line);
+        if directive_arguments(stripped.trim()).is_some() {
+            continue;
+        }
+        let Some((names, prerequisites, inline_recipe)) = split_rule(stripped) else {
+            continue;
+        };
+        let prerequisites: Vec<String> = expand_variables(prerequisites, variables)
+            .split_whitespace()
+            .map(str::to_string)
+            .collect();
+
+        for name in names.split_whitespace() {
+            if name == ".PHONY" {
+                phony.extend(prerequisites.iter().cloned());
+                continue;
+            }
+            if name.starts_with('.') && name[1..].chars().all(|ch| ch.is_ascii_uppercase()) {
+                continue;
+            }
+            let names = if name.contains('%') {
+                vec![expand_variables(name, variables)]
+            } else {
+                sanitize_token(name, variables)
+            };
+            for name in names {
+                let index = match targets.iter().position(|target| target.name == name) {
+                    Some(index) => index,
+                    None => {
+                        targets.push(MakefileTarget {
+                            is_pattern: name.contains('%'),
+                            name,
+                            prerequisites: Vec::new(),
+                            recipe: Vec::new(),
+                            is_phony: false,
+                            output_path: None,
+                        });
+                        targets.len() - 1
+                    }
+                };
+                let target = &mut targets[index];
+                for prerequisite in &prerequisites {
+                    if !target.prerequisites.contains(prerequisite) {
+                        target.prerequisites.push(prerequisite.clone());
+                    }
+                }
+                if let Some(command) = inline_recipe {
+                    target.recipe.push(command.to_string());
+                }
+                current.push(index);
+            }
+        }
+    }
+
+    for target in &mut targets {
+        target.is_phony = phony.contains(&target.name);
+    }
+    targets
+}
+
+/// Values of variables assigned literally (`NAME = value`, `NAME := value`,
+/// `?=`, `+=`). Variables whose value references other variables or calls
+/// functions are left out so their uses stay unresolved.
+fn collect_variables(contents: &str) -> HashMap<String, String> {
+    let mut variables = HashMap::new();
+    let mut computed = BTreeSet::new();
+
+    for line in logical_lines(contents) {
+        let stripped = strip_comment(&line);
+        if stripped.starts_with('\t') {
+            continue;
+        }
+        if let Some((name, operator, value)) = split_assignment(stripped) {
+            apply_assignment(&mut variables, &mut computed, name, operator, value);
+        }
+    }
+
+    variables
+}
+
+/// Applies one assignment to the literal `variables`, moving the name to
+/// `computed` when its value is not literal.
+fn apply_assignment(
+    variables: &mut HashMap<String, String>,
+    computed: &mut BTreeSet<String>,
+    name: &str,
+    operator: &str,
+    value: &str,
+) {
+    let value = value.trim();
+    match operator {
+        "?=" if variables.contains_key(name) || computed.contains(name) => {}
+        "!=" => {
+            variables.remove(name);
+            computed.insert(name.to_string());
+        }
+        _ if value.contains('$') => {
+            variables.remove(name);
+            computed.insert(name.to_string());
+        }
+        "+=" if computed.contains(name) => {}
+        "+=" => {
+            let current: &mut String = variables.entry(name.to_string()).or_default();
+            if !current.is_empty() && !value.is_empty() {
+                current.push(' ');
+            }
+            current.push_str(value);
+        }
+        _ => {
+            computed.remove(name);
+            variables.insert(name.to_string(), value.to_string());
         }
     }
+}
 
-    references
+/// Directories make searches for prerequisites, from a `vpath` directive
+/// (limited to names matching its `%` pattern) or the `VPATH` variable.
+struct SearchPath {
+    pattern: Option<String>,
+    dirs: Vec<PathBuf>,
+}
+
+impl SearchPath {
+    fn matches(&self, include: &Path) -> bool {
+        let Some(pattern) = &self.pattern else {
+            return true;
+        };
+        let name = include.to_string_lossy();
+        match pattern.split_once('%') {
+            Some((prefix, suffix)) => {
+                name.len() >= prefix.len() + suffix.len()
+                    && name.starts_with(prefix)
+                    && name.ends_with(suffix)
+            }
+            None => name == pattern.as_str(),
+        }
+    }
+}
+
+/// `vpath` directives in order, followed by `VPATH`, which make consults last.
+fn collect_search_paths(contents: &str, variables: &HashMap<String, String>) -> Vec<SearchPath> {
+    let split_dirs = |value: &str| -> Vec<PathBuf> {
+        expand_variables(value, variables)
+            .split(|ch: char| ch == ':' || ch == ';' || ch.is_whitespace())
+            .filter(|dir| !dir.is_empty() && !dir.contains('$'))
+            .map(|dir| PathBuf::from(dir.replace('\\', "/")))
+            .collect()
+    };
+
+    let mut search_paths = Vec::new();
+    for line in logical_lines(contents) {
+        let stripped = strip_comment(&line);
+        if stripped.starts_with('\t') {
+            continue;
+        }
+        let Some(rest) = stripped.trim().strip_prefix("vpath") else {
+            continue;
+        };
+        if !rest.starts_with(char::is_whitespace) {
+            continue;
+        }
+        let rest = rest.trim_start();
+        let Some((pattern, dirs)) = rest.split_once(char::is_whitespace) else {
+            continue;
+        };
+        let dirs = split_dirs(dirs);
+        if !dirs.is_empty() {
+            search_paths.push(SearchPath {
+                pattern: Some(expand_variables(pattern, variables)),
+                dirs,
+            });
+        }
+    }
+
+    if let Some(vpath) = variables.get("VPATH") {
+        search_paths.push(SearchPath {
+            pattern: None,
+            dirs: split_dirs(vpath),
+        });
+    }
+    search_paths
+}
+
+/// Splits a variable assignment into its name, operator and value.
+fn split_assignment(line: &str) -> Option<(&str, &str, &str)> {
+    let equals = line.find('=')?;
+    let lhs = &line[..equals];
+    let (name, operator) = ["::", ":", "?", "+", "!"]
+        .iter()
+        .find_map(|prefix| {
+            lhs.strip_suffix(prefix)
+                .map(|name| (name, &line[equals - prefix.len()..=equals]))
+        })
+        .unwrap_or((lhs, "="));
+    let name = name.trim();
+    let name = name
+        .strip_prefix("override ")
+        .or_else(|| name.strip_prefix("export "))
+        .map_or(name, str::trim);
+    if name.is_empty() || name.contains(|ch: char| ch.is_whitespace() || matches!(ch, ':' | '$')) {
+        return None;
+    }
+    Some((name, operator, &line[equals + 1..]))
+}
+
+/// Substitutes `$(NAME)` and `${NAME}` references to known variables, one
+/// level deep. Unknown references are kept.
+fn expand_variables(token: &str, variables: &HashMap<String, String>) -> String {
+    let mut expanded = String::with_capacity(token.len());
+    let mut rest = token;
+    while let Some(start) = rest.find('$') {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let close = match after.chars().next() {
+            Some('(') => ')',
+            Some('{') => '}',
+            _ => {
+                expanded.push('$');
+                rest = after;
+                continue;
+            }
+        };
+        match after[1..]
+            .find(close)
+            .and_then(|end| variables.get(&after[1..1 + end]).map(|value| (end, value)))
+        {
+            Some((end, value)) => {
+                expanded.push_str(value);
+                rest = &after[end + 2..];
+            }
+            None => {
+                expanded.push('$');
+                rest = after;
+            }
+        }
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+/// Splits a rule line into its targets, prerequisites and the recipe given
+/// after `;`, if any. Variable assignments (`=`, `:=`, `::=`, `?=`, `+=`,
+/// `!=`) are not rules.
+fn split_rule(line: &str) -> Option<(&str, &str, Option<&str>)> {
+    let colon = line.find(':')?;
+    if line[..colon].contains('=') {
+        return None;
+    }
+    let rest = &line[colon + 1..];
+    let rest = rest.strip_prefix(':').unwrap_or(rest);
+    if rest.starts_with('=') || rest.starts_with(":=") {
+        return None;
+    }
+    let (prerequisites, recipe) = match rest.split_once(';') {
+        Some((prerequisites, recipe)) => (prerequisites, Some(recipe.trim())),
+        None => (rest, None),
+    };
+    // `target: VAR = value` sets a target-specific variable.
+    if prerequisites.contains('=') {
+        return None;
+    }
+    Some((
+        line[..colon].trim(),
+        prerequisites.trim(),
+        recipe.filter(|recipe| !recipe.is_empty()),
+    ))
+}
+
+/// Whether a target name denotes a file: it has a directory part or an
+/// extension, or a file of that name already exists.
+fn looks_like_file(base: &Path, name: &str) -> bool {
+    let path = Path::new(name);
+    name.contains('/')
+        || path
+            .extension()
+            .is_some_and(|ext| !ext.is_empty() && !name.starts_with('.'))
+        || resolve_path(base, path).is_file()
+}
+
+/// Patterns passed to `$(wildcard ...)` calls. Other make functions are not
+/// evaluated.
+fn wildcard_patterns(arguments: &str) -> Vec<String> {
+    let mut patterns = Vec::new();
+    let mut rest = arguments;
+    while let Some(start) = rest.find("$(wildcard") {
+        let call = &rest[start + "$(wildcard".len()..];
+        if !call.starts_with(char::is_whitespace) {
+            rest = call;
+            continue;
+        }
+        let Some(end) = call.find(')') else {
+            break;
+        };
+        patterns.extend(call[..end].split_whitespace().map(|p| p.replace('\\', "/")));
+        rest = &call[end + 1..];
+    }
+    patterns
+}
+
+/// Expands a make wildcard pattern relative to `base`, returning matching
+/// files as paths in the same form as the pattern, sorted.
+fn expand_wildcard(base: &Path, pattern: &str) -> Vec<PathBuf> {
+    let mut candidates = vec![PathBuf::new()];
+    let absolute = pattern.starts_with('/');
+
+    for segment in pattern.split('/').filter(|segment| !segment.is_empty()) {
+        let mut next = Vec::new();
+        for candidate in &candidates {
+            if !segment.contains(['*', '?', '[']) {
+                next.push(candidate.join(segment));
+                continue;
+            }
+
+            let directory = if absolute {
+                Path::new("/").join(candidate)
+            } else {
+                base.join(candidate)
+            };
+            let Ok(entries) = fs::read_dir(&directory) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let name = entry.file_name();
+                let Some(name) = name.to_str() else {
+                    continue;
+                };
+                if wildcard_matches(segment.as_bytes(), name.as_bytes()) {
+                    next.push(candidate.join(name));
+                }
+            }
+        }
+        candidates = next;
+    }
+
+    let mut matches: Vec<PathBuf> = candidates
+        .into_iter()
+        .filter(|candidate| !candidate.as_os_str().is_empty())
+        .map(|candidate| {
+            if absolute {
+                Path::new("/").join(candidate)
+            } else {
+                candidate
+            }
+        })
+        .filter(|candidate| resolve_path(base, candidate).exists())
+        .collect();
+    matches.sort();
+    matches
+}
+
+/// Matches a single path segment against a glob supporting `*`, `?` and
+/// bracket expressions. Like the shell, wildcards do not match a leading dot.
+fn wildcard_matches(pattern: &[u8], name: &[u8]) -> bool {
+    if name.first() == Some(&b'.') && pattern.first() != Some(&b'.') {
+        return false;
+    }
+
+    let (mut p, mut n) = (0usize, 0usize);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while n < name.len() {
+        let step = match pattern.get(p) {
+            Some(b'*') => {
+                backtrack = Some((p, n));
+                p += 1;
+                continue;
+            }
+            Some(b'?') => Some(1),
+            Some(b'[') => match_bracket(&pattern[p..], name[n]),
+            Some(&literal) => (literal == name[n]).then_some(1),
+            None => None,
+        };
+        match step {
+            Some(advance) => {
+                p += advance;
+                n += 1;
+            }
+            None => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    n = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&byte| byte == b'*')
+}
+
+/// Length of the bracket expression at the start of `pattern`, or 1 when it
+/// is unterminated and `[` is treated literally.
+fn bracket_len(pattern: &[u8]) -> usize {
+    let mut index = 1;
+    if matches!(pattern.get(index), Some(b'!' | b'^')) {
+        index += 1;
+    }
+    if pattern.get(index) == Some(&b']') {
+        index += 1;
+    }
+    match pattern[index.min(pattern.len())..]
+        .iter()
+        .position(|&byte| byte == b']')
+    {
+        Some(offset) => index + offset + 1,
+        None => 1,
+    }
+}
+
+fn match_bracket(pattern: &[u8], byte: u8) -> Option<usize> {
+    let len = bracket_len(pattern);
+    if len == 1 {
+        return (byte == b'[').then_some(1);
+    }
+    let mut body = &pattern[1..len - 1];
+    let negated = matches!(body.first(), Some(b'!' | b'^'));
+    if negated {
+        body = &body[1..];
+    }
+    let mut found = false;
+    let mut index = 0;
+    while index < body.len() {
+        if index + 2 < body.len() && body[index + 1] == b'-' {
+            found |= (body[index]..=body[index + 2]).contains(&byte);
+            index += 3;
+        } else {
+            found |= body[index] == byte;
+            index += 1;
+        }
+    }
+    (found != negated).then_some(len)
 }
 
 fn logical_lines(contents: &str) -> Vec<String> {
@@ -213,7 +938,19 @@ fn split_after_separator(line: &str) -> Option<&str> {
     None
 }
 
-fn sanitize_token(token: &str) -> Option<String> {
+/// Cleans a file token, expanding literal variables first. A variable may
+/// expand to several files.
+fn sanitize_token(token: &str, variables: &HashMap<String, String>) -> Vec<String> {
+    if token.contains('$') {
+        return expand_variables(token, variables)
+            .split_whitespace()
+            .filter_map(sanitize_word)
+            .collect();
+    }
+    sanitize_word(token).into_iter().collect()
+}
+
+fn sanitize_word(token: &str) -> Option<String> {
     let trimmed = token.trim_matches(|ch: char| matches!(ch, ';' | ',' | '\r' | '\n'));
     if trimmed.is_empty() {
         return None;
@@ -317,4 +1054,329 @@ mod tests {
         assert!(includes.contains("include/util.h"));
         assert!(includes.contains("config.mk"));
     }
+
+    #[test]
+    fn include_expands_wildcard() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+        for module in ["net", "ui", "core"] {
+            fs::create_dir_all(dir_path.join("modules").join(module)).unwrap();
+        }
+        fs::write(dir_path.join("modules/net/rules.mk"), "").unwrap();
+        fs::write(dir_path.join("modules/ui/rules.mk"), "").unwrap();
+        fs::write(dir_path.join("modules/core/other.mk"), "").unwrap();
+        fs::create_dir_all(dir_path.join("modules/.hidden")).unwrap();
+        fs::write(dir_path.join("modules/.hidden/rules.mk"), "").unwrap();
+
+        let makefile_path = dir_path.join("Makefile");
+        fs::write(
+            &makefile_path,
+            "include $(wildcard modules/*/rules.mk)\n-include $(wildcard missing/*.mk)\n",
+        )
+        .unwrap();
+
+        let parsed = Makefile::from_path(&makefile_path).unwrap();
+        let includes: Vec<_> = parsed
+            .files
+            .iter()
+            .map(|item| item.include.to_string_lossy().to_string())
+            .collect();
+        assert_eq!(
+            includes,
+            vec!["modules/net/rules.mk", "modules/ui/rules.mk"]
+        );
+    }
+
+    #[test]
+    fn expands_literal_variables() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+        fs::create_dir_all(dir_path.join("src")).unwrap();
+        fs::write(dir_path.join("src/a.c"), "").unwrap();
+        fs::write(dir_path.join("src/b.c"), "").unwrap();
+
+        let makefile_path = dir_path.join("Makefile");
+        fs::write(
+            &makefile_path,
+            "SRC := src\n\
+             SOURCES = src/a.c\n\
+             app: $(SOURCES) ${SRC}/b.c $(UNKNOWN)/c.c\n",
+        )
+        .unwrap();
+
+        let parsed = Makefile::from_path(&makefile_path).unwrap();
+        let includes: Vec<_> = parsed
+            .files
+            .iter()
+            .map(|item| item.include.to_string_lossy().to_string())
+            .collect();
+        assert_eq!(includes, vec!["src/a.c", "src/b.c"]);
+
+        let variables = collect_variables(
+            "A = one two\nA += three\nB ?= x\nB ?= y\nC != date\n\
+             D = $(wildcard *.c)\nE = $(A)\nE += more\n",
+        );
+        assert_eq!(variables["A"], "one two three");
+        assert_eq!(variables["B"], "x");
+        assert!(!variables.contains_key("C"));
+        assert!(!variables.contains_key("D"));
+        assert!(!variables.contains_key("E"));
+        assert_eq!(expand_variables("$(E)/x.c", &variables), "$(E)/x.c");
+    }
+
+    #[test]
+    fn resolves_files_through_vpath() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+        for sub in ["src", "lib", "include"] {
+            fs::create_dir_all(dir_path.join(sub)).unwrap();
+        }
+        fs::write(dir_path.join("src/main.c"), "").unwrap();
+        fs::write(dir_path.join("lib/util.c"), "").unwrap();
+        fs::write(dir_path.join("include/util.h"), "").unwrap();
+        fs::write(dir_path.join("local.c"), "").unwrap();
+
+        let makefile_path = dir_path.join("Makefile");
+        fs::write(
+            &makefile_path,
+            "VPATH = src:lib\n\
+             vpath %.h include\n\
+             app: main.c util.c util.h local.c missing.c\n",
+        )
+        .unwrap();
+
+        let parsed = Makefile::from_path(&makefile_path).unwrap();
+        let base = normalize_path(dir_path);
+        let resolved: Vec<_> = parsed
+            .files
+            .iter()
+            .map(|item| {
+                (
+                    item.include.to_string_lossy().to_string(),
+                    item.full_path.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            resolved,
+            vec![
+                ("local.c".to_string(), base.join("local.c")),
+                ("main.c".to_string(), base.join("src/main.c")),
+                ("util.c".to_string(), base.join("lib/util.c")),
+                ("util.h".to_string(), base.join("include/util.h")),
+            ]
+        );
+    }
+
+    #[test]
+    fn targets_resolve_output_paths() {
+        let dir = tempdir().unwrap();
+        let makefile_path = dir.path().join("Makefile");
+        fs::write(
+            &makefile_path,
+            "CC := cc\n\
+             .PHONY: all clean\n\
+             all: bin/app\n\
+             bin/app: obj/main.o obj/util.o\n\
+             \t$(CC) -o $@ $^\n\
+             obj/%.o: src/%.c\n\
+             \t$(CC) -c -o $@ $<\n\
+             libfoo.a:: obj/util.o\n\
+             clean:\n\
+             \trm -rf bin obj\n",
+        )
+        .unwrap();
+
+        let parsed = Makefile::from_path(&makefile_path).unwrap();
+        let base = normalize_path(dir.path());
+        let outputs: Vec<_> = parsed
+            .targets
+            .iter()
+            .map(|target| (target.name.as_str(), target.output_path.clone()))
+            .collect();
+        assert_eq!(
+            outputs,
+            vec![
+                ("all", None),
+                ("bin/app", Some(base.join("bin/app"))),
+                ("obj/%.o", None),
+                ("libfoo.a", Some(base.join("libfoo.a"))),
+                ("clean", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn evaluates_conditionals_with_vars() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+        for file in [
+            "common.c",
+            "linux.c",
+            "windows.c",
+            "debug.c",
+            "release.c",
+            "shell.c",
+        ] {
+            fs::write(dir_path.join(file), "").unwrap();
+        }
+        let makefile_path = dir_path.join("Makefile");
+        fs::write(
+            &makefile_path,
+            "DEBUG = 1\n\
+             HOST != uname\n\
+             SRCS = common.c\n\
+             ifeq ($(PLATFORM),linux)\n\
+             SRCS += linux.c\n\
+             else ifeq \"$(PLATFORM)\" \"windows\"\n\
+             SRCS += windows.c\n\
+             endif\n\
+             ifdef DEBUG\n\
+             SRCS += debug.c\n\
+             else\n\
+             SRCS += release.c\n\
+             endif\n\
+             ifeq ($(HOST),Darwin)\n\
+             SRCS += shell.c\n\
+             endif\n\
+             app: $(SRCS)\n",
+        )
+        .unwrap();
+        let includes = |makefile: Makefile| -> Vec<String> {
+            makefile
+                .files
+                .iter()
+                .map(|item| item.include.to_string_lossy().to_string())
+                .collect()
+        };
+
+        let vars = HashMap::from([("PLATFORM".to_string(), "linux".to_string())]);
+        let parsed = Makefile::from_path_with_vars(&makefile_path, &vars).unwrap();
+        // `HOST` is computed, so its branch is kept.
+        assert_eq!(
+            includes(parsed),
+            vec!["common.c", "debug.c", "linux.c", "shell.c"]
+        );
+
+        let vars = HashMap::from([
+            ("PLATFORM".to_string(), "windows".to_string()),
+            ("DEBUG".to_string(), String::new()),
+        ]);
+        let parsed = Makefile::from_path_with_vars(&makefile_path, &vars).unwrap();
+        assert_eq!(
+            includes(parsed),
+            vec!["common.c", "release.c", "shell.c", "windows.c"]
+        );
+
+        // Without evaluation every branch is included.
+        let parsed = Makefile::from_path(&makefile_path).unwrap();
+        assert_eq!(includes(parsed).len(), 6);
+    }
+
+    #[test]
+    fn conditionals_follow_assignment_order() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+        for file in ["main.c", "win_main.c", "posix_main.c", "late.c"] {
+            fs::write(dir_path.join(file), "").unwrap();
+        }
+        let makefile_path = dir_path.join("Makefile");
+        fs::write(
+            &makefile_path,
+            "SRCS = main.c\n\
+             ifeq ($(OS),Windows)\n\
+             EXT = .exe\n\
+             else\n\
+             EXT = .bin\n\
+             endif\n\
+             ifeq ($(EXT),.exe)\n\
+             SRCS += win_main.c\n\
+             else\n\
+             SRCS += posix_main.c\n\
+             endif\n\
+             ifeq ($(LATE),yes)\n\
+             SRCS += late.c\n\
+             endif\n\
+             LATE = yes\n\
+             app: $(SRCS)\n",
+        )
+        .unwrap();
+        let includes = |vars: &HashMap<String, String>| -> Vec<String> {
+            Makefile::from_path_with_vars(&makefile_path, vars)
+                .unwrap()
+                .files
+                .iter()
+                .map(|item| item.include.to_string_lossy().to_string())
+                .collect()
+        };
+
+        let windows = HashMap::from([("OS".to_string(), "Windows".to_string())]);
+        assert_eq!(includes(&windows), vec!["main.c", "win_main.c"]);
+        assert_eq!(includes(&HashMap::new()), vec!["main.c", "posix_main.c"]);
+    }
+
+    #[test]
+    fn captures_rules_with_recipes() {
+        let dir = tempdir().unwrap();
+        let makefile_path = dir.path().join("Makefile");
+        fs::write(
+            &makefile_path,
+            "OBJS = main.o util.o\n\
+             .PHONY: all\n\
+             all: app\n\
+             \n\
+             app: $(OBJS)\n\
+             \t$(CC) -o $@ $^\n\
+             \n\
+             \t@echo linked # shell comment\n\
+             %.o: %.c config.h\n\
+             \t$(CC) -c \\\n\
+             \t  -o $@ $<\n\
+             app: VERBOSE = 1\n\
+             install: app ; cp app /usr/local/bin\n",
+        )
+        .unwrap();
+
+        let parsed = Makefile::from_path(&makefile_path).unwrap();
+        let target = |name: &str| {
+            parsed
+                .targets
+                .iter()
+                .find(|target| target.name == name)
+                .unwrap()
+        };
+
+        let all = target("all");
+        assert!(all.is_phony);
+        assert!(!all.is_pattern);
+        assert_eq!(all.prerequisites, vec!["app"]);
+        assert!(all.recipe.is_empty());
+
+        let app = target("app");
+        assert!(!app.is_phony);
+        assert_eq!(app.prerequisites, vec!["main.o", "util.o"]);
+        assert_eq!(
+            app.recipe,
+            vec!["$(CC) -o $@ $^", "@echo linked # shell comment"]
+        );
+
+        let pattern = target("%.o");
+        assert!(pattern.is_pattern);
+        assert_eq!(pattern.prerequisites, vec!["%.c", "config.h"]);
+        assert_eq!(pattern.recipe, vec!["$(CC) -c -o $@ $<"]);
+        assert_eq!(pattern.output_path, None);
+
+        assert_eq!(target("install").recipe, vec!["cp app /usr/local/bin"]);
+        assert_eq!(parsed.targets.len(), 4);
+    }
+
+    #[test]
+    fn wildcard_segment_matching() {
+        assert!(wildcard_matches(b"*.mk", b"rules.mk"));
+        assert!(wildcard_matches(b"r?les.*", b"rules.mk"));
+        assert!(wildcard_matches(b"[a-c]*", b"core"));
+        assert!(!wildcard_matches(b"[!a-c]*", b"core"));
+        assert!(!wildcard_matches(b"*.mk", b".hidden.mk"));
+        assert!(!wildcard_matches(b"*.mk", b"rules.mak"));
+    }
 }
@@ -142,6 +142,21 @@ impl ProjectIndexer for MakefileIndexer {
     fn root_dir(&self) -> &Path {
         &self.root_dir
     }
+
+    fn project_files(&self, path: &Path) -> Result<Vec<PathBuf>> {
+        let makefile = Makefile::from_path(path)
+            .map_err(|e| SymbolError::ProjectError(format!("Failed to parse Makefile: {}", e)))?;
+
+        let mut files = Vec::new();
+        for item in &makefile.files {
+            let path = &item.full_path;
+            let is_code = is_header_file(path) || is_source_file(path);
+            if is_code && path.exists() && !files.contains(path) {
+                files.push(path.clone());
+            }
+        }
+        Ok(files)
+    }
 }
 
 /// Check if a path is a C/C++ header file
@@ -273,6 +288,21 @@ void init_utils(void) {}
         assert!(!index.find_definition("Point").is_empty());
     }
 
+    #[test]
+    fn test_makefile_index_project() {
+        let temp_dir = TempDir::new().unwrap();
+        let makefile_path = create_test_makefile(temp_dir.path());
+
+        let indexer = MakefileIndexer::from_path(&makefile_path).unwrap();
+        let files = indexer.project_files(&makefile_path).unwrap();
+        assert!(files.contains(&temp_dir.path().join("utils.h")));
+        assert!(files.contains(&temp_dir.path().join("main.c")));
+
+        let mut index = SymbolIndex::new();
+        indexer.index_project(&makefile_path, &mut index).unwrap();
+        assert!(!index.find_definition("Utils").is_empty());
+    }
+
     #[test]
     fn test_is_header_file() {
         assert!(is_header_file(Path::new("foo.h")));
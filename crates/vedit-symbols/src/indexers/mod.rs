@@ -10,7 +10,7 @@ mod vs;
 mod makefile;
 
 use crate::{Result, SymbolIndex};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// Trait for indexing symbols from different project types
 ///
@@ -37,6 +37,35 @@ pub trait ProjectIndexer {
 
     /// Get the project root directory
     fn root_dir(&self) -> &Path;
+
+    /// Load the project at `path` and list the C/C++ source and header files
+    /// it references
+    fn project_files(&self, path: &Path) -> Result<Vec<PathBuf>>;
+
+    /// Index every file of the project at `path`
+    ///
+    /// Files are enumerated by [`ProjectIndexer::project_files`]; files that
+    /// cannot be read or parsed are skipped with a warning. Fails only when
+    /// the project itself cannot be loaded.
+    fn index_project(&self, path: &Path, index: &mut SymbolIndex) -> Result<()> {
+        for file in self.project_files(path)? {
+            if !index.needs_reindex(&file) {
+                continue;
+            }
+            match std::fs::read_to_string(&file) {
+                Ok(content) => match index.index_file(&file, &content) {
+                    Err(e) if !e.to_string().contains("Skipping") => {
+                        eprintln!("Warning: Failed to index {}: {}", file.display(), e);
+                    }
+                    _ => {}
+                },
+                Err(e) => {
+                    eprintln!("Warning: Failed to read {}: {}", file.display(), e);
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
 #[cfg(feature = "vs")]
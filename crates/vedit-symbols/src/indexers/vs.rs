@@ -6,7 +6,7 @@
 use crate::indexers::ProjectIndexer;
 use crate::{Result, SymbolError, SymbolIndex};
 use std::path::{Path, PathBuf};
-use vedit_vs::{Solution, VcxItemKind};
+use vedit_vs::{Solution, VcxItemKind, VcxProject};
 
 /// Indexer for Visual Studio solutions
 ///
@@ -147,6 +147,35 @@ impl ProjectIndexer for VsSolutionIndexer {
     fn root_dir(&self) -> &Path {
         self.solution_path.parent().unwrap_or(Path::new("."))
     }
+
+    /// Accepts a `.sln` (all loaded projects) or a single `.vcxproj`
+    fn project_files(&self, path: &Path) -> Result<Vec<PathBuf>> {
+        let is_vcxproj = path
+            .extension()
+            .map(|ext| ext.eq_ignore_ascii_case("vcxproj"))
+            .unwrap_or(false);
+        let projects = if is_vcxproj {
+            vec![VcxProject::from_path(path).map_err(|e| {
+                SymbolError::ProjectError(format!("Failed to parse project: {}", e))
+            })?]
+        } else {
+            Solution::from_path(path)
+                .map_err(|e| SymbolError::ProjectError(format!("Failed to parse solution: {}", e)))?
+                .projects
+                .into_iter()
+                .filter_map(|project| project.project)
+                .collect()
+        };
+
+        let mut files = Vec::new();
+        for item in projects.iter().flat_map(|vcx| &vcx.files) {
+            let is_code = matches!(item.kind, VcxItemKind::Source | VcxItemKind::Header);
+            if is_code && item.full_path.exists() && !files.contains(&item.full_path) {
+                files.push(item.full_path.clone());
+            }
+        }
+        Ok(files)
+    }
 }
 
 #[cfg(test)]
@@ -202,6 +231,38 @@ struct TestStruct {
         sln_path
     }
 
+    #[test]
+    fn test_vs_index_project() {
+        let temp_dir = TempDir::new().unwrap();
+        let sln_path = create_test_solution(temp_dir.path());
+        let proj_dir = temp_dir.path().join("TestProject");
+        let vcxproj = std::fs::read_to_string(proj_dir.join("TestProject.vcxproj"))
+            .unwrap()
+            .replace(
+                r#"<ClInclude Include="test.h" />"#,
+                r#"<ClInclude Include="test.h" />
+    <ClCompile Include="impl.cpp" />"#,
+            );
+        std::fs::write(proj_dir.join("TestProject.vcxproj"), vcxproj).unwrap();
+        std::fs::write(
+            proj_dir.join("impl.cpp"),
+            "class Widget {\n    int id;\n};\n",
+        )
+        .unwrap();
+
+        let indexer = VsSolutionIndexer::from_path(&sln_path).unwrap();
+        let files = indexer.project_files(&sln_path).unwrap();
+        assert_eq!(files.len(), 2);
+
+        let mut index = SymbolIndex::new();
+        indexer.index_project(&sln_path, &mut index).unwrap();
+        assert_eq!(index.find_definition("TestStruct").len(), 1);
+        assert_eq!(index.find_definition("Widget").len(), 1);
+
+        let missing = temp_dir.path().join("Missing.sln");
+        assert!(indexer.index_project(&missing, &mut index).is_err());
+    }
+
     #[test]
     fn test_vs_solution_indexer_creation() {
         let temp_dir = TempDir::new().unwrap();
@@ -1,4 +1,5 @@
 use std::fmt;
+use std::path::Path;
 
 /// Programming languages the editor can recognize.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -53,6 +54,81 @@ pub enum Language {
 }
 
 impl Language {
+    /// Detect the language of a file from its name, falling back to
+    /// [`Language::PlainText`] when it is not recognized.
+    ///
+    /// Well-known file names (`Makefile`, `Dockerfile`, `CMakeLists.txt`)
+    /// take precedence over the extension; both compare case-insensitively.
+    pub fn from_path(path: &Path) -> Language {
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+            return Self::PlainText;
+        };
+        match name.to_ascii_lowercase().as_str() {
+            "makefile" | "gnumakefile" => return Self::Makefile,
+            "dockerfile" => return Self::Dockerfile,
+            "cmakelists.txt" => return Self::CMake,
+            _ => {}
+        }
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(Self::from_extension)
+            .unwrap_or(Self::PlainText)
+    }
+
+    /// Language of files with extension `ext` (without the leading dot,
+    /// compared case-insensitively).
+    pub fn from_extension(ext: &str) -> Option<Language> {
+        let language = match ext.to_ascii_lowercase().as_str() {
+            "rs" => Self::Rust,
+            "c" => Self::C,
+            "h" => Self::CHeader,
+            "hh" | "hpp" | "hxx" | "h++" | "inl" | "ipp" => Self::CppHeader,
+            "cpp" | "cc" | "cxx" | "c++" => Self::Cpp,
+            "m" => Self::ObjectiveC,
+            "mm" => Self::ObjectiveCpp,
+            "swift" => Self::Swift,
+            "java" => Self::Java,
+            "kt" | "kts" => Self::Kotlin,
+            "cs" => Self::CSharp,
+            "go" => Self::Go,
+            "py" | "pyi" => Self::Python,
+            "rb" => Self::Ruby,
+            "php" => Self::Php,
+            "hs" => Self::Haskell,
+            "erl" | "hrl" => Self::Erlang,
+            "ex" | "exs" => Self::Elixir,
+            "js" | "mjs" | "cjs" => Self::JavaScript,
+            "jsx" => Self::Jsx,
+            "ts" | "mts" | "cts" => Self::TypeScript,
+            "tsx" => Self::Tsx,
+            "json" => Self::Json,
+            "toml" => Self::Toml,
+            "yaml" | "yml" => Self::Yaml,
+            "ini" => Self::Ini,
+            "md" | "markdown" => Self::Markdown,
+            "sql" => Self::Sql,
+            "html" | "htm" => Self::Html,
+            "css" => Self::Css,
+            "scss" | "sass" => Self::Scss,
+            "less" => Self::Less,
+            "lua" => Self::Lua,
+            "zig" => Self::Zig,
+            "dart" => Self::Dart,
+            "scala" => Self::Scala,
+            "sh" | "bash" => Self::Shell,
+            "fish" => Self::Fish,
+            "ps1" => Self::PowerShell,
+            "bat" | "cmd" => Self::Batch,
+            "vue" => Self::Vue,
+            "svelte" => Self::Svelte,
+            "mk" => Self::Makefile,
+            "cmake" => Self::CMake,
+            "nix" => Self::Nix,
+            _ => return None,
+        };
+        Some(language)
+    }
+
     /// Human friendly label.
     pub fn display_name(self) -> &'static str {
         match self {
@@ -105,6 +181,130 @@ impl Language {
             Self::Nix => "Nix",
         }
     }
+
+    /// C and its preprocessor-based descendants, sources and headers alike.
+    pub fn is_c_like(self) -> bool {
+        matches!(
+            self,
+            Self::C
+                | Self::CHeader
+                | Self::Cpp
+                | Self::CppHeader
+                | Self::ObjectiveC
+                | Self::ObjectiveCpp
+        )
+    }
+
+    /// Languages of web front ends: scripts, markup, style sheets and
+    /// single-file components.
+    pub fn is_web(self) -> bool {
+        matches!(
+            self,
+            Self::JavaScript
+                | Self::Jsx
+                | Self::TypeScript
+                | Self::Tsx
+                | Self::Html
+                | Self::Css
+                | Self::Scss
+                | Self::Less
+                | Self::Vue
+                | Self::Svelte
+        )
+    }
+
+    /// Data formats used for configuration files.
+    pub fn is_config(self) -> bool {
+        matches!(self, Self::Json | Self::Toml | Self::Yaml | Self::Ini)
+    }
+
+    /// Token starting a comment that runs to the end of the line, if the
+    /// language has one.
+    pub fn line_comment_prefix(self) -> Option<&'static str> {
+        match self {
+            Self::Rust
+            | Self::C
+            | Self::CHeader
+            | Self::Cpp
+            | Self::CppHeader
+            | Self::ObjectiveC
+            | Self::ObjectiveCpp
+            | Self::Swift
+            | Self::Java
+            | Self::Kotlin
+            | Self::CSharp
+            | Self::Go
+            | Self::Php
+            | Self::JavaScript
+            | Self::Jsx
+            | Self::TypeScript
+            | Self::Tsx
+            | Self::Scss
+            | Self::Less
+            | Self::Zig
+            | Self::Dart
+            | Self::Scala => Some("//"),
+            Self::Python
+            | Self::Ruby
+            | Self::Elixir
+            | Self::Toml
+            | Self::Yaml
+            | Self::Shell
+            | Self::Fish
+            | Self::PowerShell
+            | Self::Makefile
+            | Self::Dockerfile
+            | Self::CMake
+            | Self::Nix => Some("#"),
+            Self::Haskell | Self::Sql | Self::Lua => Some("--"),
+            Self::Erlang => Some("%"),
+            Self::Ini => Some(";"),
+            Self::Batch => Some("REM"),
+            Self::PlainText
+            | Self::Json
+            | Self::Markdown
+            | Self::Html
+            | Self::Css
+            | Self::Vue
+            | Self::Svelte => None,
+        }
+    }
+
+    /// Opening and closing tokens of a block comment, if the language has
+    /// them.
+    pub fn block_comment(self) -> Option<(&'static str, &'static str)> {
+        match self {
+            Self::Rust
+            | Self::C
+            | Self::CHeader
+            | Self::Cpp
+            | Self::CppHeader
+            | Self::ObjectiveC
+            | Self::ObjectiveCpp
+            | Self::Swift
+            | Self::Java
+            | Self::Kotlin
+            | Self::CSharp
+            | Self::Go
+            | Self::Php
+            | Self::JavaScript
+            | Self::Jsx
+            | Self::TypeScript
+            | Self::Tsx
+            | Self::Css
+            | Self::Scss
+            | Self::Less
+            | Self::Dart
+            | Self::Scala
+            | Self::Sql
+            | Self::Nix => Some(("/*", "*/")),
+            Self::Html | Self::Markdown | Self::Vue | Self::Svelte => Some(("<!--", "-->")),
+            Self::Haskell => Some(("{-", "-}")),
+            Self::Lua => Some(("--[[", "]]")),
+            Self::PowerShell => Some(("<#", "#>")),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for Language {
@@ -133,6 +333,103 @@ mod tests {
         assert_eq!(Language::Tsx.display_name(), "TypeScript JSX");
     }
 
+    #[test]
+    fn comment_tokens() {
+        assert_eq!(Language::Rust.line_comment_prefix(), Some("//"));
+        assert_eq!(Language::Python.line_comment_prefix(), Some("#"));
+        assert_eq!(Language::Lua.line_comment_prefix(), Some("--"));
+        assert_eq!(Language::Html.line_comment_prefix(), None);
+        assert_eq!(Language::Html.block_comment(), Some(("<!--", "-->")));
+        assert_eq!(Language::Css.block_comment(), Some(("/*", "*/")));
+        assert_eq!(Language::Python.block_comment(), None);
+        assert_eq!(Language::Json.line_comment_prefix(), None);
+        assert_eq!(Language::Json.block_comment(), None);
+    }
+
+    #[test]
+    fn detects_language_from_path() {
+        let extensions = [
+            ("rs", Language::Rust),
+            ("c", Language::C),
+            ("h", Language::CHeader),
+            ("cpp", Language::Cpp),
+            ("cc", Language::Cpp),
+            ("hpp", Language::CppHeader),
+            ("inl", Language::CppHeader),
+            ("m", Language::ObjectiveC),
+            ("mm", Language::ObjectiveCpp),
+            ("swift", Language::Swift),
+            ("java", Language::Java),
+            ("kt", Language::Kotlin),
+            ("cs", Language::CSharp),
+            ("go", Language::Go),
+            ("py", Language::Python),
+            ("rb", Language::Ruby),
+            ("php", Language::Php),
+            ("hs", Language::Haskell),
+            ("erl", Language::Erlang),
+            ("exs", Language::Elixir),
+            ("js", Language::JavaScript),
+            ("jsx", Language::Jsx),
+            ("ts", Language::TypeScript),
+            ("tsx", Language::Tsx),
+            ("json", Language::Json),
+            ("toml", Language::Toml),
+            ("yml", Language::Yaml),
+            ("ini", Language::Ini),
+            ("md", Language::Markdown),
+            ("sql", Language::Sql),
+            ("htm", Language::Html),
+            ("css", Language::Css),
+            ("scss", Language::Scss),
+            ("less", Language::Less),
+            ("lua", Language::Lua),
+            ("zig", Language::Zig),
+            ("dart", Language::Dart),
+            ("scala", Language::Scala),
+            ("sh", Language::Shell),
+            ("fish", Language::Fish),
+            ("ps1", Language::PowerShell),
+            ("bat", Language::Batch),
+            ("vue", Language::Vue),
+            ("svelte", Language::Svelte),
+            ("cmake", Language::CMake),
+            ("nix", Language::Nix),
+        ];
+        for (ext, language) in extensions {
+            assert_eq!(Language::from_extension(ext), Some(language), "{ext}");
+            let path = format!("dir/file.{}", ext.to_ascii_uppercase());
+            assert_eq!(Language::from_path(Path::new(&path)), language, "{path}");
+        }
+
+        let names = [
+            ("Makefile", Language::Makefile),
+            ("src/makefile", Language::Makefile),
+            ("Dockerfile", Language::Dockerfile),
+            ("CMakeLists.txt", Language::CMake),
+            ("notes.txt", Language::PlainText),
+            ("archive.unknown", Language::PlainText),
+            ("README", Language::PlainText),
+        ];
+        for (name, language) in names {
+            assert_eq!(Language::from_path(Path::new(name)), language, "{name}");
+        }
+        assert_eq!(Language::from_extension("txt"), None);
+    }
+
+    #[test]
+    fn classifies_languages() {
+        assert!(Language::Cpp.is_c_like());
+        assert!(Language::ObjectiveC.is_c_like());
+        assert!(!Language::Rust.is_c_like());
+        assert!(Language::Tsx.is_web());
+        assert!(!Language::Python.is_web());
+        assert!(Language::Toml.is_config());
+        assert!(!Language::Markdown.is_config());
+        assert_eq!(Language::Python.line_comment_prefix(), Some("#"));
+        assert_eq!(Language::Ini.line_comment_prefix(), Some(";"));
+    }
+
     #[test]
     fn language_all_unique() {
         use std::collections::HashSet;
@@ -1,6 +1,10 @@
+use std::borrow::Cow;
 use std::fmt;
-use std::ops::{Bound, RangeBounds};
-use std::sync::Arc;
+use std::io::{self, Read};
+use std::ops::{Bound, Range, RangeBounds};
+use std::sync::{Arc, OnceLock};
+
+use unicode_segmentation::{GraphemeCursor, GraphemeIncomplete, UnicodeSegmentation};
 
 /// Source identifier for a [`Piece`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -31,6 +35,101 @@ impl Piece {
     }
 }
 
+/// A recorded edit: `removed` was replaced by `inserted` at `offset`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Edit {
+    offset: usize,
+    removed: String,
+    inserted: String,
+}
+
+/// Undo and redo stacks of edit groups, each undone as a unit.
+#[derive(Debug, Clone, Default)]
+struct History {
+    undo: Vec<Vec<Edit>>,
+    redo: Vec<Vec<Edit>>,
+    /// Whether the last undo group is a run of typed characters that the
+    /// next adjacent single-character insert may extend.
+    typing: bool,
+}
+
+impl History {
+    fn record(&mut self, edit: Edit) {
+        self.redo.clear();
+        let typed = edit.removed.is_empty() && edit.inserted.chars().count() == 1;
+        if typed
+            && self.typing
+            && let Some(last) = self.undo.last_mut().and_then(|group| group.last_mut())
+            && last.offset + last.inserted.len() == edit.offset
+        {
+            last.inserted.push_str(&edit.inserted);
+            return;
+        }
+        self.undo.push(vec![edit]);
+        self.typing = typed;
+    }
+
+    fn record_group(&mut self, group: Vec<Edit>) {
+        self.redo.clear();
+        self.undo.push(group);
+        self.typing = false;
+    }
+}
+
+/// Saved contents of a [`TextBuffer`], see [`TextBuffer::snapshot`].
+#[derive(Debug, Clone)]
+pub struct BufferSnapshot {
+    original: Arc<str>,
+    added: String,
+    pieces: Vec<Piece>,
+    len: usize,
+    newlines: usize,
+}
+
+impl BufferSnapshot {
+    /// Length of the saved contents in bytes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// Document statistics computed by [`TextBuffer::stats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TextStats {
+    pub bytes: usize,
+    pub chars: usize,
+    /// Extended grapheme clusters, i.e. user-perceived characters.
+    pub graphemes: usize,
+    /// Number of lines, counting the (possibly empty) line after the last `\n`.
+    pub lines: usize,
+    /// Runs of non-whitespace characters.
+    pub words: usize,
+}
+
+/// Why a fallible edit such as [`TextBuffer::try_insert`] was rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditError {
+    /// The buffer was marked with [`TextBuffer::read_only`].
+    ReadOnly,
+    /// The offset or range lies past the end of the buffer.
+    OutOfBounds,
+}
+
+impl fmt::Display for EditError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EditError::ReadOnly => f.write_str("buffer is read-only"),
+            EditError::OutOfBounds => f.write_str("edit out of bounds"),
+        }
+    }
+}
+
+impl std::error::Error for EditError {}
+
 /// Text buffer implementation inspired by VS Code's piece table.
 ///
 /// Instead of copying and reallocating the entire document on each edit, the
@@ -44,6 +143,13 @@ pub struct TextBuffer {
     added: String,
     pieces: Vec<Piece>,
     len: usize,
+    /// Byte offsets where each line starts, built on first use and dropped
+    /// on every edit.
+    line_starts: OnceLock<Vec<usize>>,
+    /// Number of `\n` bytes, kept up to date by every edit.
+    newlines: usize,
+    history: History,
+    read_only: bool,
 }
 
 impl Default for TextBuffer {
@@ -53,6 +159,10 @@ impl Default for TextBuffer {
             added: String::new(),
             pieces: Vec::new(),
             len: 0,
+            line_starts: OnceLock::new(),
+            newlines: 0,
+            history: History::default(),
+            read_only: false,
         }
     }
 }
@@ -71,6 +181,7 @@ impl TextBuffer {
             return Self::new();
         }
 
+        let newlines = count_newlines(string.as_bytes());
         let original: Arc<str> = Arc::from(string.into_boxed_str());
         let mut pieces = Vec::new();
         if len > 0 {
@@ -82,6 +193,10 @@ impl TextBuffer {
             added: String::new(),
             pieces,
             len,
+            line_starts: OnceLock::new(),
+            newlines,
+            history: History::default(),
+            read_only: false,
         }
     }
 
@@ -95,15 +210,48 @@ impl TextBuffer {
         }
 
         let pieces = vec![Piece::new(PieceSource::Original, 0, len)];
+        let newlines = count_newlines(original.as_bytes());
 
         Self {
             original,
             added: String::new(),
             pieces,
             len,
+            line_starts: OnceLock::new(),
+            newlines,
+            history: History::default(),
+            read_only: false,
+        }
+    }
+
+    /// Marks the buffer read-only, for views that cannot be saved back such
+    /// as diffs or blobs. Edits are then rejected: the `try_` variants return
+    /// [`EditError::ReadOnly`] and the others panic.
+    pub fn read_only(mut self) -> Self {
+        self.read_only = true;
+        self
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+    }
+
+    fn check_writable(&self) -> Result<(), EditError> {
+        if self.read_only {
+            Err(EditError::ReadOnly)
+        } else {
+            Ok(())
         }
     }
 
+    fn assert_writable(&self) {
+        assert!(!self.read_only, "cannot edit a read-only TextBuffer");
+    }
+
     /// Total length of the buffer in bytes.
     pub fn len(&self) -> usize {
         self.len
@@ -119,6 +267,18 @@ impl TextBuffer {
         self.slice(..)
     }
 
+    /// Returns a [`Read`] over the buffer's bytes.
+    ///
+    /// The reader walks the piece table directly, so the document is never
+    /// materialized as a single allocation.
+    pub fn bytes_reader(&self) -> impl Read + '_ {
+        BytesReader {
+            buffer: self,
+            piece: 0,
+            offset: 0,
+        }
+    }
+
     /// Returns the amount of `char`s contained in the buffer.
     pub fn char_count(&self) -> usize {
         self.pieces
@@ -130,6 +290,366 @@ impl TextBuffer {
             .sum()
     }
 
+    /// Number of lines, counting the (possibly empty) line after the last
+    /// `\n`, so an empty buffer has one line and `"a\n"` has two.
+    ///
+    /// Unlike [`str::lines`], which does not yield the empty line after a
+    /// trailing line break, this matches the lines a cursor can be placed on.
+    /// See [`TextBuffer::line_count`] for the number of lines the iterators
+    /// yield. Kept up to date by every edit, so this is O(1).
+    pub fn len_lines(&self) -> usize {
+        self.newlines + 1
+    }
+
+    /// Number of lines [`TextBuffer::lines`] yields, in O(1).
+    ///
+    /// A final line break terminates the last line rather than starting an
+    /// empty one, so `"a\n"` and `"a"` both have one line and an empty
+    /// buffer has none. The cursor can still be placed after a final line
+    /// break: [`TextBuffer::len_lines`] counts that virtual line, and exceeds
+    /// this by one exactly when the buffer is empty or
+    /// [`TextBuffer::has_final_newline`] holds.
+    pub fn line_count(&self) -> usize {
+        if self.is_empty() {
+            0
+        } else {
+            self.len_lines() - usize::from(self.has_final_newline())
+        }
+    }
+
+    /// Whether the buffer ends with a `\n` (including a `\r\n`).
+    pub fn has_final_newline(&self) -> bool {
+        self.pieces
+            .iter()
+            .rev()
+            .find_map(|piece| self.piece_bytes(piece).last())
+            == Some(&b'\n')
+    }
+
+    /// Computes byte, char, grapheme, line and word counts in a single pass
+    /// over the pieces.
+    pub fn stats(&self) -> TextStats {
+        let mut stats = TextStats {
+            bytes: self.len,
+            lines: 1,
+            ..TextStats::default()
+        };
+        let mut in_word = false;
+        let mut chunks = Vec::with_capacity(self.pieces.len());
+        let mut offset = 0usize;
+
+        for piece in &self.pieces {
+            let text = self.piece_str(piece);
+            for ch in text.chars() {
+                stats.chars += 1;
+                if ch == '\n' {
+                    stats.lines += 1;
+                }
+                if ch.is_whitespace() {
+                    in_word = false;
+                } else if !in_word {
+                    in_word = true;
+                    stats.words += 1;
+                }
+            }
+            chunks.push((offset, text));
+            offset += text.len();
+        }
+
+        // Grapheme clusters may straddle pieces, so feed the cursor chunk by
+        // chunk instead of segmenting each piece on its own.
+        let mut cursor = GraphemeCursor::new(0, self.len, true);
+        let mut index = 0usize;
+        while let Some(&(start, text)) = chunks.get(index) {
+            match cursor.next_boundary(text, start) {
+                Ok(Some(_)) => stats.graphemes += 1,
+                Ok(None) => break,
+                Err(GraphemeIncomplete::NextChunk) => index += 1,
+                Err(GraphemeIncomplete::PreContext(end)) => {
+                    let Some(&(context_start, context)) = chunks
+                        .iter()
+                        .find(|(chunk_start, chunk)| chunk_start + chunk.len() == end)
+                    else {
+                        break;
+                    };
+                    cursor.provide_context(context, context_start);
+                }
+                Err(_) => break,
+            }
+        }
+
+        stats
+    }
+
+    /// Maps a tab-expanded visual column on the zero-based `line` to a byte
+    /// offset.
+    ///
+    /// Every grapheme occupies one column except tabs, which advance to the
+    /// next multiple of `tab_width`. A column inside a tab maps to the tab
+    /// itself; columns past the end of the line map to the end of the line
+    /// (before its line break), and lines past the end map to the buffer end.
+    pub fn visual_col_to_offset(&self, line: usize, visual_col: usize, tab_width: usize) -> usize {
+        let Some(start) = self.line_start(line) else {
+            return self.len;
+        };
+        let text = self.line_text(start);
+        let tab_width = tab_width.max(1);
+
+        let mut column = 0usize;
+        for (index, grapheme) in text.grapheme_indices(true) {
+            let width = grapheme_width(grapheme, column, tab_width);
+            if column + width > visual_col {
+                return start + index;
+            }
+            column += width;
+        }
+        start + text.len()
+    }
+
+    /// Inverse of [`TextBuffer::visual_col_to_offset`]: the tab-expanded
+    /// visual column of byte `offset` within its line.
+    pub fn offset_to_visual_col(&self, offset: usize, tab_width: usize) -> usize {
+        let offset = offset.min(self.len);
+        let start = self.line_start_before(offset);
+        let text = self.slice(start..offset);
+        let tab_width = tab_width.max(1);
+
+        text.graphemes(true).fold(0, |column, grapheme| {
+            column + grapheme_width(grapheme, column, tab_width)
+        })
+    }
+
+    /// Iterates over the lines of the buffer without materializing it.
+    ///
+    /// Lines follow [`str::lines`]: they exclude the trailing `\n` or `\r\n`,
+    /// and a final line break does not start an empty line.
+    pub fn lines(&self) -> impl Iterator<Item = String> + '_ {
+        Lines {
+            buffer: self,
+            piece: 0,
+            offset: 0,
+        }
+    }
+
+    /// Text of the zero-based `line`, as [`TextBuffer::lines`] would yield it,
+    /// reconstructed by scanning only the pieces up to its line break.
+    pub fn line(&self, index: usize) -> Option<String> {
+        let start = self.line_start(index)?;
+        let mut offset = 0usize;
+        for (piece_index, piece) in self.pieces.iter().enumerate() {
+            if start < offset + piece.len {
+                let mut lines = Lines {
+                    buffer: self,
+                    piece: piece_index,
+                    offset: start - offset,
+                };
+                return lines.next();
+            }
+            offset += piece.len;
+        }
+        None
+    }
+
+    /// Byte offset of the zero-based `line` and `column`, counting the column
+    /// in `char`s.
+    ///
+    /// Only `\n` breaks lines, so the `\r` of a CRLF pair is an ordinary
+    /// character at the end of its line. Columns past the end of the line map
+    /// to the end of the line (before its `\n`); lines past the end of the
+    /// buffer return `None`.
+    pub fn offset_at(&self, line: usize, column: usize) -> Option<usize> {
+        let start = self.line_start(line)?;
+        let mut remaining = column;
+        let mut offset = 0usize;
+        for piece in &self.pieces {
+            let text = self.piece_str(piece);
+            let end = offset + text.len();
+            if end > start {
+                let local = start.saturating_sub(offset);
+                for (index, ch) in text[local..].char_indices() {
+                    if remaining == 0 || ch == '\n' {
+                        return Some(offset + local + index);
+                    }
+                    remaining -= 1;
+                }
+            }
+            offset = end;
+        }
+        Some(self.len)
+    }
+
+    /// Inverse of [`TextBuffer::offset_at`]: the zero-based line and `char`
+    /// column of byte `offset`, which is clamped to the buffer length.
+    pub fn position_at(&self, offset: usize) -> (usize, usize) {
+        let offset = offset.min(self.len);
+        let line = self.line_of(offset);
+        let start = self.line_starts()[line];
+
+        let mut column = 0usize;
+        let mut piece_offset = 0usize;
+        for piece in &self.pieces {
+            if piece_offset >= offset {
+                break;
+            }
+            let bytes = self.piece_bytes(piece);
+            let end = piece_offset + bytes.len();
+            if end > start {
+                let from = start.saturating_sub(piece_offset);
+                let to = (offset - piece_offset).min(bytes.len());
+                // Count lead bytes only, so each char counts once.
+                column += bytes[from..to]
+                    .iter()
+                    .filter(|&&byte| byte & 0xC0 != 0x80)
+                    .count();
+            }
+            piece_offset = end;
+        }
+        (line, column)
+    }
+
+    /// Byte length of the leading spaces and tabs on the zero-based `line`,
+    /// and whether the line holds nothing but whitespace.
+    ///
+    /// Empty lines and lines past the end of the buffer report `(0, true)`.
+    pub fn line_indent(&self, line: usize) -> (usize, bool) {
+        let Some(start) = self.line_start(line) else {
+            return (0, true);
+        };
+
+        let mut indent = 0usize;
+        let mut in_indent = true;
+        let mut offset = 0usize;
+        for piece in &self.pieces {
+            let bytes = self.piece_bytes(piece);
+            let end = offset + bytes.len();
+            if end > start {
+                for &byte in &bytes[start.saturating_sub(offset)..] {
+                    match byte {
+                        b'\n' => return (indent, true),
+                        b' ' | b'\t' if in_indent => indent += 1,
+                        b' ' | b'\t' | b'\r' => in_indent = false,
+                        _ => return (indent, false),
+                    }
+                }
+            }
+            offset = end;
+        }
+        (indent, true)
+    }
+
+    /// Byte offsets where each line starts, scanning the pieces once after
+    /// each edit.
+    fn line_starts(&self) -> &[usize] {
+        self.line_starts.get_or_init(|| {
+            let mut starts = vec![0];
+            let mut offset = 0usize;
+            for piece in &self.pieces {
+                let bytes = self.piece_bytes(piece);
+                starts.extend(
+                    bytes
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, b)| **b == b'\n')
+                        .map(|(index, _)| offset + index + 1),
+                );
+                offset += bytes.len();
+            }
+            starts
+        })
+    }
+
+    /// Byte offset where the zero-based `line` starts, if it exists.
+    fn line_start(&self, line: usize) -> Option<usize> {
+        self.line_starts().get(line).copied()
+    }
+
+    /// Zero-based line containing `offset`.
+    fn line_of(&self, offset: usize) -> usize {
+        self.line_starts().partition_point(|&start| start <= offset) - 1
+    }
+
+    /// Byte offset of the start of the line containing `offset`.
+    fn line_start_before(&self, offset: usize) -> usize {
+        self.line_starts()[self.line_of(offset)]
+    }
+
+    /// `(index, text)` of every line overlapping the byte `range`, e.g. the
+    /// lines of a viewport, without walking the rest of the buffer. A line's
+    /// `\n` counts as part of it; an empty range yields the line containing
+    /// its start. Lines are split as by [`TextBuffer::lines`], and borrowed
+    /// from the buffer unless they span pieces.
+    pub fn lines_in_range(
+        &self,
+        range: Range<usize>,
+    ) -> impl Iterator<Item = (usize, Cow<'_, str>)> {
+        let start = range.start.min(self.len);
+        let end = range.end.clamp(start, self.len);
+        let first = self.line_of(start);
+        let last = self.line_of(end.saturating_sub(1).max(start));
+        (first..=last).filter_map(move |line| {
+            let line_start = self.line_start(line)?;
+            if line_start == self.len {
+                // The empty line after a trailing `\n`, which `lines` omits.
+                return None;
+            }
+            let line_end = self.line_start(line + 1).map_or(self.len, |next| next - 1);
+            let mut text = self.slice_cow(line_start, line_end);
+            if text.ends_with('\r') {
+                match &mut text {
+                    Cow::Borrowed(text) => *text = &text[..text.len() - 1],
+                    Cow::Owned(text) => {
+                        text.pop();
+                    }
+                }
+            }
+            Some((line, text))
+        })
+    }
+
+    /// The text between `start` and `end`, borrowed when a single piece
+    /// holds it.
+    fn slice_cow(&self, start: usize, end: usize) -> Cow<'_, str> {
+        if start == end {
+            return Cow::Borrowed("");
+        }
+        let mut offset = 0usize;
+        for piece in &self.pieces {
+            let piece_end = offset + piece.len;
+            if start < piece_end {
+                if end <= piece_end {
+                    let text = self.piece_str(piece);
+                    return Cow::Borrowed(&text[start - offset..end - offset]);
+                }
+                break;
+            }
+            offset = piece_end;
+        }
+        Cow::Owned(self.slice(start..end))
+    }
+
+    /// Text of the line beginning at `start`, without its line break.
+    fn line_text(&self, start: usize) -> String {
+        let mut text = String::new();
+        let mut offset = 0usize;
+        for piece in &self.pieces {
+            let piece_text = self.piece_str(piece);
+            let end = offset + piece_text.len();
+            if end > start {
+                let local = &piece_text[start.saturating_sub(offset)..];
+                if let Some(newline) = local.find('\n') {
+                    text.push_str(&local[..newline]);
+                    break;
+                }
+                text.push_str(local);
+            }
+            offset = end;
+        }
+        if text.ends_with('\r') {
+            text.pop();
+        }
+        text
+    }
+
     /// Extracts a substring using byte offsets, similar to [`String::get`].
     pub fn slice<R>(&self, range: R) -> String
     where
@@ -183,7 +703,23 @@ impl TextBuffer {
     }
 
     /// Inserts `text` at the provided byte `offset`.
+    ///
+    /// Consecutive single-character inserts, each right after the previous
+    /// one, are undone together until [`TextBuffer::commit_group`] is called
+    /// or another kind of edit intervenes.
     pub fn insert(&mut self, offset: usize, text: &str) {
+        self.assert_writable();
+        self.insert_raw(offset, text);
+        if !text.is_empty() {
+            self.history.record(Edit {
+                offset,
+                removed: String::new(),
+                inserted: text.to_string(),
+            });
+        }
+    }
+
+    fn insert_raw(&mut self, offset: usize, text: &str) {
         assert!(offset <= self.len, "insert offset out of bounds");
         if text.is_empty() {
             return;
@@ -230,6 +766,8 @@ impl TextBuffer {
         }
 
         self.len += text.len();
+        self.newlines += count_newlines(text.as_bytes());
+        self.line_starts.take();
     }
 
     /// Deletes the text in the provided byte range.
@@ -237,13 +775,30 @@ impl TextBuffer {
     where
         R: RangeBounds<usize>,
     {
+        self.assert_writable();
         let (start, end) = self.normalize_range(range);
         if start >= end {
             return;
         }
 
         assert!(end <= self.len, "delete range out of bounds");
+        let removed = self.slice(start..end);
+        self.delete_raw(start, end);
+        self.history.record(Edit {
+            offset: start,
+            removed,
+            inserted: String::new(),
+        });
+    }
+
+    fn delete_raw(&mut self, start: usize, end: usize) {
+        if start >= end {
+            return;
+        }
+        self.newlines -= self.newlines_in(start, end);
 
+        // `cursor` tracks piece starts in pre-deletion offsets, like `start`
+        // and `end`, so it advances past removed text too.
         let mut cursor = 0usize;
         let mut index = 0usize;
 
@@ -278,7 +833,7 @@ impl TextBuffer {
 
             if local_start == 0 && local_end == piece_len {
                 self.pieces.remove(index);
-                cursor = piece_start;
+                cursor = piece_end;
                 continue;
             }
 
@@ -290,9 +845,9 @@ impl TextBuffer {
 
                 if self.pieces[index].is_empty() {
                     self.pieces.remove(index);
-                    cursor = piece_start;
+                    cursor = piece_end;
                 } else {
-                    cursor = piece_start + new_len;
+                    cursor = piece_end;
                     index += 1;
                 }
 
@@ -305,9 +860,9 @@ impl TextBuffer {
 
                 if self.pieces[index].is_empty() {
                     self.pieces.remove(index);
-                    cursor = piece_start;
+                    cursor = piece_end;
                 } else {
-                    cursor = piece_start + new_len;
+                    cursor = piece_end;
                     index += 1;
                 }
 
@@ -324,6 +879,7 @@ impl TextBuffer {
         }
 
         self.len -= end - start;
+        self.line_starts.take();
         self.coalesce_all();
     }
 
@@ -332,13 +888,329 @@ impl TextBuffer {
     where
         R: RangeBounds<usize>,
     {
-        let start = match range.start_bound() {
-            Bound::Included(&value) => value,
-            Bound::Excluded(&value) => value + 1,
-            Bound::Unbounded => 0,
+        self.assert_writable();
+        let (start, end) = self.normalize_range(range);
+        assert!(
+            start >= end || end <= self.len,
+            "replace range out of bounds"
+        );
+        let removed = self.slice(start..end);
+        self.delete_raw(start, end);
+        self.insert_raw(start, text);
+        if !removed.is_empty() || !text.is_empty() {
+            self.history.record(Edit {
+                offset: start,
+                removed,
+                inserted: text.to_string(),
+            });
+        }
+    }
+
+    /// Like [`TextBuffer::insert`], but returns an error instead of
+    /// panicking when the buffer is read-only or `offset` is out of bounds.
+    pub fn try_insert(&mut self, offset: usize, text: &str) -> Result<(), EditError> {
+        self.check_writable()?;
+        if offset > self.len {
+            return Err(EditError::OutOfBounds);
+        }
+        self.insert(offset, text);
+        Ok(())
+    }
+
+    /// Like [`TextBuffer::delete`], but returns an error instead of
+    /// panicking when the buffer is read-only or `range` is out of bounds.
+    pub fn try_delete<R>(&mut self, range: R) -> Result<(), EditError>
+    where
+        R: RangeBounds<usize>,
+    {
+        self.check_writable()?;
+        let (start, end) = self.normalize_range(range);
+        if start < end && end > self.len {
+            return Err(EditError::OutOfBounds);
+        }
+        self.delete(start..end);
+        Ok(())
+    }
+
+    /// Like [`TextBuffer::replace`], but returns an error instead of
+    /// panicking when the buffer is read-only or `range` is out of bounds.
+    pub fn try_replace<R>(&mut self, range: R, text: &str) -> Result<(), EditError>
+    where
+        R: RangeBounds<usize>,
+    {
+        self.check_writable()?;
+        let (start, end) = self.normalize_range(range);
+        if start > self.len || (start < end && end > self.len) {
+            return Err(EditError::OutOfBounds);
+        }
+        self.replace(start..end, text);
+        Ok(())
+    }
+
+    /// Reverts the most recent undo group. Returns `false` when there is
+    /// nothing to undo, or the buffer is read-only.
+    pub fn undo(&mut self) -> bool {
+        if self.read_only {
+            return false;
+        }
+        let Some(group) = self.history.undo.pop() else {
+            return false;
         };
-        self.delete(range);
-        self.insert(start, text);
+        for edit in group.iter().rev() {
+            self.delete_raw(edit.offset, edit.offset + edit.inserted.len());
+            self.insert_raw(edit.offset, &edit.removed);
+        }
+        self.history.redo.push(group);
+        self.history.typing = false;
+        true
+    }
+
+    /// Reapplies the most recently undone group. Any new edit after an undo
+    /// clears the redo history. Returns `false` when there is nothing to redo,
+    /// or the buffer is read-only.
+    pub fn redo(&mut self) -> bool {
+        if self.read_only {
+            return false;
+        }
+        let Some(group) = self.history.redo.pop() else {
+            return false;
+        };
+        for edit in &group {
+            self.delete_raw(edit.offset, edit.offset + edit.removed.len());
+            self.insert_raw(edit.offset, &edit.inserted);
+        }
+        self.history.undo.push(group);
+        self.history.typing = false;
+        true
+    }
+
+    /// Captures the current contents for a later [`TextBuffer::restore`].
+    ///
+    /// Only the piece table and the append buffer are copied; the original
+    /// text is shared.
+    pub fn snapshot(&self) -> BufferSnapshot {
+        BufferSnapshot {
+            original: Arc::clone(&self.original),
+            added: self.added.clone(),
+            pieces: self.pieces.clone(),
+            len: self.len,
+            newlines: self.newlines,
+        }
+    }
+
+    /// Returns the buffer to the contents captured by `snapshot`.
+    ///
+    /// The undo history describes edits from before the restore, so it is
+    /// cleared.
+    pub fn restore(&mut self, snapshot: BufferSnapshot) {
+        self.original = snapshot.original;
+        self.added = snapshot.added;
+        self.pieces = snapshot.pieces;
+        self.len = snapshot.len;
+        self.newlines = snapshot.newlines;
+        self.line_starts.take();
+        self.history = History::default();
+    }
+
+    /// Bytes held by the append-only edit buffer, including text that has
+    /// since been deleted. See [`TextBuffer::compact`].
+    pub fn added_bytes(&self) -> usize {
+        self.added.len()
+    }
+
+    /// Rebuilds the buffer from its current contents as a single original
+    /// piece, releasing the memory edits accumulated in the append buffer.
+    ///
+    /// The undo history is reset as well, releasing the text it records.
+    pub fn compact(&mut self) {
+        let text = self.to_string();
+        self.original = Arc::from(text.into_boxed_str());
+        self.added = String::new();
+        self.pieces.clear();
+        if self.len > 0 {
+            self.pieces
+                .push(Piece::new(PieceSource::Original, 0, self.len));
+        }
+        self.history = History::default();
+    }
+
+    /// Ends the current undo group, so the next edit is undone separately.
+    pub fn commit_group(&mut self) {
+        self.history.typing = false;
+    }
+
+    /// Replaces every non-overlapping occurrence of `needle` with
+    /// `replacement`, returning the number of replacements made.
+    ///
+    /// Matches are located on the original text before any replacement is
+    /// applied, so a `replacement` containing `needle` does not cascade. The
+    /// piece table is rebuilt in a single pass rather than one edit per match.
+    pub fn replace_all(&mut self, needle: &str, replacement: &str) -> usize {
+        self.assert_writable();
+        let matches = self.find_matches(needle, 0, usize::MAX);
+        if matches.is_empty() {
+            return 0;
+        }
+
+        let replacement_piece = (!replacement.is_empty()).then(|| {
+            let start = self.added.len();
+            self.added.push_str(replacement);
+            Piece::new(PieceSource::Added, start, replacement.len())
+        });
+
+        let mut pieces = Vec::with_capacity(self.pieces.len() + matches.len() * 2);
+        let mut cursor = (0usize, 0usize);
+        let mut kept_from = 0usize;
+        for &start in &matches {
+            self.copy_range(kept_from, start, &mut cursor, &mut pieces);
+            if let Some(piece) = &replacement_piece {
+                pieces.push(piece.clone());
+            }
+            kept_from = start + needle.len();
+        }
+        self.copy_range(kept_from, self.len, &mut cursor, &mut pieces);
+
+        // Record each match at its offset once the earlier ones are replaced,
+        // so the group replays in order and reverts in reverse.
+        let edits = matches
+            .iter()
+            .enumerate()
+            .map(|(index, &start)| Edit {
+                offset: start + index * replacement.len() - index * needle.len(),
+                removed: needle.to_string(),
+                inserted: replacement.to_string(),
+            })
+            .collect();
+        self.history.record_group(edits);
+
+        self.pieces = pieces;
+        self.len = self.len - matches.len() * needle.len() + matches.len() * replacement.len();
+        self.newlines = self.newlines - matches.len() * count_newlines(needle.as_bytes())
+            + matches.len() * count_newlines(replacement.as_bytes());
+        self.line_starts.take();
+        self.coalesce_all();
+        matches.len()
+    }
+
+    /// Byte offsets of every non-overlapping occurrence of `needle`.
+    ///
+    /// The pieces are searched in place, including matches that straddle a
+    /// piece boundary, so the buffer is never materialized. An empty `needle`
+    /// has no matches.
+    pub fn find_all(&self, needle: &str) -> Vec<usize> {
+        self.find_matches(needle, 0, usize::MAX)
+    }
+
+    /// Byte offset of the first occurrence of `needle` starting at or after
+    /// `start`, for an incremental "find next".
+    pub fn find_from(&self, start: usize, needle: &str) -> Option<usize> {
+        self.find_matches(needle, start, 1).first().copied()
+    }
+
+    /// Byte offsets of up to `limit` non-overlapping occurrences of `needle`
+    /// at or after `from`, searched across piece boundaries without
+    /// materializing the buffer.
+    fn find_matches(&self, needle: &str, from: usize, limit: usize) -> Vec<usize> {
+        let needle = needle.as_bytes();
+        let mut matches = Vec::new();
+        if needle.is_empty() || from >= self.len {
+            return matches;
+        }
+
+        // Knuth-Morris-Pratt failure table so partial matches survive piece
+        // boundaries without backtracking into earlier pieces.
+        let mut failure = vec![0usize; needle.len()];
+        let mut prefix = 0usize;
+        for index in 1..needle.len() {
+            while prefix > 0 && needle[index] != needle[prefix] {
+                prefix = failure[prefix - 1];
+            }
+            if needle[index] == needle[prefix] {
+                prefix += 1;
+            }
+            failure[index] = prefix;
+        }
+
+        let mut matched = 0usize;
+        let mut offset = 0usize;
+        for piece in &self.pieces {
+            let bytes = self.piece_bytes(piece);
+            let skip = from.saturating_sub(offset).min(bytes.len());
+            for (index, &byte) in bytes.iter().enumerate().skip(skip) {
+                while matched > 0 && needle[matched] != byte {
+                    matched = failure[matched - 1];
+                }
+                if needle[matched] == byte {
+                    matched += 1;
+                }
+                if matched == needle.len() {
+                    matches.push(offset + index + 1 - needle.len());
+                    if matches.len() == limit {
+                        return matches;
+                    }
+                    matched = 0;
+                }
+            }
+            offset += bytes.len();
+        }
+
+        matches
+    }
+
+    /// Appends pieces covering the logical range `start..end` to `out`.
+    ///
+    /// `cursor` holds the current piece index and its logical start offset so
+    /// that successive calls with increasing ranges walk the table once.
+    fn copy_range(
+        &self,
+        mut start: usize,
+        end: usize,
+        cursor: &mut (usize, usize),
+        out: &mut Vec<Piece>,
+    ) {
+        while start < end {
+            let (index, piece_offset) = *cursor;
+            let piece = &self.pieces[index];
+            if piece_offset + piece.len <= start {
+                *cursor = (index + 1, piece_offset + piece.len);
+                continue;
+            }
+            let local = start - piece_offset;
+            let take = (piece.len - local).min(end - start);
+            out.push(Piece::new(piece.source, piece.start + local, take));
+            start += take;
+        }
+    }
+
+    /// Number of `\n` bytes in the logical range `start..end`.
+    fn newlines_in(&self, start: usize, end: usize) -> usize {
+        let mut count = 0usize;
+        let mut offset = 0usize;
+        for piece in &self.pieces {
+            if offset >= end {
+                break;
+            }
+            let piece_end = offset + piece.len;
+            if piece_end > start {
+                let bytes = self.piece_bytes(piece);
+                let from = start.saturating_sub(offset);
+                let to = end.min(piece_end) - offset;
+                count += count_newlines(&bytes[from..to]);
+            }
+            offset = piece_end;
+        }
+        count
+    }
+
+    fn piece_str(&self, piece: &Piece) -> &str {
+        match piece.source {
+            PieceSource::Original => &self.original[piece.start..piece.end()],
+            PieceSource::Added => &self.added[piece.start..piece.end()],
+        }
+    }
+
+    fn piece_bytes(&self, piece: &Piece) -> &[u8] {
+        self.piece_str(piece).as_bytes()
     }
 
     fn normalize_range<R>(&self, range: R) -> (usize, usize)
@@ -431,6 +1303,81 @@ impl TextBuffer {
     }
 }
 
+fn count_newlines(bytes: &[u8]) -> usize {
+    bytes.iter().filter(|&&byte| byte == b'\n').count()
+}
+
+/// Visual width of `grapheme` when it starts at `column`.
+fn grapheme_width(grapheme: &str, column: usize, tab_width: usize) -> usize {
+    if grapheme == "\t" {
+        tab_width - column % tab_width
+    } else {
+        1
+    }
+}
+
+/// Streams the bytes of a [`TextBuffer`] piece by piece.
+struct BytesReader<'a> {
+    buffer: &'a TextBuffer,
+    piece: usize,
+    offset: usize,
+}
+
+impl Read for BytesReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut written = 0usize;
+        while written < buf.len() {
+            let Some(piece) = self.buffer.pieces.get(self.piece) else {
+                break;
+            };
+            let remaining = &self.buffer.piece_bytes(piece)[self.offset..];
+            let count = remaining.len().min(buf.len() - written);
+            buf[written..written + count].copy_from_slice(&remaining[..count]);
+            written += count;
+            self.offset += count;
+            if self.offset == piece.len {
+                self.piece += 1;
+                self.offset = 0;
+            }
+        }
+        Ok(written)
+    }
+}
+
+/// Yields the lines of a [`TextBuffer`] piece by piece.
+struct Lines<'a> {
+    buffer: &'a TextBuffer,
+    piece: usize,
+    offset: usize,
+}
+
+impl Iterator for Lines<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        let mut line = String::new();
+        while let Some(piece) = self.buffer.pieces.get(self.piece) {
+            let rest = &self.buffer.piece_str(piece)[self.offset..];
+            if let Some(newline) = rest.find('\n') {
+                line.push_str(&rest[..newline]);
+                self.offset += newline + 1;
+                if self.offset == piece.len {
+                    self.piece += 1;
+                    self.offset = 0;
+                }
+                if line.ends_with('\r') {
+                    line.pop();
+                }
+                return Some(line);
+            }
+            line.push_str(rest);
+            self.piece += 1;
+            self.offset = 0;
+        }
+        (!line.is_empty()).then_some(line)
+    }
+}
+
 enum InsertPosition {
     Empty,
     At((usize, usize)),
@@ -748,4 +1695,591 @@ mod tests {
             handle.join().unwrap();
         }
     }
+
+    #[test]
+    fn bytes_reader_matches_to_string() {
+        let mut buffer = TextBuffer::from_text("hello wörld, streaming");
+        buffer.insert(5, " there");
+        buffer.delete(0..1);
+        buffer.insert(0, "✨H");
+        buffer.replace(12..14, "🌍");
+
+        let mut bytes = Vec::new();
+        buffer.bytes_reader().read_to_end(&mut bytes).unwrap();
+        assert_eq!(bytes, buffer.to_string().into_bytes());
+
+        // Small reads must cross piece boundaries correctly.
+        let mut reader = buffer.bytes_reader();
+        let mut chunked = Vec::new();
+        let mut chunk = [0u8; 3];
+        loop {
+            let read = reader.read(&mut chunk).unwrap();
+            if read == 0 {
+                break;
+            }
+            chunked.extend_from_slice(&chunk[..read]);
+        }
+        assert_eq!(chunked, bytes);
+
+        let mut empty = Vec::new();
+        TextBuffer::new()
+            .bytes_reader()
+            .read_to_end(&mut empty)
+            .unwrap();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn replace_all_matches_across_pieces() {
+        let mut buffer = TextBuffer::from_text("foo bar foo");
+        // Split "foo" at the end across an original and an added piece.
+        buffer.delete(9..11);
+        buffer.insert(9, "oo");
+        buffer.insert(0, "f");
+        buffer.delete(1..2);
+        assert_eq!(buffer.to_string(), "foo bar foo");
+        assert!(buffer.pieces.len() > 1);
+
+        assert_eq!(buffer.replace_all("foo", "baz"), 2);
+        assert_eq!(buffer.to_string(), "baz bar baz");
+        assert_eq!(buffer.len(), "baz bar baz".len());
+
+        assert_eq!(buffer.replace_all("missing", "x"), 0);
+        assert_eq!(buffer.replace_all(" ", ""), 2);
+        assert_eq!(buffer.to_string(), "bazbarbaz");
+    }
+
+    #[test]
+    fn replace_all_with_replacement_containing_needle() {
+        let mut buffer = TextBuffer::from_text("a-a-a");
+        assert_eq!(buffer.replace_all("a", "aa"), 3);
+        assert_eq!(buffer.to_string(), "aa-aa-aa");
+
+        let mut overlapping = TextBuffer::from_text("aaaa");
+        assert_eq!(overlapping.replace_all("aa", "b"), 2);
+        assert_eq!(overlapping.to_string(), "bb");
+    }
+
+    #[test]
+    fn stats_over_multiline_document() {
+        let mut buffer = TextBuffer::from_text("fn main() {\n    println!(\"hi\");\n}\n");
+        assert_eq!(
+            buffer.stats(),
+            TextStats {
+                bytes: 34,
+                chars: 34,
+                graphemes: 34,
+                lines: 4,
+                words: 5,
+            }
+        );
+
+        // "e" + combining acute accent inserted as separate pieces still forms
+        // one grapheme.
+        buffer = TextBuffer::from_text("cafe bar");
+        buffer.insert(4, "\u{301}");
+        buffer.insert(0, "😀 ");
+        let stats = buffer.stats();
+        assert_eq!(stats.bytes, buffer.to_string().len());
+        assert_eq!(stats.chars, 11);
+        assert_eq!(stats.graphemes, 10);
+        assert_eq!(stats.lines, 1);
+        assert_eq!(stats.words, 3);
+
+        assert_eq!(
+            TextBuffer::new().stats(),
+            TextStats {
+                lines: 1,
+                ..TextStats::default()
+            }
+        );
+    }
+
+    #[test]
+    fn visual_column_to_offset_expands_tabs() {
+        let mut buffer = TextBuffer::from_text("first\n\tif x {\n\t\tcall();\n");
+        buffer.insert(buffer.len(), "e\u{301}\tend");
+
+        // Line 1 is "\tif x {": the tab spans columns 0..4.
+        assert_eq!(buffer.visual_col_to_offset(1, 0, 4), 6);
+        assert_eq!(buffer.visual_col_to_offset(1, 3, 4), 6);
+        assert_eq!(buffer.visual_col_to_offset(1, 4, 4), 7);
+        assert_eq!(buffer.visual_col_to_offset(1, 6, 4), 9);
+        assert_eq!(buffer.visual_col_to_offset(1, 100, 4), 13);
+
+        // Line 2 is "\t\tcall();".
+        assert_eq!(buffer.visual_col_to_offset(2, 5, 4), 15);
+        assert_eq!(buffer.visual_col_to_offset(2, 8, 4), 16);
+
+        // Line 3 starts with a combining sequence counted as one column.
+        assert_eq!(buffer.visual_col_to_offset(3, 1, 4), 27);
+        assert_eq!(buffer.visual_col_to_offset(3, 4, 4), 28);
+        assert_eq!(buffer.visual_col_to_offset(9, 0, 4), buffer.len());
+    }
+
+    #[test]
+    fn line_indent_reports_leading_whitespace() {
+        let mut buffer = TextBuffer::from_text("fn main() {\n\t  let x = 1;\n");
+        // Split the indentation of the second line across pieces.
+        buffer.insert(13, "  ");
+        buffer.insert(buffer.len(), "    \n\n  \t\r\n}");
+
+        assert_eq!(buffer.line_indent(0), (0, false));
+        assert_eq!(buffer.line_indent(1), (5, false));
+        assert_eq!(buffer.line_indent(2), (4, true));
+        assert_eq!(buffer.line_indent(3), (0, true));
+        assert_eq!(buffer.line_indent(4), (3, true));
+        assert_eq!(buffer.line_indent(5), (0, false));
+        assert_eq!(buffer.line_indent(6), (0, true));
+    }
+
+    #[test]
+    fn converts_between_positions_and_offsets() {
+        let mut buffer = TextBuffer::from_text("héllo\r\nwörld 😀x\n");
+        assert_eq!(buffer.offset_at(0, 2), Some(3));
+        assert_eq!(buffer.position_at(3), (0, 2));
+        // The `\r` is part of the first line.
+        assert_eq!(buffer.offset_at(0, 5), Some(6));
+        assert_eq!(buffer.offset_at(0, 6), Some(7));
+        assert_eq!(buffer.offset_at(0, 99), Some(7));
+        assert_eq!(buffer.position_at(7), (0, 6));
+        assert_eq!(buffer.offset_at(1, 7), Some(19));
+        assert_eq!(buffer.slice(19..20), "x");
+        assert_eq!(buffer.position_at(19), (1, 7));
+        assert_eq!(buffer.offset_at(2, 0), Some(buffer.len()));
+        assert_eq!(buffer.position_at(buffer.len()), (2, 0));
+        assert_eq!(buffer.offset_at(3, 0), None);
+
+        // Edits split the text across pieces.
+        buffer.insert(8, "ü");
+        buffer.delete(0..1);
+        buffer.insert(buffer.len(), "ñ\nend");
+        assert_eq!(buffer.to_string(), "éllo\r\nüwörld 😀x\nñ\nend");
+        assert_eq!(buffer.offset_at(1, 8), Some(20));
+        assert_eq!(buffer.slice(20..21), "x");
+        assert_eq!(buffer.position_at(20), (1, 8));
+        assert_eq!(buffer.offset_at(2, 1), Some(24));
+        assert_eq!(buffer.position_at(24), (2, 1));
+        assert_eq!(buffer.offset_at(3, 3), Some(buffer.len()));
+        assert_eq!(buffer.offset_at(4, 0), None);
+
+        for offset in 0..=buffer.len() {
+            if buffer.to_string().is_char_boundary(offset) {
+                let (line, column) = buffer.position_at(offset);
+                assert_eq!(buffer.offset_at(line, column), Some(offset));
+            }
+        }
+    }
+
+    #[test]
+    fn lines_in_range_yields_overlapping_lines() {
+        let mut buffer = TextBuffer::from_text("zero\none\r\ntwo\nthr");
+        buffer.insert(buffer.len(), "ee\nfour\n");
+
+        let lines: Vec<_> = buffer.lines_in_range(7..16).collect();
+        assert_eq!(
+            lines,
+            vec![
+                (1, Cow::Borrowed("one")),
+                (2, Cow::Borrowed("two")),
+                (3, Cow::Borrowed("three")),
+            ]
+        );
+        assert!(matches!(lines[0].1, Cow::Borrowed(_)));
+        assert!(matches!(lines[2].1, Cow::Owned(_)));
+
+        // Ending on a line's `\n` includes that line only.
+        let lines: Vec<_> = buffer
+            .lines_in_range(0..5)
+            .map(|(index, _)| index)
+            .collect();
+        assert_eq!(lines, vec![0]);
+        let lines: Vec<_> = buffer.lines_in_range(10..10).collect();
+        assert_eq!(lines, vec![(2, Cow::Borrowed("two"))]);
+        let lines: Vec<_> = buffer.lines_in_range(20..100).collect();
+        assert_eq!(lines, vec![(4, Cow::Borrowed("four"))]);
+        assert_eq!(buffer.lines_in_range(25..25).count(), 0);
+        assert_eq!(TextBuffer::new().lines_in_range(0..0).count(), 0);
+
+        let all: Vec<_> = buffer
+            .lines_in_range(0..buffer.len())
+            .map(|(_, line)| line.into_owned())
+            .collect();
+        assert_eq!(all, buffer.lines().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn line_matches_str_lines() {
+        let mut buffer = TextBuffer::from_text("alpha\r\nbeta\n\ngamma");
+        buffer.insert(8, "be");
+        buffer.insert(buffer.len(), " delta\nlast");
+        buffer.delete(0..1);
+        let text = buffer.to_string();
+
+        for index in 0..7 {
+            assert_eq!(
+                buffer.line(index),
+                text.lines().nth(index).map(String::from)
+            );
+        }
+        assert_eq!(buffer.line(4).as_deref(), Some("last"));
+        assert_eq!(
+            buffer.lines().collect::<Vec<_>>(),
+            text.lines().collect::<Vec<_>>()
+        );
+
+        buffer.insert(buffer.len(), "\n");
+        assert_eq!(buffer.line(5), None);
+        assert_eq!(buffer.lines().count(), 5);
+        assert_eq!(TextBuffer::new().line(0), None);
+        assert_eq!(TextBuffer::new().lines().count(), 0);
+
+        let large = TextBuffer::from_text(
+            (0..100_000)
+                .map(|n| format!("line {n}\n"))
+                .collect::<String>(),
+        );
+        assert_eq!(large.line(50_000).as_deref(), Some("line 50000"));
+    }
+
+    #[test]
+    fn line_cache_survives_edits() {
+        let mut buffer = TextBuffer::from_text("one\ntwo\nthree\n");
+        // Small linear congruential generator for a reproducible edit mix.
+        let mut seed = 0x2545_f491_u64;
+        let mut next = |bound: usize| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) as usize % bound.max(1)
+        };
+
+        // Plain string mirror of every edit.
+        let mut model = buffer.to_string();
+
+        for round in 0..300 {
+            let offset = next(buffer.len() + 1);
+            match next(4) {
+                0 if !buffer.is_empty() => {
+                    let end = (offset + next(8)).min(buffer.len());
+                    buffer.delete(offset.min(end)..end);
+                    model.replace_range(offset.min(end)..end, "");
+                }
+                1 => {
+                    buffer.insert(offset, "a\nb");
+                    model.insert_str(offset, "a\nb");
+                }
+                2 => {
+                    buffer.insert(offset, "\n");
+                    model.insert(offset, '\n');
+                }
+                _ => {
+                    buffer.insert(offset, "xy");
+                    model.insert_str(offset, "xy");
+                }
+            }
+            assert_eq!(buffer.to_string(), model);
+            if round % 3 != 0 {
+                continue;
+            }
+
+            // Query between edits so the cache is both built and invalidated.
+            let text = buffer.to_string();
+            let starts: Vec<usize> = std::iter::once(0)
+                .chain(text.match_indices('\n').map(|(index, _)| index + 1))
+                .collect();
+            for _ in 0..10 {
+                let line = next(starts.len() + 1);
+                assert_eq!(buffer.line_start(line), starts.get(line).copied());
+                let offset = next(text.len() + 1);
+                let expected = starts.iter().rposition(|&start| start <= offset).unwrap();
+                assert_eq!(buffer.position_at(offset).0, expected);
+                assert_eq!(buffer.line_start_before(offset), starts[expected]);
+            }
+            assert_eq!(
+                buffer.line(starts.len() / 2),
+                text.lines().nth(starts.len() / 2).map(String::from)
+            );
+        }
+
+        buffer.replace_all("\n", "");
+        assert_eq!(buffer.line_start(1), None);
+    }
+
+    #[test]
+    fn undo_coalesces_typing() {
+        let mut buffer = TextBuffer::from_text("fn main() {}\n");
+        for (index, ch) in "let".chars().enumerate() {
+            buffer.insert(11 + index, &ch.to_string());
+        }
+        buffer.commit_group();
+        buffer.insert(14, " ");
+        buffer.insert(15, "x");
+        assert_eq!(buffer.to_string(), "fn main() {let x}\n");
+
+        assert!(buffer.undo());
+        assert_eq!(buffer.to_string(), "fn main() {let}\n");
+        assert!(buffer.undo());
+        assert_eq!(buffer.to_string(), "fn main() {}\n");
+        assert!(!buffer.undo());
+
+        assert!(buffer.redo());
+        assert!(buffer.redo());
+        assert_eq!(buffer.to_string(), "fn main() {let x}\n");
+        assert!(!buffer.redo());
+
+        // Typing somewhere else starts a new group.
+        buffer.insert(0, "a");
+        buffer.insert(0, "b");
+        assert!(buffer.undo());
+        assert_eq!(buffer.to_string(), "afn main() {let x}\n");
+    }
+
+    #[test]
+    fn undo_restores_deleted_and_replaced_text() {
+        let mut buffer = TextBuffer::from_text("héllo world");
+        buffer.insert(buffer.len(), "!");
+        buffer.delete(0..7);
+        assert_eq!(buffer.to_string(), "world!");
+        buffer.replace(0..5, "there");
+        assert_eq!(buffer.replace_all("e", "ë"), 2);
+        assert_eq!(buffer.to_string(), "thërë!");
+
+        assert!(buffer.undo());
+        assert_eq!(buffer.to_string(), "there!");
+        assert!(buffer.undo());
+        assert_eq!(buffer.to_string(), "world!");
+        assert!(buffer.undo());
+        assert_eq!(buffer.to_string(), "héllo world!");
+        assert!(buffer.redo());
+        assert!(buffer.redo());
+        assert!(buffer.redo());
+        assert_eq!(buffer.to_string(), "thërë!");
+    }
+
+    #[test]
+    fn new_edit_clears_redo() {
+        let mut buffer = TextBuffer::from_text("abc");
+        buffer.delete(1..2);
+        assert!(buffer.undo());
+        assert_eq!(buffer.to_string(), "abc");
+
+        buffer.insert(3, "d");
+        assert!(!buffer.redo());
+        assert_eq!(buffer.to_string(), "abcd");
+        assert!(buffer.undo());
+        assert_eq!(buffer.to_string(), "abc");
+    }
+
+    #[test]
+    fn line_count_with_final_newline() {
+        let buffer = TextBuffer::from_text("one\ntwo\n");
+        assert!(buffer.has_final_newline());
+        assert_eq!(buffer.line_count(), 2);
+        assert_eq!(buffer.lines().count(), buffer.line_count());
+        assert_eq!(buffer.lines_in_range(0..buffer.len()).count(), 2);
+        assert_eq!(buffer.line(2), None);
+        assert_eq!(buffer.len_lines(), 3);
+
+        let buffer = TextBuffer::from_text("one\r\n\n");
+        assert!(buffer.has_final_newline());
+        assert_eq!(buffer.line_count(), 2);
+        assert_eq!(buffer.lines().collect::<Vec<_>>(), vec!["one", ""]);
+    }
+
+    #[test]
+    fn line_count_without_final_newline() {
+        let mut buffer = TextBuffer::from_text("one\ntwo");
+        assert!(!buffer.has_final_newline());
+        assert_eq!(buffer.line_count(), 2);
+        assert_eq!(buffer.lines().count(), buffer.line_count());
+        assert_eq!(buffer.len_lines(), 2);
+
+        buffer.insert(buffer.len(), "\n");
+        assert!(buffer.has_final_newline());
+        assert_eq!(buffer.line_count(), 2);
+        buffer.insert(buffer.len(), "x");
+        assert_eq!(buffer.line_count(), 3);
+
+        let empty = TextBuffer::new();
+        assert!(!empty.has_final_newline());
+        assert_eq!(empty.line_count(), 0);
+        assert_eq!(empty.lines().count(), 0);
+        assert_eq!(empty.len_lines(), 1);
+    }
+
+    #[test]
+    fn len_lines_tracks_edits() {
+        assert_eq!(TextBuffer::new().len_lines(), 1);
+        assert_eq!(TextBuffer::from_text("a\n").len_lines(), 2);
+
+        let mut buffer = TextBuffer::from_text("one\ntwo\r\nthree");
+        let recount = |buffer: &TextBuffer| buffer.to_string().matches('\n').count() + 1;
+        assert_eq!(buffer.len_lines(), 3);
+
+        buffer.insert(4, "\n\nnew\n");
+        buffer.delete(2..9);
+        buffer.replace(0..3, "x\ny");
+        assert_eq!(buffer.len_lines(), recount(&buffer));
+        buffer.replace_all("\n", "\n\n");
+        assert_eq!(buffer.len_lines(), recount(&buffer));
+        buffer.replace_all("\n\n", "-");
+        assert_eq!(buffer.len_lines(), recount(&buffer));
+
+        for round in 0..200 {
+            let offset = (round * 7) % (buffer.len() + 1);
+            if round % 3 == 0 {
+                let end = (offset + 5).min(buffer.len());
+                buffer.delete(offset..end);
+            } else {
+                buffer.insert(offset, if round % 2 == 0 { "\n" } else { "ab\ncd" });
+            }
+            assert_eq!(buffer.len_lines(), recount(&buffer));
+        }
+        assert_eq!(buffer.len_lines(), buffer.stats().lines);
+
+        while buffer.undo() {}
+        assert_eq!(buffer.to_string(), "one\ntwo\r\nthree");
+        assert_eq!(buffer.len_lines(), 3);
+        buffer.delete(..);
+        assert_eq!(buffer.len_lines(), 1);
+    }
+
+    #[test]
+    fn compact_releases_append_buffer() {
+        let mut buffer = TextBuffer::from_text("fn main() {\n}\n");
+        for round in 0..5000 {
+            let offset = (round * 13) % (buffer.len() + 1);
+            if round % 2 == 0 {
+                buffer.insert(offset, "edit\n");
+            } else {
+                let end = (offset + 4).min(buffer.len());
+                buffer.delete(offset..end);
+            }
+        }
+        let text = buffer.to_string();
+        let lines = buffer.len_lines();
+        assert!(buffer.added_bytes() > text.len());
+
+        buffer.compact();
+        assert_eq!(buffer.to_string(), text);
+        assert_eq!(buffer.added_bytes(), 0);
+        assert_eq!(buffer.len_lines(), lines);
+        assert!(!buffer.undo());
+
+        buffer.insert(0, "// ");
+        assert_eq!(buffer.added_bytes(), 3);
+        assert!(buffer.undo());
+        assert_eq!(buffer.to_string(), text);
+
+        let mut empty = TextBuffer::from_text("x");
+        empty.delete(..);
+        empty.compact();
+        assert!(empty.is_empty());
+        assert_eq!(empty.added_bytes(), 0);
+    }
+
+    #[test]
+    fn finds_matches_across_pieces() {
+        let mut buffer = TextBuffer::from_text("needle hay neele hay nee");
+        // Complete the broken occurrences with inserts, so each match spans
+        // the original piece and an added one.
+        buffer.insert(14, "d");
+        buffer.insert(buffer.len(), "dle");
+        assert_eq!(buffer.to_string(), "needle hay needle hay needle");
+
+        assert_eq!(buffer.find_all("needle"), vec![0, 11, 22]);
+        assert_eq!(buffer.find_all("le h"), vec![4, 15]);
+        assert_eq!(buffer.find_all("eedl"), vec![1, 12, 23]);
+        assert!(buffer.find_all("").is_empty());
+        assert!(buffer.find_all("haystack").is_empty());
+
+        assert_eq!(buffer.find_from(0, "needle"), Some(0));
+        assert_eq!(buffer.find_from(1, "needle"), Some(11));
+        assert_eq!(buffer.find_from(12, "needle"), Some(22));
+        assert_eq!(buffer.find_from(23, "needle"), None);
+        assert_eq!(buffer.find_from(0, ""), None);
+
+        let mut overlapping = TextBuffer::from_text("aa");
+        overlapping.insert(2, "aa");
+        assert_eq!(overlapping.find_all("aa"), vec![0, 2]);
+    }
+
+    #[test]
+    fn read_only_rejects_edits() {
+        let mut buffer = TextBuffer::from_text("hello\n").read_only();
+        assert!(buffer.is_read_only());
+        assert_eq!(buffer.try_insert(0, "x"), Err(EditError::ReadOnly));
+        assert_eq!(buffer.try_delete(0..1), Err(EditError::ReadOnly));
+        assert_eq!(buffer.try_replace(0..5, "bye"), Err(EditError::ReadOnly));
+        assert!(!buffer.undo());
+        assert_eq!(buffer.to_string(), "hello\n");
+
+        let result = std::panic::catch_unwind(move || {
+            let mut buffer = buffer;
+            buffer.insert(0, "x");
+        });
+        let message = result.unwrap_err();
+        assert!(
+            message
+                .downcast_ref::<&str>()
+                .is_some_and(|message| message.contains("read-only"))
+        );
+
+        let mut buffer = TextBuffer::from_text("hello");
+        assert_eq!(buffer.try_insert(6, "x"), Err(EditError::OutOfBounds));
+        assert_eq!(buffer.try_delete(2..9), Err(EditError::OutOfBounds));
+        assert_eq!(buffer.try_replace(0..5, "bye"), Ok(()));
+        assert_eq!(buffer.try_insert(3, "!"), Ok(()));
+        assert_eq!(buffer.to_string(), "bye!");
+
+        buffer.set_read_only(true);
+        assert_eq!(buffer.try_delete(..), Err(EditError::ReadOnly));
+        buffer.set_read_only(false);
+        assert!(buffer.undo());
+        assert_eq!(buffer.to_string(), "bye");
+    }
+
+    #[test]
+    fn restores_snapshot() {
+        let mut buffer = TextBuffer::from_text("first line\nsecond line\n");
+        buffer.insert(0, "// ");
+        buffer.delete(14..21);
+        let expected = buffer.to_string();
+
+        let snapshot = buffer.snapshot();
+        assert!(Arc::ptr_eq(&snapshot.original, &buffer.original));
+        assert_eq!(snapshot.len(), expected.len());
+
+        buffer.insert(buffer.len(), "third line\n");
+        buffer.replace(0..3, "");
+        buffer.compact();
+        assert_ne!(buffer.to_string(), expected);
+
+        buffer.restore(snapshot);
+        assert_eq!(buffer.to_string(), expected);
+        assert_eq!(buffer.len(), expected.len());
+        assert_eq!(buffer.len_lines(), 3);
+        assert_eq!(buffer.line(1).as_deref(), Some("line"));
+        assert!(!buffer.undo());
+    }
+
+    #[test]
+    fn offset_to_visual_column_expands_tabs() {
+        let mut buffer = TextBuffer::from_text("first\n\tif x {\n\t\tcall();\n");
+        buffer.insert(buffer.len(), "e\u{301}\tend");
+
+        assert_eq!(buffer.offset_to_visual_col(6, 4), 0);
+        assert_eq!(buffer.offset_to_visual_col(7, 4), 4);
+        assert_eq!(buffer.offset_to_visual_col(9, 4), 6);
+        assert_eq!(buffer.offset_to_visual_col(16, 4), 8);
+        assert_eq!(buffer.offset_to_visual_col(16, 2), 4);
+        assert_eq!(buffer.offset_to_visual_col(27, 4), 1);
+        assert_eq!(buffer.offset_to_visual_col(28, 4), 4);
+
+        for column in [0, 4, 5, 6] {
+            let offset = buffer.visual_col_to_offset(1, column, 4);
+            assert_eq!(buffer.offset_to_visual_col(offset, 4), column);
+        }
+    }
 }
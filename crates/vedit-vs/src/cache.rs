@@ -0,0 +1,156 @@
+//! Sharing parsed projects between solution loads.
+
+use crate::{Result, VcxProject, canonical};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+type Loader<'a> = Box<dyn FnMut(&Path) -> Result<VcxProject> + 'a>;
+
+/// Parsed C/C++ projects keyed by canonical path, so a project listed more
+/// than once, or by several solutions, is parsed a single time. See
+/// [`Solution::parse_with_cache`](crate::Solution::parse_with_cache).
+///
+/// Only the parse is shared: each solution still holds its own copy of the
+/// projects it lists.
+pub struct ProjectCache<'a> {
+    projects: HashMap<PathBuf, VcxProject>,
+    loader: Loader<'a>,
+}
+
+impl<'a> ProjectCache<'a> {
+    /// A cache loading projects with [`VcxProject::from_path`].
+    pub fn new() -> Self {
+        Self::with_loader(|path| VcxProject::from_path(path))
+    }
+
+    /// A cache loading projects with `loader`, e.g.
+    /// [`VcxProject::from_path_with_imports`].
+    pub fn with_loader(loader: impl FnMut(&Path) -> Result<VcxProject> + 'a) -> Self {
+        Self {
+            projects: HashMap::new(),
+            loader: Box::new(loader),
+        }
+    }
+
+    /// The project at `path`, loading it on first use. Failed loads are not
+    /// cached.
+    pub fn get_or_load(&mut self, path: &Path) -> Result<&VcxProject> {
+        let key = canonical(path);
+        if !self.projects.contains_key(&key) {
+            let project = (self.loader)(path)?;
+            self.projects.insert(key.clone(), project);
+        }
+        Ok(&self.projects[&key])
+    }
+
+    /// Number of cached projects.
+    pub fn len(&self) -> usize {
+        self.projects.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.projects.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.projects.clear();
+    }
+}
+
+impl Default for ProjectCache<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for ProjectCache<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProjectCache")
+            .field("projects", &self.projects.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Solution;
+    use std::cell::RefCell;
+    use std::fs;
+    use tempfile::tempdir;
+
+    const PROJECT: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<Project xmlns="http://schemas.microsoft.com/developer/msbuild/2003">
+  <ItemGroup>
+    <ClCompile Include="main.cpp" />
+  </ItemGroup>
+</Project>"#;
+
+    fn solution(projects: &[(&str, &str)]) -> String {
+        let mut sln = String::from("Microsoft Visual Studio Solution File, Format Version 12.00\n");
+        for (index, (name, path)) in projects.iter().enumerate() {
+            sln.push_str(&format!(
+                "Project(\"{{8BC9CEB8-8B4A-11D0-8D11-00A0C91BC942}}\") = \"{name}\", \"{path}\", \"{{00000000-0000-0000-0000-00000000000{index}}}\"\nEndProject\n"
+            ));
+        }
+        sln
+    }
+
+    #[test]
+    fn loads_shared_projects_once() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("sub")).unwrap();
+        fs::write(dir.path().join("Core.vcxproj"), PROJECT).unwrap();
+        fs::write(dir.path().join("sub/App.vcxproj"), PROJECT).unwrap();
+
+        let loads = RefCell::new(Vec::new());
+        let mut cache = ProjectCache::with_loader(|path| {
+            loads.borrow_mut().push(path.to_path_buf());
+            VcxProject::from_path(path)
+        });
+
+        let first = Solution::parse_with_cache(
+            &solution(&[
+                ("Core", "Core.vcxproj"),
+                ("App", "sub\\App.vcxproj"),
+                ("CoreAgain", "sub\\..\\Core.vcxproj"),
+            ]),
+            &dir.path().join("First.sln"),
+            &mut cache,
+        )
+        .unwrap();
+        let second = Solution::parse_with_cache(
+            &solution(&[("Core", "Core.vcxproj")]),
+            &dir.path().join("Second.sln"),
+            &mut cache,
+        )
+        .unwrap();
+
+        assert_eq!(loads.borrow().len(), 2);
+        assert_eq!(cache.len(), 2);
+        assert!(
+            first
+                .projects
+                .iter()
+                .all(|project| project.project.is_some())
+        );
+        assert_eq!(
+            second.projects[0].project.as_ref().unwrap().files.len(),
+            first.projects[0].project.as_ref().unwrap().files.len()
+        );
+
+        assert!(
+            cache
+                .get_or_load(&dir.path().join("Missing.vcxproj"))
+                .is_err()
+        );
+        assert!(
+            cache
+                .get_or_load(&dir.path().join("Missing.vcxproj"))
+                .is_err()
+        );
+        assert_eq!(loads.borrow().len(), 4);
+        assert_eq!(cache.len(), 2);
+    }
+}
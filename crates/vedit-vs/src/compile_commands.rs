@@ -0,0 +1,320 @@
+//! Export of solutions as a clang compilation database (`compile_commands.json`).
+
+use crate::{ConfigurationPlatform, Solution, VcxItemKind, expand_macros};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// The compiler driver named in synthesized commands. `clang-cl` accepts the
+/// MSVC-style options found in project files.
+const COMPILER: &str = "clang-cl";
+
+/// One entry of a compilation database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompileCommand {
+    /// Working directory of the compilation (the project directory).
+    pub directory: PathBuf,
+    pub file: PathBuf,
+    pub arguments: Vec<String>,
+    /// `arguments` joined into a single shell command line.
+    pub command: String,
+}
+
+impl Solution {
+    /// Synthesize a compile command for every source file of the projects
+    /// the solution builds in `config`.
+    ///
+    /// Commands carry the project's include directories, preprocessor
+    /// definitions, forced includes, disabled warnings, language standard and
+    /// additional options for the mapped project configuration. `vars`
+    /// supplies MSBuild macro values on top of the project defaults, with
+    /// `SolutionDir` defaulting to the solution's directory.
+    pub fn to_compile_commands(
+        &self,
+        config: &ConfigurationPlatform,
+        vars: &HashMap<String, String>,
+    ) -> Vec<CompileCommand> {
+        let solution_dir = self
+            .path
+            .parent()
+            .map(|dir| format!("{}/", dir.to_string_lossy().trim_end_matches('/')))
+            .unwrap_or_default();
+
+        let mut commands = Vec::new();
+        for project in &self.projects {
+            let Some(vcx) = &project.project else {
+                continue;
+            };
+            if !self.builds_project(project, config) {
+                continue;
+            }
+            let project_config = self.project_config(project, config);
+
+            let mut macros = vcx.default_macros(project_config);
+            macros.insert("SolutionDir".to_string(), solution_dir.clone());
+            macros.extend(vars.iter().map(|(k, v)| (k.clone(), v.clone())));
+
+            let resolved = vcx.resolve_macros(&macros);
+            let settings = resolved.effective_settings_for(project_config);
+            let compiler = &settings.compiler;
+            let directory = vcx
+                .path
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_else(|| PathBuf::from("."));
+
+            let mut common = Vec::new();
+            common.extend(
+                resolved
+                    .resolved_include_dirs(project_config)
+                    .into_iter()
+                    .map(|dir| format!("-I{}", dir.to_string_lossy())),
+            );
+            common.extend(
+                compiler
+                    .preprocessor_definitions
+                    .iter()
+                    .map(|def| format!("-D{def}")),
+            );
+            common.extend(
+                compiler
+                    .forced_includes
+                    .iter()
+                    .map(|header| format!("/FI{header}")),
+            );
+            common.extend(
+                compiler
+                    .disabled_warnings
+                    .iter()
+                    .map(|warning| format!("/wd{warning}")),
+            );
+
+            for item in vcx
+                .files
+                .iter()
+                .filter(|item| item.kind == VcxItemKind::Source)
+                .filter(|item| item.is_built_in(project_config))
+            {
+                let is_c = item
+                    .full_path
+                    .extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("c"));
+                let standard = if is_c {
+                    compiler.c_language_standard.as_deref().and_then(c_standard)
+                } else {
+                    compiler.language_standard.as_deref().and_then(cpp_standard)
+                };
+
+                let mut arguments = vec![COMPILER.to_string()];
+                arguments.extend(common.iter().cloned());
+                arguments.extend(item.per_file_defines.iter().map(|def| format!("-D{def}")));
+                arguments.extend(standard);
+                arguments.extend(
+                    compiler
+                        .additional_options
+                        .iter()
+                        .map(|option| expand_macros(option, &macros)),
+                );
+                arguments.push("/c".to_string());
+                arguments.push(item.full_path.to_string_lossy().to_string());
+
+                commands.push(CompileCommand {
+                    directory: directory.clone(),
+                    file: item.full_path.clone(),
+                    command: join_command(&arguments),
+                    arguments,
+                });
+            }
+        }
+        commands
+    }
+}
+
+/// Render commands as the JSON text of a `compile_commands.json` file.
+pub fn compile_commands_json(commands: &[CompileCommand]) -> String {
+    let mut out = String::from("[");
+    for (index, command) in commands.iter().enumerate() {
+        if index > 0 {
+            out.push(',');
+        }
+        let arguments: Vec<String> = command
+            .arguments
+            .iter()
+            .map(|argument| json_string(argument))
+            .collect();
+        out.push_str(&format!(
+            "\n  {{\n    \"directory\": {},\n    \"file\": {},\n    \"arguments\": [{}],\n    \"command\": {}\n  }}",
+            json_string(&command.directory.to_string_lossy()),
+            json_string(&command.file.to_string_lossy()),
+            arguments.join(", "),
+            json_string(&command.command),
+        ));
+    }
+    out.push_str("\n]\n");
+    out
+}
+
+/// `/std:` option for a `LanguageStandard` value such as `stdcpp20`.
+fn cpp_standard(value: &str) -> Option<String> {
+    let version = value.strip_prefix("stdcpp")?;
+    Some(format!("/std:c++{version}"))
+}
+
+/// `/std:` option for a `LanguageStandard_C` value such as `stdc17`.
+fn c_standard(value: &str) -> Option<String> {
+    let version = value.strip_prefix("stdc")?;
+    Some(format!("/std:c{version}"))
+}
+
+fn join_command(arguments: &[String]) -> String {
+    arguments
+        .iter()
+        .map(|argument| {
+            if argument.is_empty() || argument.contains([' ', '\t', '"']) {
+                format!("\"{}\"", argument.replace('"', "\\\""))
+            } else {
+                argument.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for ch in value.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            ch if u32::from(ch) < 0x20 => out.push_str(&format!("\\u{:04x}", u32::from(ch))),
+            ch => out.push(ch),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn synthesizes_commands_for_sources() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("App.vcxproj"),
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<Project xmlns="http://schemas.microsoft.com/developer/msbuild/2003">
+  <ItemGroup Label="ProjectConfigurations">
+    <ProjectConfiguration Include="Debug|x64">
+      <Configuration>Debug</Configuration>
+      <Platform>x64</Platform>
+    </ProjectConfiguration>
+  </ItemGroup>
+  <ItemDefinitionGroup Condition="'$(Configuration)|$(Platform)'=='Debug|x64'">
+    <ClCompile>
+      <AdditionalIncludeDirectories>include;$(SdkRoot)\inc;%(AdditionalIncludeDirectories)</AdditionalIncludeDirectories>
+      <PreprocessorDefinitions>_DEBUG;APP_NAME="demo";%(PreprocessorDefinitions)</PreprocessorDefinitions>
+      <LanguageStandard>stdcpp20</LanguageStandard>
+      <LanguageStandard_C>stdc17</LanguageStandard_C>
+      <AdditionalOptions>/utf-8 %(AdditionalOptions)</AdditionalOptions>
+    </ClCompile>
+  </ItemDefinitionGroup>
+  <ItemGroup>
+    <ClCompile Include="src\main.cpp" />
+    <ClCompile Include="src\legacy.c" />
+    <ClInclude Include="include\app.h" />
+  </ItemGroup>
+</Project>"#,
+        )
+        .unwrap();
+        let sln = dir.path().join("App.sln");
+        fs::write(
+            &sln,
+            "Project(\"{8BC9CEB8-8B4A-11D0-8D11-00A0C91BC942}\") = \"App\", \"App.vcxproj\", \"{AAAAAAAA-0000-0000-0000-000000000001}\"\nEndProject\n",
+        )
+        .unwrap();
+        let solution = Solution::from_path(&sln).unwrap();
+
+        let vars = HashMap::from([("SdkRoot".to_string(), "/opt/sdk".to_string())]);
+        let commands =
+            solution.to_compile_commands(&ConfigurationPlatform::new("Debug", "x64"), &vars);
+        assert_eq!(commands.len(), 2);
+
+        let main = commands
+            .iter()
+            .find(|command| command.file.ends_with("src/main.cpp"))
+            .unwrap();
+        let project_dir = dir.path().to_string_lossy().to_string();
+        assert_eq!(main.directory, dir.path());
+        assert_eq!(main.arguments[0], "clang-cl");
+        assert!(main.arguments.contains(&format!("-I{project_dir}/include")));
+        assert!(main.arguments.contains(&"-I/opt/sdk/inc".to_string()));
+        assert!(main.arguments.contains(&"-D_DEBUG".to_string()));
+        assert!(main.arguments.contains(&"/std:c++20".to_string()));
+        assert!(main.arguments.contains(&"/utf-8".to_string()));
+        assert!(main.command.contains("\"-DAPP_NAME=\\\"demo\\\"\""));
+
+        let legacy = commands
+            .iter()
+            .find(|command| command.file.ends_with("src/legacy.c"))
+            .unwrap();
+        assert!(legacy.arguments.contains(&"/std:c17".to_string()));
+
+        let json = compile_commands_json(&commands);
+        assert!(json.starts_with("[\n  {\n    \"directory\": "));
+        assert!(json.contains("\"-DAPP_NAME=\\\"demo\\\"\""));
+    }
+
+    #[test]
+    fn inherits_unconditioned_settings() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("Lib.vcxproj"),
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<Project xmlns="http://schemas.microsoft.com/developer/msbuild/2003">
+  <ItemGroup Label="ProjectConfigurations">
+    <ProjectConfiguration Include="Debug|x64">
+      <Configuration>Debug</Configuration>
+      <Platform>x64</Platform>
+    </ProjectConfiguration>
+  </ItemGroup>
+  <ItemDefinitionGroup>
+    <ClCompile>
+      <AdditionalIncludeDirectories>include</AdditionalIncludeDirectories>
+      <PreprocessorDefinitions>SHARED</PreprocessorDefinitions>
+    </ClCompile>
+  </ItemDefinitionGroup>
+  <ItemGroup>
+    <ClCompile Include="lib.cpp" />
+  </ItemGroup>
+</Project>"#,
+        )
+        .unwrap();
+        let sln = dir.path().join("Lib.sln");
+        fs::write(
+            &sln,
+            "Project(\"{8BC9CEB8-8B4A-11D0-8D11-00A0C91BC942}\") = \"Lib\", \"Lib.vcxproj\", \"{AAAAAAAA-0000-0000-0000-000000000002}\"\nEndProject\n",
+        )
+        .unwrap();
+        let solution = Solution::from_path(&sln).unwrap();
+        let debug = ConfigurationPlatform::new("Debug", "x64");
+
+        let vcx = solution.projects[0].project.as_ref().unwrap();
+        assert_eq!(
+            vcx.resolved_include_dirs(&debug),
+            vec![dir.path().join("include")]
+        );
+
+        let commands = solution.to_compile_commands(&debug, &HashMap::new());
+        assert_eq!(commands.len(), 1);
+        let include = format!("-I{}/include", dir.path().to_string_lossy());
+        assert!(commands[0].arguments.contains(&include));
+        assert!(commands[0].arguments.contains(&"-DSHARED".to_string()));
+    }
+}
@@ -0,0 +1,303 @@
+//! Lightweight parser for C# project files (.csproj).
+
+use crate::{
+    Result, VisualStudioError, normalize_include, normalize_path, paths_equal_ci, read_text,
+    resolve_path,
+};
+use roxmltree::Document;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Parsed representation of a C# project (.csproj).
+///
+/// Only the information the editor needs is extracted: source files, NuGet
+/// packages and a few identifying properties.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CsProject {
+    pub name: String,
+    pub path: PathBuf,
+    /// Whether the project uses the SDK-style format (`<Project Sdk="...">`).
+    pub sdk_style: bool,
+    /// C# source files compiled by the project.
+    pub files: Vec<CsItem>,
+    pub package_references: Vec<PackageReference>,
+    /// `OutputType`, e.g. `Exe` or `Library`.
+    pub output_type: Option<String>,
+    /// `TargetFramework` (`TargetFrameworks` or `TargetFrameworkVersion` as
+    /// fallbacks), e.g. `net8.0` or `v4.7.2`.
+    pub target_framework: Option<String>,
+    pub root_namespace: Option<String>,
+}
+
+/// A `<Compile>` entry of a C# project.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CsItem {
+    pub include: PathBuf,
+    pub full_path: PathBuf,
+}
+
+/// A `<PackageReference>` entry of a C# project.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PackageReference {
+    pub name: String,
+    pub version: Option<String>,
+}
+
+impl CsProject {
+    /// Load a C# project from disk.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = read_text(path).map_err(|source| VisualStudioError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        let mut project = Self::parse(&contents, path)?;
+        if project.sdk_style {
+            project.add_default_compile_items(&contents);
+        }
+        Ok(project)
+    }
+
+    /// Parse a C# project from a string.
+    ///
+    /// Only explicit `<Compile Include>` items are collected; the implicit
+    /// `**/*.cs` items of SDK-style projects are added by [`CsProject::from_path`],
+    /// which can look at the project directory.
+    pub fn parse(contents: &str, path: &Path) -> Result<Self> {
+        let document = Document::parse(contents).map_err(|source| VisualStudioError::Xml {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        let project_dir = project_dir(path);
+        let root = document.root_element();
+        let sdk_style = root.attribute("Sdk").is_some()
+            || root.children().any(|child| child.has_tag_name("Sdk"));
+
+        let mut files = Vec::new();
+        let mut package_references = Vec::new();
+        let mut output_type = None;
+        let mut target_framework = None;
+        let mut target_frameworks = None;
+        let mut target_framework_version = None;
+        let mut root_namespace = None;
+
+        for node in document.descendants().filter(|n| n.is_element()) {
+            let text = || {
+                node.text()
+                    .map(str::trim)
+                    .filter(|text| !text.is_empty())
+                    .map(str::to_string)
+            };
+            match node.tag_name().name() {
+                "Compile" => {
+                    let Some(include) = node
+                        .attribute("Include")
+                        .filter(|include| !include.contains('*'))
+                        .and_then(normalize_include)
+                    else {
+                        continue;
+                    };
+                    let full_path = resolve_path(&project_dir, &include);
+                    if !files
+                        .iter()
+                        .any(|item: &CsItem| paths_equal_ci(&item.full_path, &full_path))
+                    {
+                        files.push(CsItem { include, full_path });
+                    }
+                }
+                "PackageReference" => {
+                    let Some(name) = node.attribute("Include").map(str::trim) else {
+                        continue;
+                    };
+                    let version = node
+                        .attribute("Version")
+                        .map(str::to_string)
+                        .or_else(|| {
+                            node.children()
+                                .find(|child| child.has_tag_name("Version"))
+                                .and_then(|child| child.text())
+                                .map(|text| text.trim().to_string())
+                        })
+                        .filter(|version| !version.is_empty());
+                    package_references.push(PackageReference {
+                        name: name.to_string(),
+                        version,
+                    });
+                }
+                "OutputType" => output_type = output_type.or_else(text),
+                "TargetFramework" => target_framework = target_framework.or_else(text),
+                "TargetFrameworks" => target_frameworks = target_frameworks.or_else(text),
+                "TargetFrameworkVersion" => {
+                    target_framework_version = target_framework_version.or_else(text)
+                }
+                "RootNamespace" => root_namespace = root_namespace.or_else(text),
+                _ => {}
+            }
+        }
+
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| "Unnamed".to_string());
+
+        Ok(CsProject {
+            name,
+            path: normalize_path(path),
+            sdk_style,
+            files,
+            package_references,
+            output_type,
+            target_framework: target_framework
+                .or(target_frameworks)
+                .or(target_framework_version),
+            root_namespace,
+        })
+    }
+
+    /// Add the `.cs` files SDK-style projects compile implicitly, skipping
+    /// `bin`, `obj`, hidden directories and `<Compile Remove>` entries.
+    fn add_default_compile_items(&mut self, contents: &str) {
+        let Ok(document) = Document::parse(contents) else {
+            return;
+        };
+        let disabled = document
+            .descendants()
+            .filter(|node| node.has_tag_name("EnableDefaultCompileItems"))
+            .any(|node| node.text().map(str::trim) == Some("false"));
+        if disabled {
+            return;
+        }
+
+        let project_dir = project_dir(&self.path);
+        let removed: Vec<PathBuf> = document
+            .descendants()
+            .filter(|node| node.has_tag_name("Compile"))
+            .filter_map(|node| node.attribute("Remove"))
+            .filter_map(normalize_include)
+            .map(|include| resolve_path(&project_dir, &include))
+            .collect();
+
+        let mut found = Vec::new();
+        collect_cs_files(&project_dir, &mut found);
+        found.sort();
+
+        for full_path in found {
+            if removed.iter().any(|path| paths_equal_ci(path, &full_path))
+                || self
+                    .files
+                    .iter()
+                    .any(|item| paths_equal_ci(&item.full_path, &full_path))
+            {
+                continue;
+            }
+            let include = full_path
+                .strip_prefix(&project_dir)
+                .map(Path::to_path_buf)
+                .unwrap_or_else(|_| full_path.clone());
+            self.files.push(CsItem { include, full_path });
+        }
+    }
+
+    /// Move the project to `new_project_dir`, recomputing the project path and
+    /// every item `full_path` from its `include`.
+    pub fn rebase(&mut self, new_project_dir: &Path) {
+        let new_project_dir = normalize_path(new_project_dir);
+        if let Some(file_name) = self.path.file_name() {
+            self.path = new_project_dir.join(file_name);
+        }
+        for item in &mut self.files {
+            item.full_path = resolve_path(&new_project_dir, &item.include);
+        }
+    }
+}
+
+fn project_dir(path: &Path) -> PathBuf {
+    path.parent()
+        .map(normalize_path)
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+fn collect_cs_files(dir: &Path, found: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if path.is_dir() {
+            let skip = name.starts_with('.')
+                || name.eq_ignore_ascii_case("bin")
+                || name.eq_ignore_ascii_case("obj");
+            if !skip {
+                collect_cs_files(&path, found);
+            }
+        } else if path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("cs"))
+        {
+            found.push(normalize_path(&path));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_old_style_csproj() {
+        let project = CsProject::parse(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<Project ToolsVersion="15.0" xmlns="http://schemas.microsoft.com/developer/msbuild/2003">
+  <PropertyGroup>
+    <OutputType>WinExe</OutputType>
+    <RootNamespace>Tools.Viewer</RootNamespace>
+    <TargetFrameworkVersion>v4.7.2</TargetFrameworkVersion>
+  </PropertyGroup>
+  <ItemGroup>
+    <Compile Include="Program.cs" />
+    <Compile Include="Properties\AssemblyInfo.cs" />
+    <Compile Include="Generated\*.cs" />
+  </ItemGroup>
+  <ItemGroup>
+    <PackageReference Include="Newtonsoft.Json">
+      <Version>13.0.3</Version>
+    </PackageReference>
+  </ItemGroup>
+</Project>"#,
+            Path::new("/work/Viewer/Viewer.csproj"),
+        )
+        .unwrap();
+
+        assert_eq!(project.name, "Viewer");
+        assert!(!project.sdk_style);
+        assert_eq!(project.output_type.as_deref(), Some("WinExe"));
+        assert_eq!(project.root_namespace.as_deref(), Some("Tools.Viewer"));
+        assert_eq!(project.target_framework.as_deref(), Some("v4.7.2"));
+        let includes: Vec<_> = project.files.iter().map(|f| f.include.clone()).collect();
+        assert_eq!(
+            includes,
+            vec![
+                PathBuf::from("Program.cs"),
+                PathBuf::from("Properties/AssemblyInfo.cs")
+            ]
+        );
+        assert_eq!(
+            project.files[1].full_path,
+            PathBuf::from("/work/Viewer/Properties/AssemblyInfo.cs")
+        );
+        assert_eq!(
+            project.package_references,
+            vec![PackageReference {
+                name: "Newtonsoft.Json".to_string(),
+                version: Some("13.0.3".to_string()),
+            }]
+        );
+    }
+}
@@ -0,0 +1,278 @@
+//! Loading of property sheets (`.props`) imported by C/C++ projects.
+
+use crate::{
+    ConfigurationPlatform, ConfigurationSettings, Result, VcxProject, VisualStudioError, canonical,
+    extract_config_from_condition, normalize_path, parse_item_definition_group, read_text,
+    resolve_path,
+};
+use roxmltree::Document;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+impl VcxProject {
+    /// Load a project like [`VcxProject::from_path`] and merge the
+    /// `ClCompile`/`Link` item definitions of the property sheets it imports.
+    ///
+    /// `<Import>` elements are followed recursively, relative to the importing
+    /// file. Imports with their own `Condition`, imports using MSBuild
+    /// properties (`$(VCTargetsPath)\Microsoft.Cpp.props`, ...), `Microsoft.Cpp.*`
+    /// sheets and missing files are skipped. An `ImportGroup` conditioned on a
+    /// configuration limits its sheets to that configuration. Imported values
+    /// come first; the project's own item definitions override them.
+    pub fn from_path_with_imports(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let mut project = Self::from_path(path)?;
+
+        let mut visited = HashSet::from([(canonical(path), None)]);
+        let mut imported: HashMap<String, ConfigurationSettings> = HashMap::new();
+        collect_imports(path, None, false, &mut visited, &mut imported)?;
+
+        for (key, mut settings) in imported {
+            if let Some(own) = project.config_settings.get(&key) {
+                settings.overlay(own);
+            }
+            project.config_settings.insert(key, settings);
+        }
+        Ok(project)
+    }
+}
+
+/// Walk `file` in document order, following imports and (for imported
+/// sheets) layering their item definitions into `imported`. `scope` is the
+/// configuration key an enclosing `ImportGroup` restricted the walk to.
+///
+/// `visited` holds `(file, scope)` pairs, so a sheet shared by several
+/// configurations' `ImportGroup`s is walked once per configuration while
+/// cycles and repeated imports within one scope are cut.
+fn collect_imports(
+    file: &Path,
+    scope: Option<&str>,
+    is_sheet: bool,
+    visited: &mut HashSet<(PathBuf, Option<String>)>,
+    imported: &mut HashMap<String, ConfigurationSettings>,
+) -> Result<()> {
+    let contents = read_text(file).map_err(|source| VisualStudioError::Io {
+        path: file.to_path_buf(),
+        source,
+    })?;
+    let document = Document::parse(&contents).map_err(|source| VisualStudioError::Xml {
+        path: file.to_path_buf(),
+        source,
+    })?;
+    let base_dir = file
+        .parent()
+        .map(normalize_path)
+        .unwrap_or_else(|| PathBuf::from("."));
+
+    for node in document.descendants().filter(|n| n.is_element()) {
+        match node.tag_name().name() {
+            "Import" if node.attribute("Condition").is_none() => {
+                let group_condition = node
+                    .parent_element()
+                    .filter(|parent| parent.has_tag_name("ImportGroup"))
+                    .and_then(|parent| parent.attribute("Condition"));
+                let import_scope = match group_condition {
+                    Some(condition) => match extract_config_from_condition(condition) {
+                        Some(key) => Some(key),
+                        None => continue,
+                    },
+                    None => scope.map(str::to_string),
+                };
+                if scope.is_some() && import_scope.as_deref() != scope {
+                    continue;
+                }
+
+                let Some(target) = node
+                    .attribute("Project")
+                    .map(str::trim)
+                    .filter(|project| !project.contains("$("))
+                    .map(|project| resolve_path(&base_dir, Path::new(&project.replace('\\', "/"))))
+                else {
+                    continue;
+                };
+                let builtin = target
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_ascii_lowercase())
+                    .is_some_and(|name| name.starts_with("microsoft.cpp."));
+                if builtin
+                    || !target.is_file()
+                    || !visited.insert((canonical(&target), import_scope.clone()))
+                {
+                    continue;
+                }
+                collect_imports(&target, import_scope.as_deref(), true, visited, imported)?;
+            }
+            "ItemDefinitionGroup" if is_sheet => {
+                let key = match node.attribute("Condition") {
+                    None => scope.unwrap_or("").to_string(),
+                    Some(condition) => match extract_config_from_condition(condition) {
+                        Some(key) => key,
+                        None => continue,
+                    },
+                };
+                if scope.is_some_and(|scope| scope != key) {
+                    continue;
+                }
+
+                let mut settings = ConfigurationSettings {
+                    config: ConfigurationPlatform::parse(&key),
+                    ..Default::default()
+                };
+                parse_item_definition_group(node, &mut settings);
+                imported.entry(key).or_default().overlay(&settings);
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn write(dir: &Path, name: &str, body: &str) -> PathBuf {
+        let path = dir.join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(
+            &path,
+            format!(
+                r#"<?xml version="1.0" encoding="utf-8"?>
+<Project xmlns="http://schemas.microsoft.com/developer/msbuild/2003">
+{body}
+</Project>"#
+            ),
+        )
+        .unwrap();
+        path
+    }
+
+    #[test]
+    fn merges_imported_property_sheets() {
+        let dir = tempdir().unwrap();
+        write(
+            dir.path(),
+            "props/common.props",
+            r#"  <Import Project="nested.props" />
+  <ItemDefinitionGroup>
+    <ClCompile>
+      <AdditionalIncludeDirectories>..\shared;%(AdditionalIncludeDirectories)</AdditionalIncludeDirectories>
+      <PreprocessorDefinitions>COMMON;%(PreprocessorDefinitions)</PreprocessorDefinitions>
+      <LanguageStandard>stdcpp17</LanguageStandard>
+    </ClCompile>
+  </ItemDefinitionGroup>"#,
+        );
+        write(
+            dir.path(),
+            "props/nested.props",
+            r#"  <Import Project="common.props" />
+  <ItemDefinitionGroup>
+    <Link>
+      <AdditionalDependencies>nested.lib;%(AdditionalDependencies)</AdditionalDependencies>
+    </Link>
+  </ItemDefinitionGroup>"#,
+        );
+        write(
+            dir.path(),
+            "props/debug.props",
+            r#"  <ItemDefinitionGroup>
+    <ClCompile>
+      <PreprocessorDefinitions>DEBUG_SHEET;%(PreprocessorDefinitions)</PreprocessorDefinitions>
+    </ClCompile>
+  </ItemDefinitionGroup>"#,
+        );
+        let project_path = write(
+            dir.path(),
+            "App.vcxproj",
+            r#"  <ItemGroup Label="ProjectConfigurations">
+    <ProjectConfiguration Include="Debug|x64" />
+    <ProjectConfiguration Include="Release|x64" />
+  </ItemGroup>
+  <Import Project="$(VCTargetsPath)\Microsoft.Cpp.Default.props" />
+  <Import Project="props\common.props" />
+  <Import Project="props\missing.props" />
+  <ImportGroup Label="PropertySheets" Condition="'$(Configuration)|$(Platform)'=='Debug|x64'">
+    <Import Project="props\debug.props" />
+  </ImportGroup>
+  <ItemDefinitionGroup Condition="'$(Configuration)|$(Platform)'=='Debug|x64'">
+    <ClCompile>
+      <PreprocessorDefinitions>_DEBUG;%(PreprocessorDefinitions)</PreprocessorDefinitions>
+      <LanguageStandard>stdcpp20</LanguageStandard>
+    </ClCompile>
+  </ItemDefinitionGroup>"#,
+        );
+        let debug = ConfigurationPlatform::new("Debug", "x64");
+        let release = ConfigurationPlatform::new("Release", "x64");
+
+        let cheap = VcxProject::from_path(&project_path).unwrap();
+        assert!(
+            cheap
+                .effective_settings_for(&release)
+                .compiler
+                .include_dirs
+                .is_empty()
+        );
+
+        let project = VcxProject::from_path_with_imports(&project_path).unwrap();
+        let settings = project.effective_settings_for(&debug);
+        assert_eq!(settings.compiler.include_dirs, vec!["../shared"]);
+        assert_eq!(
+            settings.compiler.preprocessor_definitions,
+            vec!["COMMON", "DEBUG_SHEET", "_DEBUG"]
+        );
+        assert_eq!(
+            settings.compiler.language_standard.as_deref(),
+            Some("stdcpp20")
+        );
+        assert_eq!(settings.linker.additional_dependencies, vec!["nested.lib"]);
+
+        let settings = project.effective_settings_for(&release);
+        assert_eq!(settings.compiler.preprocessor_definitions, vec!["COMMON"]);
+        assert_eq!(
+            settings.compiler.language_standard.as_deref(),
+            Some("stdcpp17")
+        );
+    }
+
+    #[test]
+    fn imports_shared_sheet_per_configuration() {
+        let dir = tempdir().unwrap();
+        write(
+            dir.path(),
+            "shared.props",
+            r#"  <ItemDefinitionGroup>
+    <ClCompile>
+      <PreprocessorDefinitions>COMMON;%(PreprocessorDefinitions)</PreprocessorDefinitions>
+    </ClCompile>
+  </ItemDefinitionGroup>"#,
+        );
+        let project_path = write(
+            dir.path(),
+            "App.vcxproj",
+            r#"  <ItemGroup Label="ProjectConfigurations">
+    <ProjectConfiguration Include="Debug|x64" />
+    <ProjectConfiguration Include="Release|x64" />
+  </ItemGroup>
+  <ImportGroup Label="PropertySheets" Condition="'$(Configuration)|$(Platform)'=='Debug|x64'">
+    <Import Project="shared.props" />
+  </ImportGroup>
+  <ImportGroup Label="PropertySheets" Condition="'$(Configuration)|$(Platform)'=='Release|x64'">
+    <Import Project="shared.props" />
+    <Import Project="shared.props" />
+  </ImportGroup>"#,
+        );
+
+        let project = VcxProject::from_path_with_imports(&project_path).unwrap();
+        for config in ["Debug", "Release"] {
+            let settings =
+                project.effective_settings_for(&ConfigurationPlatform::new(config, "x64"));
+            assert_eq!(
+                settings.compiler.preprocessor_definitions,
+                vec!["COMMON"],
+                "{config}"
+            );
+        }
+    }
+}
@@ -5,11 +5,22 @@
 //! include paths, preprocessor definitions, and other project metadata.
 
 use roxmltree::Document;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fs;
 use std::io;
 use std::path::{Component, Path, PathBuf};
 use thiserror::Error;
+use vedit_syntax::Language;
+
+mod cache;
+mod compile_commands;
+mod csproj;
+mod imports;
+mod writer;
+
+pub use cache::ProjectCache;
+pub use compile_commands::{CompileCommand, compile_commands_json};
+pub use csproj::{CsItem, CsProject, PackageReference};
 
 /// Errors that can occur when parsing Visual Studio solutions and projects.
 #[derive(Debug, Error)]
@@ -38,6 +49,7 @@ pub type Result<T> = std::result::Result<T, VisualStudioError>;
 
 /// A build configuration + platform pair (e.g., "Debug|x64").
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ConfigurationPlatform {
     pub configuration: String,
     pub platform: String,
@@ -74,6 +86,7 @@ impl std::fmt::Display for ConfigurationPlatform {
 
 /// Representation of a Visual Studio solution (.sln) file.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Solution {
     pub name: String,
     pub path: PathBuf,
@@ -88,10 +101,14 @@ pub struct Solution {
     pub vs_version: Option<String>,
     /// Minimum VS version from the solution header.
     pub minimum_vs_version: Option<String>,
+    /// Key/value pairs from a legacy `GlobalSection(SourceCodeControl)`
+    /// (VSS/TFS bindings), if the solution has one.
+    pub source_control: Option<HashMap<String, String>>,
 }
 
 /// Maps a solution configuration to a project configuration.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ProjectConfigurationMapping {
     /// The solution-level configuration (e.g., Debug|x64).
     pub solution_config: ConfigurationPlatform,
@@ -103,8 +120,23 @@ pub struct ProjectConfigurationMapping {
     pub deploy: bool,
 }
 
+/// A solution configuration that builds a project for a different platform.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PlatformMismatch {
+    /// Name of the project.
+    pub project: String,
+    /// The solution-level configuration (e.g., Debug|x64).
+    pub solution_config: ConfigurationPlatform,
+    /// The project configuration it maps to (e.g., Debug|Win32).
+    pub project_config: ConfigurationPlatform,
+    /// Whether the project builds in this configuration.
+    pub build: bool,
+}
+
 /// A virtual folder in the solution for organizing projects.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct SolutionFolder {
     pub name: String,
     pub guid: String,
@@ -114,23 +146,34 @@ pub struct SolutionFolder {
 
 /// A project referenced from a Visual Studio solution.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct SolutionProject {
     pub name: String,
     pub relative_path: PathBuf,
     pub absolute_path: PathBuf,
     pub project_type_guid: Option<String>,
     pub project_guid: Option<String>,
+    /// Uppercase GUIDs from the legacy `ProjectSection(ProjectDependencies)`.
+    pub dependencies: Vec<String>,
     pub project: Option<VcxProject>,
+    /// Parsed C# project, for `.csproj` entries.
+    pub csproj: Option<CsProject>,
     pub load_error: Option<String>,
 }
 
 /// Parsed representation of a Visual Studio C/C++ project (.vcxproj).
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct VcxProject {
     pub name: String,
     pub path: PathBuf,
     pub files: Vec<VcxItem>,
+    /// Whether the default (first declared) configuration builds an
+    /// application. See [`VcxProject::produces_executable_in`] for other
+    /// configurations.
     pub produces_executable: bool,
+    /// Whether any configuration builds an application.
+    pub executable_in_any_config: bool,
     /// Available build configurations in this project.
     pub configurations: Vec<ConfigurationPlatform>,
     /// Configuration-specific settings.
@@ -139,10 +182,16 @@ pub struct VcxProject {
     pub project_references: Vec<ProjectReference>,
     /// Global properties that apply to all configurations.
     pub globals: ProjectGlobals,
+    /// `ProjectCapability` items (e.g. `VisualStudioWindowsDesktop`) hinting
+    /// at the project's flavor.
+    pub capabilities: Vec<String>,
+    /// Managed assemblies referenced by a C++/CLI project.
+    pub assembly_references: Vec<AssemblyReference>,
 }
 
 /// Global project properties.
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ProjectGlobals {
     /// Project GUID.
     pub project_guid: Option<String>,
@@ -158,6 +207,7 @@ pub struct ProjectGlobals {
 
 /// Configuration-specific build settings.
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ConfigurationSettings {
     /// The configuration this applies to.
     pub config: Option<ConfigurationPlatform>,
@@ -167,6 +217,8 @@ pub struct ConfigurationSettings {
     pub use_of_mfc: Option<String>,
     /// Character set (Unicode, MultiByte, NotSet).
     pub character_set: Option<String>,
+    /// Platform toolset for this configuration (e.g., v143, v142).
+    pub platform_toolset: Option<String>,
     /// Whole program optimization.
     pub whole_program_optimization: Option<bool>,
     /// Output directory.
@@ -177,14 +229,124 @@ pub struct ConfigurationSettings {
     pub target_name: Option<String>,
     /// Target extension (e.g., .exe, .dll, .lib).
     pub target_ext: Option<String>,
+    /// Command line run before the build (`PreBuildEvent/Command`).
+    pub pre_build_command: Option<String>,
+    /// Message shown when the pre-build event runs.
+    pub pre_build_message: Option<String>,
+    /// Command line run after the build (`PostBuildEvent/Command`).
+    pub post_build_command: Option<String>,
+    /// Message shown when the post-build event runs.
+    pub post_build_message: Option<String>,
     /// Compiler settings.
     pub compiler: CompilerSettings,
     /// Linker settings.
     pub linker: LinkerSettings,
+    /// NMake settings of `Makefile`-type configurations.
+    pub nmake: Option<NMakeSettings>,
+}
+
+impl ConfigurationSettings {
+    /// The C++ `LanguageStandard` in effect when building with `toolset`.
+    ///
+    /// An explicit standard wins; when it is unset (or `Default`) the
+    /// compiler's own default applies. Every toolset since v140 (and ClangCL,
+    /// which mimics cl.exe) defaults to `stdcpp14`; older toolsets have no
+    /// `/std` switch and yield `Default`.
+    pub fn effective_language_standard(&self, toolset: &str) -> String {
+        if let Some(standard) = self
+            .compiler
+            .language_standard
+            .as_deref()
+            .map(str::trim)
+            .filter(|standard| !standard.is_empty() && *standard != "Default")
+        {
+            return standard.to_string();
+        }
+
+        let toolset = toolset.trim();
+        let modern = toolset.eq_ignore_ascii_case("ClangCL")
+            || toolset.eq_ignore_ascii_case("LLVM")
+            || toolset
+                .strip_prefix('v')
+                .and_then(|version| version.get(..3))
+                .and_then(|version| version.parse::<u32>().ok())
+                .is_some_and(|version| version >= 140);
+        if modern { "stdcpp14" } else { "Default" }.to_string()
+    }
+
+    /// Definitions Visual Studio implies for this configuration, to be
+    /// unioned with the explicit `PreprocessorDefinitions`.
+    ///
+    /// `_DEBUG`/`NDEBUG` follow a debug runtime library, or the
+    /// configuration name when no runtime is set. The subsystem adds
+    /// `_CONSOLE`/`_WINDOWS`, the character set `UNICODE` and `_UNICODE` or
+    /// `_MBCS`, and library configuration types `_LIB`/`_USRDLL`.
+    pub fn implicit_definitions(&self) -> Vec<String> {
+        let mut definitions = Vec::new();
+        let debug = match self.compiler.runtime_library.as_deref() {
+            Some(runtime) => {
+                runtime.trim().ends_with("Debug") || runtime.trim().ends_with("DebugDLL")
+            }
+            None => self
+                .config
+                .as_ref()
+                .is_some_and(|config| config.configuration.to_ascii_lowercase().contains("debug")),
+        };
+        definitions.push(if debug { "_DEBUG" } else { "NDEBUG" });
+
+        match self.linker.subsystem.as_deref().map(str::trim) {
+            Some(subsystem) if subsystem.eq_ignore_ascii_case("Console") => {
+                definitions.push("_CONSOLE")
+            }
+            Some(subsystem) if subsystem.eq_ignore_ascii_case("Windows") => {
+                definitions.push("_WINDOWS")
+            }
+            _ => {}
+        }
+        match self.character_set.as_deref().map(str::trim) {
+            Some("Unicode") => definitions.extend(["UNICODE", "_UNICODE"]),
+            Some("MultiByte") => definitions.push("_MBCS"),
+            _ => {}
+        }
+        match self.configuration_type {
+            Some(ConfigurationType::StaticLibrary) => definitions.push("_LIB"),
+            Some(ConfigurationType::DynamicLibrary) => definitions.push("_USRDLL"),
+            _ => {}
+        }
+        definitions.into_iter().map(str::to_string).collect()
+    }
+
+    /// Apply the values set in `other` on top of these settings.
+    fn overlay(&mut self, other: &ConfigurationSettings) {
+        overlay_option(&mut self.config, &other.config);
+        overlay_option(&mut self.configuration_type, &other.configuration_type);
+        overlay_option(&mut self.use_of_mfc, &other.use_of_mfc);
+        overlay_option(&mut self.character_set, &other.character_set);
+        overlay_option(&mut self.platform_toolset, &other.platform_toolset);
+        overlay_option(
+            &mut self.whole_program_optimization,
+            &other.whole_program_optimization,
+        );
+        overlay_option(&mut self.out_dir, &other.out_dir);
+        overlay_option(&mut self.int_dir, &other.int_dir);
+        overlay_option(&mut self.target_name, &other.target_name);
+        overlay_option(&mut self.target_ext, &other.target_ext);
+        overlay_option(&mut self.pre_build_command, &other.pre_build_command);
+        overlay_option(&mut self.pre_build_message, &other.pre_build_message);
+        overlay_option(&mut self.post_build_command, &other.post_build_command);
+        overlay_option(&mut self.post_build_message, &other.post_build_message);
+        self.compiler.overlay(&other.compiler);
+        self.linker.overlay(&other.linker);
+        match (&mut self.nmake, &other.nmake) {
+            (Some(nmake), Some(other)) => nmake.overlay(other),
+            (nmake, other) => overlay_option(nmake, other),
+        }
+    }
 }
 
 /// Output type of the project.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum ConfigurationType {
     Application,
     DynamicLibrary,
@@ -213,6 +375,7 @@ impl ConfigurationType {
 
 /// Compiler (ClCompile) settings.
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct CompilerSettings {
     /// Additional include directories.
     pub include_dirs: Vec<String>,
@@ -244,12 +407,55 @@ pub struct CompilerSettings {
     pub precompiled_header: Option<String>,
     /// Precompiled header file.
     pub precompiled_header_file: Option<String>,
+    /// Warning numbers disabled with `/wd` (DisableSpecificWarnings).
+    pub disabled_warnings: Vec<String>,
+    /// Headers force-included with `/FI` (ForcedIncludeFiles).
+    pub forced_includes: Vec<String>,
     /// Additional compiler options.
     pub additional_options: Vec<String>,
 }
 
+impl CompilerSettings {
+    fn overlay(&mut self, other: &CompilerSettings) {
+        append_unique(&mut self.include_dirs, &other.include_dirs);
+        append_unique(
+            &mut self.preprocessor_definitions,
+            &other.preprocessor_definitions,
+        );
+        overlay_option(&mut self.warning_level, &other.warning_level);
+        overlay_option(
+            &mut self.treat_warnings_as_errors,
+            &other.treat_warnings_as_errors,
+        );
+        overlay_option(&mut self.optimization, &other.optimization);
+        overlay_option(
+            &mut self.function_level_linking,
+            &other.function_level_linking,
+        );
+        overlay_option(&mut self.intrinsic_functions, &other.intrinsic_functions);
+        overlay_option(&mut self.sdl_check, &other.sdl_check);
+        overlay_option(&mut self.conformance_mode, &other.conformance_mode);
+        overlay_option(&mut self.language_standard, &other.language_standard);
+        overlay_option(&mut self.c_language_standard, &other.c_language_standard);
+        overlay_option(
+            &mut self.debug_information_format,
+            &other.debug_information_format,
+        );
+        overlay_option(&mut self.runtime_library, &other.runtime_library);
+        overlay_option(&mut self.precompiled_header, &other.precompiled_header);
+        overlay_option(
+            &mut self.precompiled_header_file,
+            &other.precompiled_header_file,
+        );
+        append_unique(&mut self.disabled_warnings, &other.disabled_warnings);
+        append_unique(&mut self.forced_includes, &other.forced_includes);
+        append_unique(&mut self.additional_options, &other.additional_options);
+    }
+}
+
 /// Linker settings.
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct LinkerSettings {
     /// Additional library directories.
     pub library_dirs: Vec<String>,
@@ -259,6 +465,10 @@ pub struct LinkerSettings {
     pub generate_debug_information: Option<bool>,
     /// Subsystem (Console, Windows, Native).
     pub subsystem: Option<String>,
+    /// Minimum subsystem version (`5.01`, `6.0`, ...), from `SubSystem` or a
+    /// `/SUBSYSTEM:` switch in the additional options. Controls the minimum
+    /// Windows version the binary runs on.
+    pub subsystem_version: Option<String>,
     /// Enable COMDAT folding.
     pub enable_comdat_folding: Option<bool>,
     /// Optimize references.
@@ -269,12 +479,94 @@ pub struct LinkerSettings {
     pub import_library: Option<String>,
     /// Program database file.
     pub program_database_file: Option<String>,
+    /// Module definition (.def) file controlling DLL exports.
+    pub module_definition_file: Option<String>,
     /// Additional linker options.
     pub additional_options: Vec<String>,
 }
 
+impl LinkerSettings {
+    fn overlay(&mut self, other: &LinkerSettings) {
+        append_unique(&mut self.library_dirs, &other.library_dirs);
+        append_unique(
+            &mut self.additional_dependencies,
+            &other.additional_dependencies,
+        );
+        overlay_option(
+            &mut self.generate_debug_information,
+            &other.generate_debug_information,
+        );
+        overlay_option(&mut self.subsystem, &other.subsystem);
+        overlay_option(&mut self.subsystem_version, &other.subsystem_version);
+        overlay_option(
+            &mut self.enable_comdat_folding,
+            &other.enable_comdat_folding,
+        );
+        overlay_option(&mut self.optimize_references, &other.optimize_references);
+        overlay_option(&mut self.output_file, &other.output_file);
+        overlay_option(&mut self.import_library, &other.import_library);
+        overlay_option(
+            &mut self.program_database_file,
+            &other.program_database_file,
+        );
+        overlay_option(
+            &mut self.module_definition_file,
+            &other.module_definition_file,
+        );
+        append_unique(&mut self.additional_options, &other.additional_options);
+    }
+}
+
+/// Build settings of a `Makefile`-type (NMake) configuration, whose build
+/// is driven by external commands instead of ClCompile/Link.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct NMakeSettings {
+    /// `NMakeBuildCommandLine`.
+    pub build_command_line: Option<String>,
+    /// `NMakeReBuildCommandLine`.
+    pub rebuild_command_line: Option<String>,
+    /// `NMakeCleanCommandLine`.
+    pub clean_command_line: Option<String>,
+    /// `NMakeOutput`, the file the build produces.
+    pub output: Option<String>,
+    /// `NMakePreprocessorDefinitions`.
+    pub preprocessor_definitions: Vec<String>,
+    /// `NMakeIncludeSearchPath`.
+    pub include_search_path: Vec<String>,
+}
+
+impl NMakeSettings {
+    fn overlay(&mut self, other: &NMakeSettings) {
+        overlay_option(&mut self.build_command_line, &other.build_command_line);
+        overlay_option(&mut self.rebuild_command_line, &other.rebuild_command_line);
+        overlay_option(&mut self.clean_command_line, &other.clean_command_line);
+        overlay_option(&mut self.output, &other.output);
+        append_unique(
+            &mut self.preprocessor_definitions,
+            &other.preprocessor_definitions,
+        );
+        append_unique(&mut self.include_search_path, &other.include_search_path);
+    }
+}
+
+fn overlay_option<T: Clone>(base: &mut Option<T>, value: &Option<T>) {
+    if value.is_some() {
+        base.clone_from(value);
+    }
+}
+
+fn append_unique(base: &mut Vec<String>, values: &[String]) {
+    for value in values {
+        if !base.contains(value) {
+            base.push(value.clone());
+        }
+    }
+}
+
 /// A reference to another project.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ProjectReference {
     /// Path to the referenced project file.
     pub include: PathBuf,
@@ -286,16 +578,73 @@ pub struct ProjectReference {
     pub name: Option<String>,
 }
 
+/// A `<Reference>` to a .NET assembly, as used by C++/CLI projects.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct AssemblyReference {
+    /// Simple assembly name, without the version, culture or key token of a
+    /// strong name (`System.Data` for `System.Data, Version=4.0.0.0, ...`).
+    pub name: String,
+    /// Resolved `HintPath`; `None` when missing or built from MSBuild
+    /// properties.
+    pub hint_path: Option<PathBuf>,
+}
+
 /// A file entry inside a Visual Studio C/C++ project.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct VcxItem {
     pub include: PathBuf,
     pub full_path: PathBuf,
     pub kind: VcxItemKind,
+    /// Virtual folder from the `.vcxproj.filters` file (e.g. `Source Files/Net`),
+    /// using `/` as separator.
+    pub filter: Option<String>,
+    /// Configurations the item has `ExcludedFromBuild` set for.
+    pub excluded_configs: Vec<ConfigurationPlatform>,
+    /// `PreprocessorDefinitions` set on the item itself, in addition to the
+    /// project-wide ones.
+    pub per_file_defines: Vec<String>,
+    /// `PrecompiledHeader` mode set on the item itself (`Use`, `Create`,
+    /// `NotUsing`), per configuration; `None` applies to every configuration.
+    pub precompiled_header: Vec<(Option<ConfigurationPlatform>, String)>,
+}
+
+impl VcxItem {
+    /// Whether the item takes part in building `config`.
+    pub fn is_built_in(&self, config: &ConfigurationPlatform) -> bool {
+        !self.excluded_configs.contains(config)
+    }
+
+    /// The item's own `PrecompiledHeader` mode for `config`, preferring a
+    /// value conditioned on `config` over an unconditioned one.
+    pub fn precompiled_header_in(&self, config: &ConfigurationPlatform) -> Option<&str> {
+        let mode = |wanted: Option<&ConfigurationPlatform>| {
+            self.precompiled_header
+                .iter()
+                .rev()
+                .find(|(condition, _)| condition.as_ref() == wanted)
+                .map(|(_, mode)| mode.as_str())
+        };
+        mode(Some(config)).or_else(|| mode(None))
+    }
+}
+
+/// How the sources of a project use its precompiled header in one
+/// configuration, see [`VcxProject::pch_usage`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PchUsage {
+    /// `PrecompiledHeaderFile` when the project compiles with a PCH.
+    pub header: Option<String>,
+    /// The source compiled with `Create` that builds the PCH.
+    pub creating_file: Option<PathBuf>,
+    /// Sources that opt out of a project-wide PCH.
+    pub opted_out_files: Vec<PathBuf>,
 }
 
 /// Categorization of file entries from a Visual Studio C/C++ project.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum VcxItemKind {
     Source,
     Header,
@@ -303,9 +652,50 @@ pub enum VcxItemKind {
     Custom,
     None,
     Image,
+    /// Module definition (.def) file describing DLL exports.
+    ModuleDefinition,
     Other,
 }
 
+/// Source language of a C/C++ project, see [`VcxProject::primary_language`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectLanguage {
+    C,
+    Cpp,
+    /// Both C and C++ translation units.
+    Mixed,
+}
+
+/// Whole program optimization agreement across linked projects, produced by
+/// [`Solution::wpo_consistency`].
+#[derive(Debug, Clone, Default)]
+pub struct WpoReport {
+    /// Effective WPO setting per loaded project (unset means disabled).
+    pub projects: Vec<(String, bool)>,
+    /// Referencing/referenced project pairs whose settings differ.
+    pub disagreements: Vec<WpoDisagreement>,
+}
+
+impl WpoReport {
+    /// Returns true when every linked project pair agrees on WPO.
+    pub fn is_consistent(&self) -> bool {
+        self.disagreements.is_empty()
+    }
+}
+
+/// A project reference whose two ends disagree on whole program optimization.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WpoDisagreement {
+    /// Name of the referencing project.
+    pub project: String,
+    /// WPO setting of the referencing project.
+    pub project_wpo: bool,
+    /// Name of the referenced project.
+    pub dependency: String,
+    /// WPO setting of the referenced project.
+    pub dependency_wpo: bool,
+}
+
 // Well-known project type GUIDs
 pub mod project_types {
     /// C++ project
@@ -324,7 +714,7 @@ impl Solution {
     /// Parse a Visual Studio solution file from disk.
     pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
         let path = path.as_ref();
-        let contents = fs::read_to_string(path).map_err(|source| VisualStudioError::Io {
+        let contents = read_text(path).map_err(|source| VisualStudioError::Io {
             path: path.to_path_buf(),
             source,
         })?;
@@ -334,6 +724,18 @@ impl Solution {
 
     /// Parse a Visual Studio solution from a string.
     pub fn parse(contents: &str, path: &Path) -> Result<Self> {
+        Self::parse_with_cache(contents, path, &mut ProjectCache::new())
+    }
+
+    /// Parse a Visual Studio solution from a string, taking its C/C++
+    /// projects from `cache` so each project file is parsed once, however
+    /// often it is listed here or in other solutions sharing the cache. The
+    /// solution gets its own copy of each project.
+    pub fn parse_with_cache(
+        contents: &str,
+        path: &Path,
+        cache: &mut ProjectCache<'_>,
+    ) -> Result<Self> {
         let name = path
             .file_stem()
             .and_then(|stem| stem.to_str())
@@ -351,9 +753,12 @@ impl Solution {
         let mut folders = Vec::new();
         let mut vs_version = None;
         let mut minimum_vs_version = None;
+        let mut source_control: Option<HashMap<String, String>> = None;
 
         // Track nested project relationships
         let mut nested_projects: HashMap<String, String> = HashMap::new();
+        // Index of the project whose Project/EndProject block is open
+        let mut current_project: Option<usize> = None;
 
         let lines: Vec<&str> = contents.lines().collect();
         let mut i = 0;
@@ -389,6 +794,7 @@ impl Solution {
                     .map(|g| g.eq_ignore_ascii_case(project_types::SOLUTION_FOLDER))
                     .unwrap_or(false);
 
+                current_project = None;
                 if is_folder {
                     folders.push(SolutionFolder {
                         name: entry.name,
@@ -406,25 +812,52 @@ impl Solution {
                         absolute_path,
                         project_type_guid: entry.project_type_guid,
                         project_guid: entry.project_guid,
+                        dependencies: Vec::new(),
                         project: None,
+                        csproj: None,
                         load_error: None,
                     };
 
-                    // Load vcxproj files
-                    if project
+                    // Load vcxproj and csproj files
+                    let extension = project
                         .relative_path
                         .extension()
-                        .map(|ext| ext.eq_ignore_ascii_case("vcxproj"))
-                        == Some(true)
-                    {
-                        match VcxProject::from_path(&project.absolute_path) {
-                            Ok(vcx) => project.project = Some(vcx),
+                        .map(|ext| ext.to_string_lossy().to_ascii_lowercase());
+                    match extension.as_deref() {
+                        Some("vcxproj") => match cache.get_or_load(&project.absolute_path) {
+                            Ok(vcx) => project.project = Some(vcx.clone()),
                             Err(err) => project.load_error = Some(err.to_string()),
-                        }
+                        },
+                        Some("csproj") => match CsProject::from_path(&project.absolute_path) {
+                            Ok(cs) => project.csproj = Some(cs),
+                            Err(err) => project.load_error = Some(err.to_string()),
+                        },
+                        _ => {}
                     }
 
+                    current_project = Some(projects.len());
                     projects.push(project);
                 }
+            } else if trimmed == "EndProject" {
+                current_project = None;
+            }
+            // Parse legacy project dependencies inside a Project block
+            else if trimmed.starts_with("ProjectSection(ProjectDependencies)") {
+                i += 1;
+                while i < lines.len() {
+                    let dependency_line = lines[i].trim();
+                    if dependency_line == "EndProjectSection" {
+                        break;
+                    }
+                    // Format: {DependencyGUID} = {DependencyGUID}
+                    let guid = dependency_line
+                        .split_once('=')
+                        .and_then(|(guid, _)| extract_guid(guid));
+                    if let (Some(guid), Some(index)) = (guid, current_project) {
+                        projects[index].dependencies.push(guid);
+                    }
+                    i += 1;
+                }
             }
             // Parse Global section
             else if trimmed == "Global" {
@@ -476,6 +909,22 @@ impl Solution {
                             i += 1;
                         }
                     }
+                    // Parse SourceCodeControl bindings
+                    else if global_line.starts_with("GlobalSection(SourceCodeControl)") {
+                        let bindings = source_control.get_or_insert_with(HashMap::new);
+                        i += 1;
+                        while i < lines.len() {
+                            let scc_line = lines[i].trim();
+                            if scc_line == "EndGlobalSection" {
+                                break;
+                            }
+                            // Format: SccProjectName0 = Perforce\u0020Project
+                            if let Some((key, value)) = scc_line.split_once('=') {
+                                bindings.insert(key.trim().to_string(), value.trim().to_string());
+                            }
+                            i += 1;
+                        }
+                    }
                     // Parse NestedProjects
                     else if global_line.starts_with("GlobalSection(NestedProjects)") {
                         i += 1;
@@ -521,15 +970,78 @@ impl Solution {
             folders,
             vs_version,
             minimum_vs_version,
+            source_control,
         })
     }
 
-    /// Get projects that produce executables.
+    /// Move the solution to `new_solution_dir`, recomputing the absolute
+    /// paths of the solution file, its projects and their items from the
+    /// stored relative paths.
+    pub fn rebase(&mut self, new_solution_dir: &Path) {
+        let new_solution_dir = normalize_path(new_solution_dir);
+        if let Some(file_name) = self.path.file_name() {
+            self.path = new_solution_dir.join(file_name);
+        }
+        for project in &mut self.projects {
+            project.absolute_path = resolve_path(&new_solution_dir, &project.relative_path);
+            let project_dir = project
+                .absolute_path
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_else(|| new_solution_dir.clone());
+            if let Some(vcx) = &mut project.project {
+                vcx.rebase(&project_dir);
+            }
+            if let Some(cs) = &mut project.csproj {
+                cs.rebase(&project_dir);
+            }
+        }
+    }
+
+    /// Every `AdditionalDependencies` entry of every loaded project, across
+    /// all configurations.
+    pub fn all_library_dependencies(&self) -> BTreeSet<String> {
+        self.projects
+            .iter()
+            .filter_map(|project| project.project.as_ref())
+            .flat_map(|vcx| vcx.config_settings.values())
+            .flat_map(|settings| settings.linker.additional_dependencies.iter().cloned())
+            .collect()
+    }
+
+    /// Every `AdditionalLibraryDirectories` entry of every loaded project,
+    /// across all configurations, resolved against the project directory
+    /// with the project's default macros expanded. Entries that still
+    /// reference unknown macros are kept as written.
+    pub fn all_library_dirs(&self) -> BTreeSet<PathBuf> {
+        let mut dirs = BTreeSet::new();
+        for vcx in self.projects.iter().filter_map(|p| p.project.as_ref()) {
+            let project_dir = vcx.path.parent().unwrap_or(Path::new("."));
+            for settings in vcx.config_settings.values() {
+                let macros = settings
+                    .config
+                    .as_ref()
+                    .map(|config| vcx.default_macros(config))
+                    .unwrap_or_default();
+                for dir in &settings.linker.library_dirs {
+                    let expanded = expand_macros(dir, &macros);
+                    dirs.insert(if expanded.contains("$(") {
+                        PathBuf::from(expanded)
+                    } else {
+                        resolve_path(project_dir, Path::new(&expanded))
+                    });
+                }
+            }
+        }
+        dirs
+    }
+
+    /// Get projects that produce executables in at least one configuration.
     pub fn executable_projects(&self) -> impl Iterator<Item = &SolutionProject> {
         self.projects.iter().filter(|p| {
             p.project
                 .as_ref()
-                .map(|vcx| vcx.produces_executable)
+                .map(|vcx| vcx.executable_in_any_config)
                 .unwrap_or(false)
         })
     }
@@ -543,105 +1055,735 @@ impl Solution {
                 .unwrap_or(false)
         })
     }
-}
 
-impl VcxProject {
-    /// Parse a Visual Studio C/C++ project file from disk.
-    pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
-        let path = path.as_ref();
-        let contents = fs::read_to_string(path).map_err(|source| VisualStudioError::Io {
-            path: path.to_path_buf(),
-            source,
-        })?;
+    /// Every C/C++ source and header of the loaded projects, followed by the
+    /// files of C# projects, paired with the language to highlight and index
+    /// them as. Resources, images and other non-code items are skipped.
+    pub fn source_files_with_language(&self) -> impl Iterator<Item = (PathBuf, Language)> + '_ {
+        let vcx_files = self
+            .projects
+            .iter()
+            .filter_map(|project| project.project.as_ref())
+            .flat_map(|vcx| &vcx.files)
+            .filter(|item| matches!(item.kind, VcxItemKind::Source | VcxItemKind::Header))
+            .map(|item| (item.full_path.clone(), Language::from_path(&item.full_path)));
+        let cs_files = self
+            .projects
+            .iter()
+            .filter_map(|project| project.csproj.as_ref())
+            .flat_map(|csproj| &csproj.files)
+            .map(|item| (item.full_path.clone(), Language::CSharp));
+        vcx_files.chain(cs_files)
+    }
 
-        Self::parse(&contents, path)
+    /// The first loaded project listing the file at `path`. See
+    /// [`Solution::projects_for_file`].
+    pub fn project_for_file(&self, path: &Path) -> Option<&SolutionProject> {
+        self.projects_for_file(path).next()
     }
 
-    /// Parse a Visual Studio C/C++ project from a string.
-    pub fn parse(contents: &str, path: &Path) -> Result<Self> {
-        let document = Document::parse(contents).map_err(|source| VisualStudioError::Xml {
-            path: path.to_path_buf(),
-            source,
-        })?;
+    /// Every loaded project listing the file at `path`, e.g. a header shared
+    /// by several projects, in solution order. The path is normalized and
+    /// compared with the items' `full_path` using [`paths_equal_ci`].
+    pub fn projects_for_file<'a>(
+        &'a self,
+        path: &Path,
+    ) -> impl Iterator<Item = &'a SolutionProject> + 'a {
+        let path = normalize_path(path);
+        self.projects.iter().filter(move |project| {
+            let in_vcx = project.project.as_ref().is_some_and(|vcx| {
+                vcx.files
+                    .iter()
+                    .any(|item| paths_equal_ci(&item.full_path, &path))
+            });
+            let in_cs = project.csproj.as_ref().is_some_and(|csproj| {
+                csproj
+                    .files
+                    .iter()
+                    .any(|item| paths_equal_ci(&item.full_path, &path))
+            });
+            in_vcx || in_cs
+        })
+    }
 
-        let project_dir = path
-            .parent()
-            .map(normalize_path)
-            .unwrap_or_else(|| PathBuf::from("."));
+    /// The configuration mapping of the project with `project_guid` for a
+    /// solution configuration, i.e. which project configuration is active
+    /// when the solution builds `solution_config`. GUIDs match
+    /// case-insensitively, with or without braces.
+    pub fn active_project_config(
+        &self,
+        project_guid: &str,
+        solution_config: &ConfigurationPlatform,
+    ) -> Option<&ProjectConfigurationMapping> {
+        self.mappings_for(project_guid)?
+            .iter()
+            .find(|mapping| &mapping.solution_config == solution_config)
+    }
 
-        let mut files = Vec::new();
-        let mut produces_executable = false;
-        let mut configurations = Vec::new();
-        let mut config_settings: HashMap<String, ConfigurationSettings> = HashMap::new();
-        let mut project_references = Vec::new();
-        let mut globals = ProjectGlobals::default();
+    /// Whether the project with `guid` builds in `solution_config`. Projects
+    /// the solution has no configuration mapping for are assumed to build;
+    /// unknown GUIDs never do.
+    pub fn builds_in(&self, guid: &str, solution_config: &ConfigurationPlatform) -> bool {
+        extract_guid(guid)
+            .and_then(|guid| self.project_by_guid(&guid))
+            .is_some_and(|project| self.builds_project(project, solution_config))
+    }
 
-        // First pass: collect configurations and global properties
-        for node in document.descendants() {
-            if !node.is_element() {
-                continue;
-            }
+    /// Summary of the solution structure as JSON, for external tooling.
+    ///
+    /// The schema is `{name, path, vs_version, configurations, projects,
+    /// mappings, folders}`: configurations as `"Debug|x64"` strings, each
+    /// project with its name, relative path, GUIDs, dependencies, declared
+    /// configurations and load error, mappings keyed by project GUID, and
+    /// folders with their nested GUIDs. Paths use `/` separators.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> serde_json::Value {
+        use serde_json::json;
+
+        let path = |path: &Path| path.to_string_lossy().replace('\\', "/");
+        let projects: Vec<_> = self
+            .projects
+            .iter()
+            .map(|project| {
+                let configurations: Vec<String> = project
+                    .project
+                    .as_ref()
+                    .map(|vcx| vcx.configurations.iter().map(|c| c.as_str()).collect())
+                    .unwrap_or_default();
+                json!({
+                    "name": project.name,
+                    "path": path(&project.relative_path),
+                    "guid": project.project_guid,
+                    "type_guid": project.project_type_guid,
+                    "dependencies": project.dependencies,
+                    "configurations": configurations,
+                    "load_error": project.load_error,
+                })
+            })
+            .collect();
+        let mappings: serde_json::Map<String, serde_json::Value> = self
+            .project_configurations
+            .iter()
+            .map(|(guid, mappings)| {
+                let mappings: Vec<_> = mappings
+                    .iter()
+                    .map(|mapping| {
+                        json!({
+                            "solution_config": mapping.solution_config.as_str(),
+                            "project_config": mapping.project_config.as_str(),
+                            "build": mapping.build,
+                            "deploy": mapping.deploy,
+                        })
+                    })
+                    .collect();
+                (guid.clone(), mappings.into())
+            })
+            .collect();
 
-            let tag_name = node.tag_name().name();
+        json!({
+            "name": self.name,
+            "path": path(&self.path),
+            "vs_version": self.vs_version,
+            "configurations": self.configurations.iter().map(|c| c.as_str()).collect::<Vec<_>>(),
+            "projects": projects,
+            "mappings": mappings,
+            "folders": self.folders,
+        })
+    }
 
-            // Parse ProjectConfiguration items
-            if tag_name == "ProjectConfiguration" {
-                if let Some(include) = node.attribute("Include") {
-                    if let Some(config) = ConfigurationPlatform::parse(include) {
-                        if !configurations.contains(&config) {
-                            configurations.push(config.clone());
-                            config_settings.insert(
-                                config.as_str(),
-                                ConfigurationSettings {
-                                    config: Some(config),
-                                    ..Default::default()
-                                },
-                            );
-                        }
-                    }
+    /// Projects whose project references point at the project with `guid`.
+    ///
+    /// `guid` may be braced. References are matched by GUID,
+    /// case-insensitively, or by resolved path when they carry no GUID.
+    /// Unknown GUIDs yield no dependents.
+    pub fn dependents_of(&self, guid: &str) -> Vec<&SolutionProject> {
+        let Some(target) = extract_guid(guid).and_then(|guid| self.project_by_guid(&guid)) else {
+            return Vec::new();
+        };
+        self.projects
+            .iter()
+            .filter(|project| !std::ptr::eq(*project, target))
+            .filter(|project| {
+                project
+                    .project
+                    .iter()
+                    .flat_map(|vcx| &vcx.project_references)
+                    .any(|reference| match &reference.project_guid {
+                        Some(reference_guid) => target
+                            .project_guid
+                            .as_deref()
+                            .is_some_and(|guid| guid.eq_ignore_ascii_case(reference_guid)),
+                        None => paths_equal_ci(&reference.full_path, &target.absolute_path),
+                    })
+            })
+            .collect()
+    }
+
+    /// Check that projects linked through project references agree on whole
+    /// program optimization (LTCG) for a solution configuration.
+    ///
+    /// Mixing WPO and non-WPO objects in one link fails with LNK errors, so
+    /// every reference edge whose ends disagree is reported.
+    pub fn wpo_consistency(&self, config: &ConfigurationPlatform) -> WpoReport {
+        let wpo_of = |project: &SolutionProject| {
+            self.project_settings(project, config)
+                .and_then(|settings| settings.whole_program_optimization)
+                .unwrap_or(false)
+        };
+
+        let mut report = WpoReport::default();
+        for project in &self.projects {
+            let Some(vcx) = project.project.as_ref() else {
+                continue;
+            };
+            let project_wpo = wpo_of(project);
+            report.projects.push((project.name.clone(), project_wpo));
+
+            for reference in &vcx.project_references {
+                let Some(dependency) = self.resolve_reference(reference) else {
+                    continue;
+                };
+                if dependency.project.is_none() {
+                    continue;
+                }
+                let dependency_wpo = wpo_of(dependency);
+                if dependency_wpo != project_wpo {
+                    report.disagreements.push(WpoDisagreement {
+                        project: project.name.clone(),
+                        project_wpo,
+                        dependency: dependency.name.clone(),
+                        dependency_wpo,
+                    });
                 }
             }
+        }
+        report
+    }
 
-            // Parse PropertyGroup globals
-            if tag_name == "PropertyGroup" {
-                let label = node.attribute("Label").unwrap_or("");
-                if label == "Globals" {
-                    for child in node.children().filter(|c| c.is_element()) {
-                        let child_tag = child.tag_name().name();
-                        let text = child.text().map(|t| t.trim().to_string());
-                        match child_tag {
-                            "ProjectGuid" => {
-                                globals.project_guid = text.as_ref().and_then(|t| extract_guid(t))
-                            }
-                            "RootNamespace" => globals.root_namespace = text,
-                            "WindowsTargetPlatformVersion" => {
-                                globals.windows_target_platform_version = text
-                            }
-                            "Keyword" => globals.keyword = text,
-                            _ => {}
+    /// Whether each loaded project's linker emits debug information (a PDB)
+    /// for `config`; `None` means the project leaves it unset.
+    pub fn debug_info_report(&self, config: &ConfigurationPlatform) -> Vec<(String, Option<bool>)> {
+        self.projects
+            .iter()
+            .filter(|project| project.project.is_some())
+            .map(|project| {
+                let generates = self
+                    .project_settings(project, config)
+                    .and_then(|settings| settings.linker.generate_debug_information);
+                (project.name.clone(), generates)
+            })
+            .collect()
+    }
+
+    /// The C runtime library (`/MT`, `/MD`, ...) each loaded project compiles
+    /// against for `config`; `None` means the project leaves it unset.
+    pub fn runtime_library_report(
+        &self,
+        config: &ConfigurationPlatform,
+    ) -> Vec<(String, Option<String>)> {
+        self.projects
+            .iter()
+            .filter(|project| project.project.is_some())
+            .map(|project| {
+                let runtime = self
+                    .project_settings(project, config)
+                    .and_then(|settings| settings.compiler.runtime_library);
+                (project.name.clone(), runtime)
+            })
+            .collect()
+    }
+
+    /// Cycles in the project reference graph, each listed as the project
+    /// names along the cycle starting from its first project in solution
+    /// order. Every cycle reached during the walk is reported once.
+    pub fn reference_cycles(&self) -> Vec<Vec<String>> {
+        let edges = self.dependency_graph();
+
+        #[derive(Clone, Copy, PartialEq)]
+        enum Mark {
+            Unvisited,
+            OnStack,
+            Done,
+        }
+
+        let mut marks = vec![Mark::Unvisited; self.projects.len()];
+        let mut cycles: Vec<Vec<usize>> = Vec::new();
+
+        for root in 0..self.projects.len() {
+            if marks[root] != Mark::Unvisited {
+                continue;
+            }
+            // Iterative DFS: (node, next edge to follow).
+            let mut stack = vec![(root, 0usize)];
+            marks[root] = Mark::OnStack;
+            while let Some(&mut (node, ref mut next)) = stack.last_mut() {
+                let Some(&target) = edges[node].get(*next) else {
+                    marks[node] = Mark::Done;
+                    stack.pop();
+                    continue;
+                };
+                *next += 1;
+                match marks[target] {
+                    Mark::Unvisited => {
+                        marks[target] = Mark::OnStack;
+                        stack.push((target, 0));
+                    }
+                    Mark::OnStack => {
+                        let start = stack.iter().position(|&(n, _)| n == target).unwrap_or(0);
+                        let mut cycle: Vec<usize> =
+                            stack[start..].iter().map(|&(n, _)| n).collect();
+                        let min = cycle
+                            .iter()
+                            .enumerate()
+                            .min_by_key(|&(_, n)| *n)
+                            .map(|(i, _)| i)
+                            .unwrap_or(0);
+                        cycle.rotate_left(min);
+                        if !cycles.contains(&cycle) {
+                            cycles.push(cycle);
                         }
                     }
+                    Mark::Done => {}
                 }
             }
         }
 
-        // Second pass: collect configuration-specific settings
-        for node in document.descendants() {
-            if !node.is_element() {
-                continue;
-            }
-
-            let tag_name = node.tag_name().name();
-            let condition = node.attribute("Condition").unwrap_or("");
+        cycles
+            .into_iter()
+            .map(|cycle| {
+                cycle
+                    .into_iter()
+                    .map(|index| self.projects[index].name.clone())
+                    .collect()
+            })
+            .collect()
+    }
 
-            // Parse PropertyGroup with configuration condition
-            if tag_name == "PropertyGroup" {
-                if let Some(config_key) = extract_config_from_condition(condition) {
-                    let settings = config_settings.entry(config_key).or_default();
+    /// Group the projects that build in `config` into levels that can build
+    /// in parallel: every project depends only on projects in earlier levels.
+    ///
+    /// Dependencies come from project references and the solution's legacy
+    /// `ProjectDependencies`. Projects the solution configuration does not
+    /// build are left out, as are projects caught in a dependency cycle (see
+    /// [`Solution::reference_cycles`]).
+    pub fn parallel_build_levels(&self, config: &ConfigurationPlatform) -> Vec<Vec<String>> {
+        let edges = self.dependency_graph();
+        let builds: Vec<bool> = self
+            .projects
+            .iter()
+            .map(|project| self.builds_project(project, config))
+            .collect();
 
-                    for child in node.children().filter(|c| c.is_element()) {
-                        let child_tag = child.tag_name().name();
-                        let text = child.text().map(|t| t.trim().to_string());
+        let mut level_of: Vec<Option<usize>> = vec![None; self.projects.len()];
+        let mut levels: Vec<Vec<String>> = Vec::new();
+        loop {
+            let ready: Vec<usize> = (0..self.projects.len())
+                .filter(|&index| builds[index] && level_of[index].is_none())
+                .filter(|&index| {
+                    edges[index]
+                        .iter()
+                        .all(|&dep| !builds[dep] || level_of[dep].is_some())
+                })
+                .collect();
+            if ready.is_empty() {
+                break;
+            }
+            for &index in &ready {
+                level_of[index] = Some(levels.len());
+            }
+            levels.push(
+                ready
+                    .into_iter()
+                    .map(|index| self.projects[index].name.clone())
+                    .collect(),
+            );
+        }
+        levels
+    }
+
+    /// Projects ordered so that every project comes after the projects it
+    /// depends on, keeping solution order where dependencies allow.
+    ///
+    /// Dependencies come from project references and the solution's legacy
+    /// `ProjectDependencies`; projects that failed to load have none. On a
+    /// dependency cycle the GUIDs (names for projects without one) of the
+    /// projects caught in it are returned instead.
+    pub fn build_order(&self) -> std::result::Result<Vec<&SolutionProject>, Vec<String>> {
+        let edges = self.dependency_graph();
+        let mut placed = vec![false; self.projects.len()];
+        let mut order = Vec::with_capacity(self.projects.len());
+
+        while let Some(next) = (0..self.projects.len())
+            .find(|&index| !placed[index] && edges[index].iter().all(|&dep| placed[dep]))
+        {
+            placed[next] = true;
+            order.push(&self.projects[next]);
+        }
+        if order.len() == self.projects.len() {
+            return Ok(order);
+        }
+
+        // Drop unplaced projects that nothing unplaced depends on; what
+        // remains is on a cycle or between cycles.
+        let mut pending: Vec<bool> = placed.iter().map(|placed| !placed).collect();
+        while let Some(leaf) = (0..self.projects.len()).find(|&index| {
+            pending[index]
+                && !(0..self.projects.len())
+                    .any(|other| pending[other] && edges[other].contains(&index))
+        }) {
+            pending[leaf] = false;
+        }
+        Err(self
+            .projects
+            .iter()
+            .zip(pending)
+            .filter(|(_, pending)| *pending)
+            .map(|(project, _)| {
+                project
+                    .project_guid
+                    .clone()
+                    .unwrap_or_else(|| project.name.clone())
+            })
+            .collect())
+    }
+
+    /// Whether the solution configuration `config` builds `project`. Projects
+    /// without any configuration mapping are assumed to build.
+    fn builds_project(&self, project: &SolutionProject, config: &ConfigurationPlatform) -> bool {
+        let Some(guid) = project.project_guid.as_deref() else {
+            return true;
+        };
+        if self.mappings_for(guid).is_none() {
+            return true;
+        }
+        self.active_project_config(guid, config)
+            .is_some_and(|mapping| mapping.build)
+    }
+
+    /// Configurations of loaded C/C++ projects that no solution
+    /// configuration maps to, as `(project name, configuration)` pairs.
+    pub fn orphan_project_configurations(&self) -> Vec<(String, ConfigurationPlatform)> {
+        let mut orphans = Vec::new();
+        for project in &self.projects {
+            let Some(vcx) = &project.project else {
+                continue;
+            };
+            let mappings = project
+                .project_guid
+                .as_deref()
+                .and_then(|guid| self.mappings_for(guid))
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            for config in &vcx.configurations {
+                if !mappings
+                    .iter()
+                    .any(|mapping| &mapping.project_config == config)
+                {
+                    orphans.push((project.name.clone(), config.clone()));
+                }
+            }
+        }
+        orphans
+    }
+
+    /// Solution configurations that no project has a mapping for.
+    pub fn unused_solution_configurations(&self) -> Vec<ConfigurationPlatform> {
+        self.configurations
+            .iter()
+            .filter(|config| {
+                !self
+                    .project_configurations
+                    .values()
+                    .flatten()
+                    .any(|mapping| &mapping.solution_config == *config)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Project mappings whose platform differs from the solution
+    /// configuration's, in project order.
+    ///
+    /// `Win32`/`x86` and `Any CPU`/`AnyCPU` are treated as the same platform,
+    /// and `Mixed Platforms` solution configurations are skipped since they
+    /// are mixed on purpose.
+    pub fn platform_mismatches(&self) -> Vec<PlatformMismatch> {
+        let key = |platform: &str| {
+            let platform = platform.replace(' ', "").to_ascii_lowercase();
+            if platform == "win32" {
+                "x86".to_string()
+            } else {
+                platform
+            }
+        };
+
+        let mut mismatches = Vec::new();
+        for project in &self.projects {
+            let Some(mappings) = project
+                .project_guid
+                .as_deref()
+                .and_then(|guid| self.mappings_for(guid))
+            else {
+                continue;
+            };
+            for mapping in mappings {
+                let solution_platform = key(&mapping.solution_config.platform);
+                if solution_platform != "mixedplatforms"
+                    && solution_platform != key(&mapping.project_config.platform)
+                {
+                    mismatches.push(PlatformMismatch {
+                        project: project.name.clone(),
+                        solution_config: mapping.solution_config.clone(),
+                        project_config: mapping.project_config.clone(),
+                        build: mapping.build,
+                    });
+                }
+            }
+        }
+        mismatches
+    }
+
+    fn mappings_for(&self, guid: &str) -> Option<&Vec<ProjectConfigurationMapping>> {
+        let guid = extract_guid(guid)?;
+        self.project_configurations
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(&guid))
+            .map(|(_, mappings)| mappings)
+    }
+
+    /// Indices of the projects each project depends on, via project
+    /// references and legacy solution dependencies.
+    fn dependency_graph(&self) -> Vec<Vec<usize>> {
+        let index_of = |target: &SolutionProject| {
+            self.projects
+                .iter()
+                .position(|candidate| std::ptr::eq(candidate, target))
+        };
+        self.projects
+            .iter()
+            .map(|project| {
+                let mut edges: Vec<usize> = project
+                    .project
+                    .iter()
+                    .flat_map(|vcx| &vcx.project_references)
+                    .filter_map(|reference| self.resolve_reference(reference))
+                    .chain(
+                        project
+                            .dependencies
+                            .iter()
+                            .filter_map(|guid| self.project_by_guid(guid)),
+                    )
+                    .filter_map(index_of)
+                    .collect();
+                edges.sort_unstable();
+                edges.dedup();
+                edges
+            })
+            .collect()
+    }
+
+    /// Find the solution project a project reference points at, matching by
+    /// GUID first and by resolved path otherwise.
+    fn resolve_reference(&self, reference: &ProjectReference) -> Option<&SolutionProject> {
+        reference
+            .project_guid
+            .as_deref()
+            .and_then(|guid| self.project_by_guid(guid))
+            .or_else(|| {
+                self.projects
+                    .iter()
+                    .find(|p| paths_equal_ci(&p.absolute_path, &reference.full_path))
+            })
+    }
+
+    /// Settings a project uses when the solution builds `config`, following
+    /// the solution's configuration mapping when one exists. Values from
+    /// unconditioned groups are included.
+    fn project_settings(
+        &self,
+        project: &SolutionProject,
+        config: &ConfigurationPlatform,
+    ) -> Option<ConfigurationSettings> {
+        let vcx = project.project.as_ref()?;
+        Some(vcx.effective_settings_for(self.project_config(project, config)))
+    }
+
+    /// Project configuration a project builds in when the solution builds
+    /// `config`; `config` itself when the solution has no mapping.
+    fn project_config<'a>(
+        &'a self,
+        project: &SolutionProject,
+        config: &'a ConfigurationPlatform,
+    ) -> &'a ConfigurationPlatform {
+        project
+            .project_guid
+            .as_deref()
+            .and_then(|guid| self.active_project_config(guid, config))
+            .map(|mapping| &mapping.project_config)
+            .unwrap_or(config)
+    }
+}
+
+impl VcxProject {
+    /// Parse a Visual Studio C/C++ project file from disk.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = read_text(path).map_err(|source| VisualStudioError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        let mut project = Self::parse(&contents, path)?;
+        // A broken filters file only costs the virtual folder layout.
+        let _ = project.load_filters();
+        Ok(project)
+    }
+
+    /// Read the sibling `<project>.vcxproj.filters` file and assign each item
+    /// its virtual folder. A missing filters file leaves the items untouched.
+    pub fn load_filters(&mut self) -> Result<()> {
+        let mut filters_path = self.path.clone().into_os_string();
+        filters_path.push(".filters");
+        let filters_path = PathBuf::from(filters_path);
+
+        let contents = match read_text(&filters_path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(source) => {
+                return Err(VisualStudioError::Io {
+                    path: filters_path,
+                    source,
+                });
+            }
+        };
+        let document = Document::parse(&contents).map_err(|source| VisualStudioError::Xml {
+            path: filters_path.clone(),
+            source,
+        })?;
+
+        let mut filters: HashMap<String, String> = HashMap::new();
+        for node in document.descendants().filter(|n| n.is_element()) {
+            let Some(include) = node.attribute("Include").and_then(normalize_include) else {
+                continue;
+            };
+            let filter = node
+                .children()
+                .find(|child| child.has_tag_name("Filter"))
+                .and_then(|child| child.text())
+                .map(|text| text.trim().replace('\\', "/"))
+                .filter(|text| !text.is_empty());
+            if let Some(filter) = filter {
+                filters.insert(include.to_string_lossy().to_lowercase(), filter);
+            }
+        }
+
+        for item in &mut self.files {
+            let key = item.include.to_string_lossy().to_lowercase();
+            if let Some(filter) = filters.get(&key) {
+                item.filter = Some(filter.clone());
+            }
+        }
+        Ok(())
+    }
+
+    /// Parse a Visual Studio C/C++ project from a string.
+    pub fn parse(contents: &str, path: &Path) -> Result<Self> {
+        let document = Document::parse(contents).map_err(|source| VisualStudioError::Xml {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        let project_dir = path
+            .parent()
+            .map(normalize_path)
+            .unwrap_or_else(|| PathBuf::from("."));
+
+        let mut files = Vec::new();
+        let mut executable_in_any_config = false;
+        let mut configurations = Vec::new();
+        let mut config_settings: HashMap<String, ConfigurationSettings> = HashMap::new();
+        let mut project_references = Vec::new();
+        let mut capabilities: Vec<String> = Vec::new();
+        let mut assembly_references = Vec::new();
+        let mut globals = ProjectGlobals::default();
+
+        // First pass: collect configurations and global properties
+        for node in document.descendants() {
+            if !node.is_element() {
+                continue;
+            }
+
+            let tag_name = node.tag_name().name();
+
+            // Parse ProjectConfiguration items
+            if tag_name == "ProjectConfiguration" {
+                if let Some(include) = node.attribute("Include") {
+                    if let Some(config) = ConfigurationPlatform::parse(include) {
+                        if !configurations.contains(&config) {
+                            configurations.push(config.clone());
+                            config_settings.insert(
+                                config.as_str(),
+                                ConfigurationSettings {
+                                    config: Some(config),
+                                    ..Default::default()
+                                },
+                            );
+                        }
+                    }
+                }
+            }
+
+            // Parse PropertyGroup globals
+            if tag_name == "PropertyGroup" {
+                let label = node.attribute("Label").unwrap_or("");
+                if label == "Globals" {
+                    for child in node.children().filter(|c| c.is_element()) {
+                        let child_tag = child.tag_name().name();
+                        let text = child.text().map(|t| t.trim().to_string());
+                        match child_tag {
+                            "ProjectGuid" => {
+                                globals.project_guid = text.as_ref().and_then(|t| extract_guid(t))
+                            }
+                            "RootNamespace" => globals.root_namespace = text,
+                            "WindowsTargetPlatformVersion" => {
+                                globals.windows_target_platform_version = text
+                            }
+                            "Keyword" => globals.keyword = text,
+                            _ => {}
+                        }
+                    }
+                }
+
+                // An unconditioned toolset (usually in the Label="Configuration"
+                // group) applies to every configuration.
+                if let Some(toolset) = node
+                    .children()
+                    .find(|c| c.has_tag_name("PlatformToolset"))
+                    .filter(|_| node.attribute("Condition").is_none())
+                    .and_then(|c| c.text())
+                {
+                    globals.platform_toolset = Some(toolset.trim().to_string());
+                }
+            }
+        }
+
+        // Second pass: collect configuration-specific settings
+        for node in document.descendants() {
+            if !node.is_element() {
+                continue;
+            }
+
+            let tag_name = node.tag_name().name();
+            let condition = node.attribute("Condition").unwrap_or("");
+
+            // Parse PropertyGroup with configuration condition
+            if tag_name == "PropertyGroup" {
+                if let Some(config_key) = extract_config_from_condition(condition) {
+                    let settings = config_settings.entry(config_key).or_default();
+
+                    for child in node.children().filter(|c| c.is_element()) {
+                        let child_tag = child.tag_name().name();
+                        let text = child.text().map(|t| t.trim().to_string());
 
                         match child_tag {
                             "ConfigurationType" => {
@@ -652,7 +1794,7 @@ impl VcxProject {
                                         .map(|ct| ct.is_executable())
                                         .unwrap_or(false)
                                     {
-                                        produces_executable = true;
+                                        executable_in_any_config = true;
                                     }
                                 }
                             }
@@ -666,37 +1808,66 @@ impl VcxProject {
                             "IntDir" => settings.int_dir = text,
                             "TargetName" => settings.target_name = text,
                             "TargetExt" => settings.target_ext = text,
-                            "PlatformToolset" => globals.platform_toolset = text,
+                            tag if tag.starts_with("NMake") => parse_nmake_property(
+                                settings.nmake.get_or_insert_default(),
+                                tag,
+                                text,
+                            ),
+                            "PlatformToolset" => {
+                                // Best-effort project-wide value: keep the
+                                // unconditioned or first configured toolset.
+                                if globals.platform_toolset.is_none() {
+                                    globals.platform_toolset = text.clone();
+                                }
+                                settings.platform_toolset = text;
+                            }
                             _ => {}
                         }
                     }
                 }
             }
 
-            // Parse ItemDefinitionGroup (ClCompile and Link settings)
+            // Parse ItemDefinitionGroup (ClCompile and Link settings). An
+            // unconditioned group applies to every configuration and is kept
+            // under the empty key.
             if tag_name == "ItemDefinitionGroup" {
-                if let Some(config_key) = extract_config_from_condition(condition) {
-                    let settings = config_settings.entry(config_key).or_default();
-
-                    for child in node.children().filter(|c| c.is_element()) {
-                        let child_tag = child.tag_name().name();
-
-                        if child_tag == "ClCompile" {
-                            parse_compiler_settings(child, &mut settings.compiler);
-                        } else if child_tag == "Link" {
-                            parse_linker_settings(child, &mut settings.linker);
-                        }
-                    }
+                let config_key = if condition.is_empty() {
+                    Some(String::new())
+                } else {
+                    extract_config_from_condition(condition)
+                };
+                if let Some(config_key) = config_key {
+                    parse_item_definition_group(
+                        node,
+                        config_settings.entry(config_key).or_default(),
+                    );
                 }
             }
 
-            // Also check for ConfigurationType without condition (fallback)
-            if tag_name == "ConfigurationType" && condition.is_empty() {
-                if let Some(text) = node.text() {
-                    if text.trim().eq_ignore_ascii_case("Application") {
-                        produces_executable = true;
-                    }
+            // ConfigurationType in an unconditioned group applies to every
+            // configuration.
+            let unconditioned_group = node
+                .parent_element()
+                .is_none_or(|parent| parent.attribute("Condition").is_none());
+            if tag_name == "ConfigurationType" && condition.is_empty() && unconditioned_group {
+                let configuration_type = node.text().and_then(ConfigurationType::from_str);
+                if configuration_type.is_some_and(|ct| ct.is_executable()) {
+                    executable_in_any_config = true;
                 }
+                config_settings
+                    .entry(String::new())
+                    .or_default()
+                    .configuration_type = configuration_type;
+            }
+        }
+
+        // NMake properties only matter for Makefile-type configurations.
+        let default_type = config_settings
+            .get("")
+            .and_then(|settings| settings.configuration_type);
+        for settings in config_settings.values_mut() {
+            if settings.configuration_type.or(default_type) != Some(ConfigurationType::Makefile) {
+                settings.nmake = None;
             }
         }
 
@@ -713,15 +1884,86 @@ impl VcxProject {
                 if let Some(include) = node.attribute("Include") {
                     if let Some(relative_path) = normalize_include(include) {
                         let full_path = resolve_path(&project_dir, &relative_path);
+                        let kind = kind.refine_for_path(&relative_path);
+
+                        let mut excluded_configs = Vec::new();
+                        let mut per_file_defines: Vec<String> = Vec::new();
+                        let mut precompiled_header = Vec::new();
+                        for child in node.children().filter(|c| c.is_element()) {
+                            let text = child.text().unwrap_or("");
+                            match child.tag_name().name() {
+                                "ExcludedFromBuild" if text.trim().eq_ignore_ascii_case("true") => {
+                                    let condition = child.attribute("Condition").unwrap_or("");
+                                    if condition.is_empty() {
+                                        excluded_configs = configurations.clone();
+                                    } else if let Some(config) =
+                                        extract_config_from_condition(condition)
+                                            .and_then(|key| ConfigurationPlatform::parse(&key))
+                                            .filter(|config| !excluded_configs.contains(config))
+                                    {
+                                        excluded_configs.push(config);
+                                    }
+                                }
+                                "PrecompiledHeader" => {
+                                    let condition = child.attribute("Condition").unwrap_or("");
+                                    let config = extract_config_from_condition(condition)
+                                        .and_then(|key| ConfigurationPlatform::parse(&key));
+                                    if condition.is_empty() || config.is_some() {
+                                        precompiled_header.push((config, text.trim().to_string()));
+                                    }
+                                }
+                                "PreprocessorDefinitions" => {
+                                    for define in parse_semicolon_list(text) {
+                                        if !per_file_defines.contains(&define) {
+                                            per_file_defines.push(define);
+                                        }
+                                    }
+                                }
+                                _ => {}
+                            }
+                        }
+
                         files.push(VcxItem {
                             include: relative_path,
                             full_path,
                             kind,
+                            filter: None,
+                            excluded_configs,
+                            per_file_defines,
+                            precompiled_header,
                         });
                     }
                 }
             }
 
+            if tag_name == "ProjectCapability" {
+                for capability in parse_semicolon_list(node.attribute("Include").unwrap_or("")) {
+                    if !capabilities.contains(&capability) {
+                        capabilities.push(capability);
+                    }
+                }
+            }
+
+            if tag_name == "Reference" {
+                let name = node
+                    .attribute("Include")
+                    .and_then(|include| include.split(',').next())
+                    .map(str::trim)
+                    .filter(|name| !name.is_empty());
+                if let Some(name) = name {
+                    let hint_path = node
+                        .children()
+                        .find(|child| child.has_tag_name("HintPath"))
+                        .and_then(|child| child.text())
+                        .and_then(normalize_include)
+                        .map(|hint| resolve_path(&project_dir, &hint));
+                    assembly_references.push(AssemblyReference {
+                        name: name.to_string(),
+                        hint_path,
+                    });
+                }
+            }
+
             // Parse project references
             if tag_name == "ProjectReference" {
                 if let Some(include) = node.attribute("Include") {
@@ -753,9 +1995,10 @@ impl VcxProject {
         }
 
         files.sort_by(|a, b| a.include.cmp(&b.include));
-        files.dedup_by(|a, b| a.include == b.include);
+        let mut seen = HashSet::new();
+        files.retain(|item| seen.insert(path_key(&item.include)));
 
-        Ok(VcxProject {
+        let mut project = VcxProject {
             name: path
                 .file_stem()
                 .and_then(|stem| stem.to_str())
@@ -763,19 +2006,111 @@ impl VcxProject {
                 .unwrap_or_else(|| path.to_string_lossy().to_string()),
             path: normalize_path(path),
             files,
-            produces_executable,
+            produces_executable: executable_in_any_config,
+            executable_in_any_config,
             configurations,
             config_settings,
             project_references,
             globals,
-        })
+            capabilities,
+            assembly_references,
+        };
+        if let Some(default_config) = project.configurations.first() {
+            project.produces_executable = project.produces_executable_in(default_config);
+        }
+        Ok(project)
+    }
+
+    /// Whether the project declares `capability`, compared
+    /// case-insensitively like MSBuild item names.
+    pub fn has_capability(&self, capability: &str) -> bool {
+        self.capabilities
+            .iter()
+            .any(|declared| declared.eq_ignore_ascii_case(capability))
+    }
+
+    /// Whether `config` builds an application, taking an unconditioned
+    /// `ConfigurationType` into account.
+    pub fn produces_executable_in(&self, config: &ConfigurationPlatform) -> bool {
+        self.effective_settings_for(config)
+            .configuration_type
+            .is_some_and(|ct| ct.is_executable())
+    }
+
+    /// Returns true for header-only projects: headers are listed but nothing
+    /// is compiled.
+    pub fn is_header_only(&self) -> bool {
+        let has_kind = |kind| self.files.iter().any(|item| item.kind == kind);
+        has_kind(VcxItemKind::Header) && !has_kind(VcxItemKind::Source)
+    }
+
+    /// Infer whether the project is C, C++ or both.
+    ///
+    /// Source file extensions decide when there are any; otherwise the
+    /// configured language standards are used, defaulting to C++.
+    pub fn primary_language(&self) -> ProjectLanguage {
+        let mut has_c = false;
+        let mut has_cpp = false;
+        for item in self
+            .files
+            .iter()
+            .filter(|item| item.kind == VcxItemKind::Source)
+        {
+            let extension = item
+                .include
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .map(|ext| ext.to_ascii_lowercase());
+            match extension.as_deref() {
+                Some("c") => has_c = true,
+                Some("cpp" | "cc" | "cxx" | "c++") => has_cpp = true,
+                _ => {}
+            }
+        }
+
+        if !has_c && !has_cpp {
+            let settings = || self.config_settings.values().map(|s| &s.compiler);
+            has_cpp = settings().any(|c| c.language_standard.is_some());
+            has_c = !has_cpp && settings().any(|c| c.c_language_standard.is_some());
+        }
+
+        match (has_c, has_cpp) {
+            (true, true) => ProjectLanguage::Mixed,
+            (true, false) => ProjectLanguage::C,
+            _ => ProjectLanguage::Cpp,
+        }
     }
 
     /// Get settings for a specific configuration.
+    ///
+    /// This is the raw entry parsed for exactly `config`; values inherited
+    /// from unconditioned groups are not included, see
+    /// [`VcxProject::effective_settings_for`].
     pub fn settings_for(&self, config: &ConfigurationPlatform) -> Option<&ConfigurationSettings> {
         self.config_settings.get(&config.as_str())
     }
 
+    /// Settings for `config` layered over the unconditioned
+    /// `ItemDefinitionGroup` (stored under the empty key) and the project's
+    /// global toolset.
+    ///
+    /// Values set for the configuration win; list values are concatenated,
+    /// inherited entries first, without duplicates.
+    pub fn effective_settings_for(&self, config: &ConfigurationPlatform) -> ConfigurationSettings {
+        let mut settings = ConfigurationSettings {
+            config: Some(config.clone()),
+            platform_toolset: self.globals.platform_toolset.clone(),
+            ..Default::default()
+        };
+        if let Some(inherited) = self.config_settings.get("") {
+            settings.overlay(inherited);
+        }
+        if let Some(specific) = self.settings_for(config) {
+            settings.overlay(specific);
+        }
+        settings
+    }
+
     /// Get all include directories across all configurations.
     pub fn all_include_dirs(&self) -> Vec<&str> {
         let mut dirs: Vec<&str> = self
@@ -788,6 +2123,140 @@ impl VcxProject {
         dirs
     }
 
+    /// Return a copy of the project with MSBuild macros (`$(SolutionDir)`,
+    /// `$(ProjectDir)`, `$(Configuration)`, ...) expanded in path-like
+    /// settings of every configuration.
+    ///
+    /// `macros` takes precedence over the defaults derived from the project,
+    /// see [`VcxProject::default_macros`]. Macros that are not known are left
+    /// in place so callers can tell what remains unresolved.
+    pub fn resolve_macros(&self, macros: &HashMap<String, String>) -> VcxProject {
+        let mut resolved = self.clone();
+        for settings in resolved.config_settings.values_mut() {
+            let mut known = match &settings.config {
+                Some(config) => self.default_macros(config),
+                None => HashMap::new(),
+            };
+            known.extend(macros.iter().map(|(k, v)| (k.clone(), v.clone())));
+            let expand = |value: &mut String| *value = expand_macros(value, &known);
+
+            settings.compiler.include_dirs.iter_mut().for_each(expand);
+            settings
+                .compiler
+                .forced_includes
+                .iter_mut()
+                .for_each(expand);
+            settings.linker.library_dirs.iter_mut().for_each(expand);
+            if let Some(nmake) = &mut settings.nmake {
+                nmake.include_search_path.iter_mut().for_each(expand);
+                nmake.output.iter_mut().for_each(expand);
+            }
+            for value in [
+                &mut settings.out_dir,
+                &mut settings.int_dir,
+                &mut settings.target_name,
+                &mut settings.linker.output_file,
+                &mut settings.linker.import_library,
+                &mut settings.linker.program_database_file,
+                &mut settings.linker.module_definition_file,
+                &mut settings.pre_build_command,
+                &mut settings.post_build_command,
+            ] {
+                value.iter_mut().for_each(expand);
+            }
+        }
+        resolved
+    }
+
+    /// Macro values derived from the project itself for `config`.
+    ///
+    /// `SolutionDir` defaults to the project directory since a project does
+    /// not know which solution loaded it; directory values end with `/` as in
+    /// MSBuild.
+    pub fn default_macros(&self, config: &ConfigurationPlatform) -> HashMap<String, String> {
+        let project_dir = self
+            .path
+            .parent()
+            .map(|dir| format!("{}/", dir.to_string_lossy().trim_end_matches('/')))
+            .unwrap_or_default();
+        let target_name = self
+            .settings_for(config)
+            .and_then(|settings| settings.target_name.clone())
+            .unwrap_or_else(|| self.name.clone());
+
+        HashMap::from([
+            ("SolutionDir".to_string(), project_dir.clone()),
+            ("ProjectDir".to_string(), project_dir),
+            ("ProjectName".to_string(), self.name.clone()),
+            ("Configuration".to_string(), config.configuration.clone()),
+            ("Platform".to_string(), config.platform.clone()),
+            ("TargetName".to_string(), target_name),
+        ])
+    }
+
+    /// Precompiled header use in `config`: the project-wide header, the
+    /// source creating it and the sources built in `config` that opt out
+    /// through a per-file `PrecompiledHeader` of `NotUsing` (or empty).
+    pub fn pch_usage(&self, config: &ConfigurationPlatform) -> PchUsage {
+        let compiler = self.effective_settings_for(config).compiler;
+        let project_mode = compiler.precompiled_header.as_deref().unwrap_or("");
+        let uses_pch = project_mode.eq_ignore_ascii_case("Use");
+
+        let mut usage = PchUsage {
+            header: compiler.precompiled_header_file.filter(|_| uses_pch),
+            ..Default::default()
+        };
+        for item in self
+            .files
+            .iter()
+            .filter(|item| item.kind == VcxItemKind::Source && item.is_built_in(config))
+        {
+            let mode = item.precompiled_header_in(config).unwrap_or(project_mode);
+            if mode.eq_ignore_ascii_case("Create") {
+                usage
+                    .creating_file
+                    .get_or_insert_with(|| item.full_path.clone());
+            } else if uses_pch
+                && (mode.is_empty()
+                    || mode.eq_ignore_ascii_case("NotUsing")
+                    || mode.eq_ignore_ascii_case("None"))
+            {
+                usage.opted_out_files.push(item.full_path.clone());
+            }
+        }
+        usage
+    }
+
+    /// Include directories for `config` as absolute paths, with macros
+    /// expanded from [`VcxProject::default_macros`]. Entries that still
+    /// reference unknown macros are returned unchanged. For `Makefile`-type
+    /// configurations the NMake include search path follows.
+    pub fn resolved_include_dirs(&self, config: &ConfigurationPlatform) -> Vec<PathBuf> {
+        let settings = self.effective_settings_for(config);
+        let macros = self.default_macros(config);
+        let project_dir = self.path.parent().unwrap_or(Path::new("."));
+
+        settings
+            .compiler
+            .include_dirs
+            .iter()
+            .chain(
+                settings
+                    .nmake
+                    .iter()
+                    .flat_map(|nmake| &nmake.include_search_path),
+            )
+            .map(|dir| {
+                let expanded = expand_macros(dir, &macros);
+                if expanded.contains("$(") {
+                    PathBuf::from(expanded)
+                } else {
+                    resolve_path(project_dir, Path::new(&expanded))
+                }
+            })
+            .collect()
+    }
+
     /// Get all preprocessor definitions across all configurations.
     pub fn all_preprocessor_definitions(&self) -> Vec<&str> {
         let mut defs: Vec<&str> = self
@@ -824,6 +2293,22 @@ impl VcxProject {
         let out_path = resolve_path(project_dir, Path::new(out_dir));
         Some(out_path.join(format!("{}{}", target_name, target_ext)))
     }
+
+    /// Move the project to `new_project_dir`, recomputing the project path and
+    /// every item and project reference `full_path` from its `include`.
+    /// Absolute includes keep pointing at the same location.
+    pub fn rebase(&mut self, new_project_dir: &Path) {
+        let new_project_dir = normalize_path(new_project_dir);
+        if let Some(file_name) = self.path.file_name() {
+            self.path = new_project_dir.join(file_name);
+        }
+        for item in &mut self.files {
+            item.full_path = resolve_path(&new_project_dir, &item.include);
+        }
+        for reference in &mut self.project_references {
+            reference.full_path = resolve_path(&new_project_dir, &reference.include);
+        }
+    }
 }
 
 impl VcxItemKind {
@@ -840,18 +2325,77 @@ impl VcxItemKind {
             _ => return None,
         })
     }
+
+    /// Module definition files are usually listed as `None` items, so the
+    /// extension decides their kind.
+    fn refine_for_path(self, path: &Path) -> Self {
+        let is_def = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("def"));
+        if is_def {
+            VcxItemKind::ModuleDefinition
+        } else {
+            self
+        }
+    }
 }
 
-// Helper to parse compiler settings from ClCompile element
-fn parse_compiler_settings(node: roxmltree::Node, settings: &mut CompilerSettings) {
+// Parse the ClCompile and Link children of an ItemDefinitionGroup
+fn parse_item_definition_group(node: roxmltree::Node, settings: &mut ConfigurationSettings) {
     for child in node.children().filter(|c| c.is_element()) {
-        let tag = child.tag_name().name();
-        let text = child.text().map(|t| t.trim());
+        let child_tag = child.tag_name().name();
+
+        if child_tag == "ClCompile" {
+            parse_compiler_settings(child, &mut settings.compiler);
+        } else if child_tag == "Link" {
+            parse_linker_settings(child, &mut settings.linker);
+        } else if child_tag == "PreBuildEvent" {
+            parse_build_event(
+                child,
+                &mut settings.pre_build_command,
+                &mut settings.pre_build_message,
+            );
+        } else if child_tag == "PostBuildEvent" {
+            parse_build_event(
+                child,
+                &mut settings.post_build_command,
+                &mut settings.post_build_message,
+            );
+        }
+    }
+}
 
-        match tag {
-            "AdditionalIncludeDirectories" => {
-                if let Some(t) = text {
-                    settings.include_dirs = parse_semicolon_list(t);
+// Helper to parse the Command/Message pair of a PreBuildEvent or PostBuildEvent
+fn parse_build_event(
+    node: roxmltree::Node,
+    command: &mut Option<String>,
+    message: &mut Option<String>,
+) {
+    for child in node.children().filter(|c| c.is_element()) {
+        let text = child
+            .text()
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(str::to_string);
+        match child.tag_name().name() {
+            "Command" => *command = text,
+            "Message" => *message = text,
+            _ => {}
+        }
+    }
+}
+
+// Helper to parse compiler settings from ClCompile element
+fn parse_compiler_settings(node: roxmltree::Node, settings: &mut CompilerSettings) {
+    for child in node.children().filter(|c| c.is_element()) {
+        let tag = child.tag_name().name();
+        let text = child.text().map(|t| t.trim());
+
+        match tag {
+            "AdditionalIncludeDirectories" => {
+                if let Some(t) = text {
+                    settings.include_dirs = parse_semicolon_list(t);
                 }
             }
             "PreprocessorDefinitions" => {
@@ -884,6 +2428,16 @@ fn parse_compiler_settings(node: roxmltree::Node, settings: &mut CompilerSetting
             "PrecompiledHeaderFile" => {
                 settings.precompiled_header_file = text.map(|t| t.to_string())
             }
+            "DisableSpecificWarnings" => {
+                if let Some(t) = text {
+                    settings.disabled_warnings = parse_semicolon_list(t);
+                }
+            }
+            "ForcedIncludeFiles" => {
+                if let Some(t) = text {
+                    settings.forced_includes = parse_semicolon_list(t);
+                }
+            }
             "AdditionalOptions" => {
                 if let Some(t) = text {
                     settings.additional_options = parse_space_list(t);
@@ -915,7 +2469,15 @@ fn parse_linker_settings(node: roxmltree::Node, settings: &mut LinkerSettings) {
                 settings.generate_debug_information = text
                     .map(|t| t.eq_ignore_ascii_case("true") || t.eq_ignore_ascii_case("DebugFull"))
             }
-            "SubSystem" => settings.subsystem = text.map(|t| t.to_string()),
+            "SubSystem" => {
+                if let Some(t) = text {
+                    let (subsystem, version) = split_subsystem(t);
+                    settings.subsystem = Some(subsystem.to_string());
+                    if version.is_some() {
+                        settings.subsystem_version = version.map(str::to_string);
+                    }
+                }
+            }
             "EnableCOMDATFolding" => {
                 settings.enable_comdat_folding = text.map(|t| t.eq_ignore_ascii_case("true"))
             }
@@ -925,6 +2487,9 @@ fn parse_linker_settings(node: roxmltree::Node, settings: &mut LinkerSettings) {
             "OutputFile" => settings.output_file = text.map(|t| t.to_string()),
             "ImportLibrary" => settings.import_library = text.map(|t| t.to_string()),
             "ProgramDatabaseFile" => settings.program_database_file = text.map(|t| t.to_string()),
+            "ModuleDefinitionFile" => {
+                settings.module_definition_file = text.map(|t| t.replace('\\', "/"))
+            }
             "AdditionalOptions" => {
                 if let Some(t) = text {
                     settings.additional_options = parse_space_list(t);
@@ -933,6 +2498,35 @@ fn parse_linker_settings(node: roxmltree::Node, settings: &mut LinkerSettings) {
             _ => {}
         }
     }
+
+    // `/SUBSYSTEM:WINDOWS,5.01` passed directly to the linker.
+    let switch = settings.additional_options.iter().find_map(|option| {
+        let (name, value) = option.get(1..)?.split_once(':')?;
+        (option.starts_with(['/', '-']) && name.eq_ignore_ascii_case("SUBSYSTEM"))
+            .then(|| split_subsystem(value))
+    });
+    if let Some((subsystem, version)) = switch {
+        if settings.subsystem.is_none() {
+            let mut chars = subsystem.chars();
+            settings.subsystem = chars.next().map(|first| {
+                first.to_ascii_uppercase().to_string() + &chars.as_str().to_ascii_lowercase()
+            });
+        }
+        if version.is_some() {
+            settings.subsystem_version = version.map(str::to_string);
+        }
+    }
+}
+
+/// Split `Windows,5.01` into the subsystem and its optional version.
+fn split_subsystem(value: &str) -> (&str, Option<&str>) {
+    match value.split_once(',') {
+        Some((subsystem, version)) => (
+            subsystem.trim(),
+            Some(version.trim()).filter(|version| !version.is_empty()),
+        ),
+        None => (value.trim(), None),
+    }
 }
 
 // Parse semicolon-separated list, filtering out MSBuild variables
@@ -945,6 +2539,29 @@ fn parse_semicolon_list(s: &str) -> Vec<String> {
         .collect()
 }
 
+// Helper to parse one NMake* property of a Makefile-type configuration
+fn parse_nmake_property(settings: &mut NMakeSettings, tag: &str, text: Option<String>) {
+    // Drop the reference to the inherited value, e.g. `$(NMakeIncludeSearchPath)`.
+    let list = |text: Option<String>| {
+        let inherited = format!("$({tag})");
+        let mut parts = text
+            .as_deref()
+            .map(parse_semicolon_list)
+            .unwrap_or_default();
+        parts.retain(|part| !part.eq_ignore_ascii_case(&inherited));
+        parts
+    };
+    match tag {
+        "NMakeBuildCommandLine" => settings.build_command_line = text,
+        "NMakeReBuildCommandLine" => settings.rebuild_command_line = text,
+        "NMakeCleanCommandLine" => settings.clean_command_line = text,
+        "NMakeOutput" => settings.output = text,
+        "NMakePreprocessorDefinitions" => settings.preprocessor_definitions = list(text),
+        "NMakeIncludeSearchPath" => settings.include_search_path = list(text),
+        _ => {}
+    }
+}
+
 // Parse space-separated options
 fn parse_space_list(s: &str) -> Vec<String> {
     s.split_whitespace()
@@ -1112,6 +2729,32 @@ fn trim_guid(value: &str) -> std::result::Result<Option<String>, String> {
     }
 }
 
+/// Replace `$(Name)` references with values from `macros`, matching names
+/// case-insensitively like MSBuild. Unknown macros are kept verbatim.
+fn expand_macros(value: &str, macros: &HashMap<String, String>) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find("$(") {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find(')') else {
+            result.push_str(&rest[start..]);
+            return result;
+        };
+        let name = &after[..end];
+        match macros
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        {
+            Some((_, replacement)) => result.push_str(replacement),
+            None => result.push_str(&rest[start..start + 2 + end + 1]),
+        }
+        rest = &after[end + 1..];
+    }
+    result.push_str(rest);
+    result
+}
+
 fn normalize_include(value: &str) -> Option<PathBuf> {
     let trimmed = value.trim();
     if trimmed.is_empty() {
@@ -1124,6 +2767,46 @@ fn normalize_include(value: &str) -> Option<PathBuf> {
     Some(PathBuf::from(normalized))
 }
 
+/// Read a text file, decoding it according to its byte order mark.
+///
+/// UTF-8, UTF-16LE and UTF-16BE BOMs are recognized and stripped; files
+/// without a BOM must be UTF-8.
+fn read_text(path: &Path) -> io::Result<String> {
+    let bytes = fs::read(path)?;
+    let invalid =
+        |err: &dyn std::fmt::Display| io::Error::new(io::ErrorKind::InvalidData, err.to_string());
+
+    let (units, big_endian) = match bytes.as_slice() {
+        [0xEF, 0xBB, 0xBF, rest @ ..] => {
+            return String::from_utf8(rest.to_vec()).map_err(|err| invalid(&err));
+        }
+        [0xFF, 0xFE, rest @ ..] => (rest, false),
+        [0xFE, 0xFF, rest @ ..] => (rest, true),
+        _ => return String::from_utf8(bytes).map_err(|err| invalid(&err)),
+    };
+
+    if units.len() % 2 != 0 {
+        return Err(invalid(&"truncated UTF-16 text"));
+    }
+    let units: Vec<u16> = units
+        .chunks_exact(2)
+        .map(|pair| {
+            if big_endian {
+                u16::from_be_bytes([pair[0], pair[1]])
+            } else {
+                u16::from_le_bytes([pair[0], pair[1]])
+            }
+        })
+        .collect();
+    String::from_utf16(&units).map_err(|err| invalid(&err))
+}
+
+/// Canonical form of `path` for identity comparisons, falling back to the
+/// lexically normalized path when it does not exist.
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| normalize_path(path))
+}
+
 fn resolve_path(base: &Path, relative: &Path) -> PathBuf {
     if relative
         .components()
@@ -1141,6 +2824,20 @@ fn resolve_path(base: &Path, relative: &Path) -> PathBuf {
     }
 }
 
+/// Whether `a` and `b` name the same file under Windows rules: `\\` and `/`
+/// are interchangeable and ASCII letters compare case-insensitively.
+pub fn paths_equal_ci(a: &Path, b: &Path) -> bool {
+    path_key(a) == path_key(b)
+}
+
+/// `path` with forward slashes and ASCII lowercase, for hashing and
+/// comparing under [`paths_equal_ci`].
+fn path_key(path: &Path) -> String {
+    path.to_string_lossy()
+        .replace('\\', "/")
+        .to_ascii_lowercase()
+}
+
 fn normalize_path(path: &Path) -> PathBuf {
     let mut normalized = PathBuf::new();
 
@@ -1164,6 +2861,48 @@ mod tests {
     use super::*;
     use tempfile::tempdir;
 
+    /// Write a minimal Debug/Release x64 project with the given GUID and
+    /// extra XML appended inside `<Project>`.
+    fn write_vcxproj(dir: &Path, name: &str, guid: &str, body: &str) -> PathBuf {
+        let path = dir.join(format!("{name}.vcxproj"));
+        fs::write(
+            &path,
+            format!(
+                r#"<?xml version="1.0" encoding="utf-8"?>
+<Project DefaultTargets="Build" xmlns="http://schemas.microsoft.com/developer/msbuild/2003">
+  <ItemGroup Label="ProjectConfigurations">
+    <ProjectConfiguration Include="Debug|x64" />
+    <ProjectConfiguration Include="Release|x64" />
+  </ItemGroup>
+  <PropertyGroup Label="Globals">
+    <ProjectGuid>{{{guid}}}</ProjectGuid>
+  </PropertyGroup>
+{body}
+</Project>
+"#
+            ),
+        )
+        .unwrap();
+        path
+    }
+
+    /// Write a solution listing `(name, guid)` projects stored as `<name>.vcxproj`.
+    fn write_sln(dir: &Path, projects: &[(&str, &str)], global: &str) -> PathBuf {
+        let mut contents =
+            String::from("Microsoft Visual Studio Solution File, Format Version 12.00\n");
+        for (name, guid) in projects {
+            contents.push_str(&format!(
+                "Project(\"{{8BC9CEB8-8B4A-11D0-8D11-00A0C91BC942}}\") = \"{name}\", \"{name}.vcxproj\", \"{{{guid}}}\"\nEndProject\n"
+            ));
+        }
+        contents.push_str("Global\n");
+        contents.push_str(global);
+        contents.push_str("EndGlobal\n");
+        let path = dir.join("test.sln");
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
     #[test]
     fn parse_solution_with_vcxproj() {
         let dir = tempdir().unwrap();
@@ -1496,4 +3235,1655 @@ EndGlobal
         assert_eq!(extract_guid(""), None);
         assert_eq!(extract_guid("{}"), None);
     }
+
+    #[test]
+    fn wpo_consistency_reports_linked_disagreement() {
+        let dir = tempdir().unwrap();
+        write_vcxproj(
+            dir.path(),
+            "App",
+            "AAAAAAAA-0000-0000-0000-000000000001",
+            r#"  <PropertyGroup Condition="'$(Configuration)|$(Platform)'=='Release|x64'">
+    <WholeProgramOptimization>true</WholeProgramOptimization>
+  </PropertyGroup>
+  <ItemGroup>
+    <ProjectReference Include="Lib.vcxproj">
+      <Project>{aaaaaaaa-0000-0000-0000-000000000002}</Project>
+    </ProjectReference>
+  </ItemGroup>"#,
+        );
+        write_vcxproj(
+            dir.path(),
+            "Lib",
+            "AAAAAAAA-0000-0000-0000-000000000002",
+            r#"  <PropertyGroup Condition="'$(Configuration)|$(Platform)'=='Release|x64'">
+    <WholeProgramOptimization>false</WholeProgramOptimization>
+  </PropertyGroup>"#,
+        );
+        let sln = write_sln(
+            dir.path(),
+            &[
+                ("App", "AAAAAAAA-0000-0000-0000-000000000001"),
+                ("Lib", "AAAAAAAA-0000-0000-0000-000000000002"),
+            ],
+            "",
+        );
+
+        let solution = Solution::from_path(&sln).unwrap();
+
+        let debug = solution.wpo_consistency(&ConfigurationPlatform::new("Debug", "x64"));
+        assert!(debug.is_consistent());
+
+        let release = solution.wpo_consistency(&ConfigurationPlatform::new("Release", "x64"));
+        assert!(!release.is_consistent());
+        assert_eq!(
+            release.disagreements,
+            vec![WpoDisagreement {
+                project: "App".to_string(),
+                project_wpo: true,
+                dependency: "Lib".to_string(),
+                dependency_wpo: false,
+            }]
+        );
+        assert!(release.projects.contains(&("App".to_string(), true)));
+        assert!(release.projects.contains(&("Lib".to_string(), false)));
+    }
+
+    #[test]
+    fn debug_info_report_flags_disabled_projects() {
+        let dir = tempdir().unwrap();
+        write_vcxproj(
+            dir.path(),
+            "App",
+            "AAAAAAAA-0000-0000-0000-000000000001",
+            r#"  <ItemDefinitionGroup Condition="'$(Configuration)|$(Platform)'=='Release|x64'">
+    <Link>
+      <GenerateDebugInformation>true</GenerateDebugInformation>
+    </Link>
+  </ItemDefinitionGroup>"#,
+        );
+        write_vcxproj(
+            dir.path(),
+            "Tool",
+            "AAAAAAAA-0000-0000-0000-000000000002",
+            r#"  <ItemDefinitionGroup Condition="'$(Configuration)|$(Platform)'=='Release|x64'">
+    <Link>
+      <GenerateDebugInformation>false</GenerateDebugInformation>
+    </Link>
+  </ItemDefinitionGroup>"#,
+        );
+        write_vcxproj(
+            dir.path(),
+            "Plain",
+            "AAAAAAAA-0000-0000-0000-000000000003",
+            "",
+        );
+        let sln = write_sln(
+            dir.path(),
+            &[
+                ("App", "AAAAAAAA-0000-0000-0000-000000000001"),
+                ("Tool", "AAAAAAAA-0000-0000-0000-000000000002"),
+                ("Plain", "AAAAAAAA-0000-0000-0000-000000000003"),
+            ],
+            "",
+        );
+
+        let solution = Solution::from_path(&sln).unwrap();
+        let report = solution.debug_info_report(&ConfigurationPlatform::new("Release", "x64"));
+        assert_eq!(
+            report,
+            vec![
+                ("App".to_string(), Some(true)),
+                ("Tool".to_string(), Some(false)),
+                ("Plain".to_string(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_module_definition_file() {
+        let dir = tempdir().unwrap();
+        let path = write_vcxproj(
+            dir.path(),
+            "Plugin",
+            "AAAAAAAA-0000-0000-0000-000000000001",
+            r#"  <ItemDefinitionGroup Condition="'$(Configuration)|$(Platform)'=='Release|x64'">
+    <Link>
+      <ModuleDefinitionFile>src\exports.def</ModuleDefinitionFile>
+    </Link>
+  </ItemDefinitionGroup>
+  <ItemGroup>
+    <ClCompile Include="src\plugin.cpp" />
+    <None Include="src\exports.def" />
+  </ItemGroup>"#,
+        );
+
+        let project = VcxProject::from_path(&path).unwrap();
+        let settings = project
+            .settings_for(&ConfigurationPlatform::new("Release", "x64"))
+            .unwrap();
+        assert_eq!(
+            settings.linker.module_definition_file.as_deref(),
+            Some("src/exports.def")
+        );
+
+        let def = project
+            .files
+            .iter()
+            .find(|item| item.include == Path::new("src/exports.def"))
+            .unwrap();
+        assert_eq!(def.kind, VcxItemKind::ModuleDefinition);
+    }
+
+    #[test]
+    fn reference_cycles_reports_full_path() {
+        let dir = tempdir().unwrap();
+        let reference = |target: &str, guid: &str| {
+            format!(
+                r#"  <ItemGroup>
+    <ProjectReference Include="{target}.vcxproj">
+      <Project>{{{guid}}}</Project>
+    </ProjectReference>
+  </ItemGroup>"#
+            )
+        };
+        let a = "AAAAAAAA-0000-0000-0000-00000000000A";
+        let b = "AAAAAAAA-0000-0000-0000-00000000000B";
+        let c = "AAAAAAAA-0000-0000-0000-00000000000C";
+        let d = "AAAAAAAA-0000-0000-0000-00000000000D";
+        write_vcxproj(dir.path(), "A", a, &reference("B", b));
+        write_vcxproj(dir.path(), "B", b, &reference("C", c));
+        write_vcxproj(dir.path(), "C", c, &reference("A", a));
+        write_vcxproj(dir.path(), "D", d, &reference("A", a));
+        let sln = write_sln(dir.path(), &[("A", a), ("B", b), ("C", c), ("D", d)], "");
+
+        let solution = Solution::from_path(&sln).unwrap();
+        assert_eq!(
+            solution.reference_cycles(),
+            vec![vec!["A".to_string(), "B".to_string(), "C".to_string()]]
+        );
+    }
+
+    #[test]
+    fn classifies_header_only_projects() {
+        let dir = tempdir().unwrap();
+        let headers = write_vcxproj(
+            dir.path(),
+            "Headers",
+            "AAAAAAAA-0000-0000-0000-000000000001",
+            r#"  <ItemGroup>
+    <ClInclude Include="include\lib.h" />
+    <None Include="README.md" />
+  </ItemGroup>"#,
+        );
+        let compiled = write_vcxproj(
+            dir.path(),
+            "Compiled",
+            "AAAAAAAA-0000-0000-0000-000000000002",
+            r#"  <ItemGroup>
+    <ClInclude Include="include\lib.h" />
+    <ClCompile Include="src\lib.cpp" />
+  </ItemGroup>"#,
+        );
+        let empty = write_vcxproj(
+            dir.path(),
+            "Empty",
+            "AAAAAAAA-0000-0000-0000-000000000003",
+            "",
+        );
+
+        assert!(VcxProject::from_path(&headers).unwrap().is_header_only());
+        assert!(!VcxProject::from_path(&compiled).unwrap().is_header_only());
+        assert!(!VcxProject::from_path(&empty).unwrap().is_header_only());
+    }
+
+    #[test]
+    fn platform_toolset_per_configuration() {
+        let dir = tempdir().unwrap();
+        let path = write_vcxproj(
+            dir.path(),
+            "Toolsets",
+            "AAAAAAAA-0000-0000-0000-000000000001",
+            r#"  <PropertyGroup Condition="'$(Configuration)|$(Platform)'=='Debug|x64'" Label="Configuration">
+    <PlatformToolset>v142</PlatformToolset>
+  </PropertyGroup>
+  <PropertyGroup Condition="'$(Configuration)|$(Platform)'=='Release|x64'" Label="Configuration">
+    <PlatformToolset>v143</PlatformToolset>
+  </PropertyGroup>"#,
+        );
+
+        let project = VcxProject::from_path(&path).unwrap();
+        let debug = project.effective_settings_for(&ConfigurationPlatform::new("Debug", "x64"));
+        let release = project.effective_settings_for(&ConfigurationPlatform::new("Release", "x64"));
+        assert_eq!(debug.platform_toolset.as_deref(), Some("v142"));
+        assert_eq!(release.platform_toolset.as_deref(), Some("v143"));
+    }
+
+    #[test]
+    fn infers_language_standard_from_toolset() {
+        let mut settings = ConfigurationSettings::default();
+        assert_eq!(settings.effective_language_standard("v143"), "stdcpp14");
+        assert_eq!(settings.effective_language_standard("v141_xp"), "stdcpp14");
+        assert_eq!(settings.effective_language_standard("ClangCL"), "stdcpp14");
+        assert_eq!(settings.effective_language_standard("v120"), "Default");
+
+        settings.compiler.language_standard = Some("stdcpp20".to_string());
+        assert_eq!(settings.effective_language_standard("v143"), "stdcpp20");
+    }
+
+    #[test]
+    fn synthesizes_implicit_definitions() {
+        let dir = tempdir().unwrap();
+        let path = write_vcxproj(
+            dir.path(),
+            "App",
+            "AAAAAAAA-0000-0000-0000-000000000001",
+            r#"  <PropertyGroup Condition="'$(Configuration)|$(Platform)'=='Debug|x64'" Label="Configuration">
+    <ConfigurationType>Application</ConfigurationType>
+    <CharacterSet>Unicode</CharacterSet>
+  </PropertyGroup>
+  <ItemDefinitionGroup Condition="'$(Configuration)|$(Platform)'=='Debug|x64'">
+    <Link>
+      <SubSystem>Console</SubSystem>
+    </Link>
+  </ItemDefinitionGroup>"#,
+        );
+        let project = VcxProject::from_path(&path).unwrap();
+        let debug = project.effective_settings_for(&ConfigurationPlatform::new("Debug", "x64"));
+        let definitions = debug.implicit_definitions();
+        for expected in ["_DEBUG", "_CONSOLE", "_UNICODE"] {
+            assert!(definitions.iter().any(|d| d == expected), "{expected}");
+        }
+        assert!(!definitions.iter().any(|d| d == "NDEBUG" || d == "_LIB"));
+
+        let mut library = ConfigurationSettings {
+            config: Some(ConfigurationPlatform::new("Release", "x64")),
+            configuration_type: Some(ConfigurationType::StaticLibrary),
+            character_set: Some("MultiByte".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            library.implicit_definitions(),
+            vec!["NDEBUG", "_MBCS", "_LIB"]
+        );
+        library.compiler.runtime_library = Some("MultiThreadedDebugDLL".to_string());
+        assert_eq!(library.implicit_definitions()[0], "_DEBUG");
+    }
+
+    #[test]
+    fn parses_source_control_section() {
+        let dir = tempdir().unwrap();
+        write_vcxproj(
+            dir.path(),
+            "App",
+            "AAAAAAAA-0000-0000-0000-000000000001",
+            "",
+        );
+        let sln = write_sln(
+            dir.path(),
+            &[("App", "AAAAAAAA-0000-0000-0000-000000000001")],
+            r#"	GlobalSection(SourceCodeControl) = preSolution
+		SccNumberOfProjects = 2
+		SccLocalPath0 = .
+		SccProjectName1 = \u0022$/Legacy/App\u0022,\u0020ABCDEFG
+		SccLocalPath1 = App
+	EndGlobalSection
+"#,
+        );
+
+        let solution = Solution::from_path(&sln).unwrap();
+        let scc = solution.source_control.expect("source control section");
+        assert_eq!(scc.len(), 4);
+        assert_eq!(scc["SccNumberOfProjects"], "2");
+        assert_eq!(scc["SccLocalPath1"], "App");
+        assert_eq!(
+            scc["SccProjectName1"],
+            "\\u0022$/Legacy/App\\u0022,\\u0020ABCDEFG"
+        );
+
+        let plain = write_sln(
+            dir.path(),
+            &[("App", "AAAAAAAA-0000-0000-0000-000000000001")],
+            "",
+        );
+        assert!(
+            Solution::from_path(&plain)
+                .unwrap()
+                .source_control
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn infers_primary_language() {
+        let dir = tempdir().unwrap();
+        let c_only = write_vcxproj(
+            dir.path(),
+            "CLib",
+            "AAAAAAAA-0000-0000-0000-000000000001",
+            r#"  <ItemGroup>
+    <ClCompile Include="src\a.c" />
+    <ClCompile Include="src\b.C" />
+    <ClInclude Include="src\a.h" />
+  </ItemGroup>"#,
+        );
+        let mixed = write_vcxproj(
+            dir.path(),
+            "Mixed",
+            "AAAAAAAA-0000-0000-0000-000000000002",
+            r#"  <ItemGroup>
+    <ClCompile Include="src\legacy.c" />
+    <ClCompile Include="src\main.cpp" />
+  </ItemGroup>"#,
+        );
+        let standard_only = write_vcxproj(
+            dir.path(),
+            "Headers",
+            "AAAAAAAA-0000-0000-0000-000000000003",
+            r#"  <ItemDefinitionGroup Condition="'$(Configuration)|$(Platform)'=='Debug|x64'">
+    <ClCompile>
+      <LanguageStandard_C>stdc17</LanguageStandard_C>
+    </ClCompile>
+  </ItemDefinitionGroup>"#,
+        );
+
+        let language = |path: &Path| VcxProject::from_path(path).unwrap().primary_language();
+        assert_eq!(language(&c_only), ProjectLanguage::C);
+        assert_eq!(language(&mixed), ProjectLanguage::Mixed);
+        assert_eq!(language(&standard_only), ProjectLanguage::C);
+    }
+
+    #[test]
+    fn runtime_library_report_lists_mismatch() {
+        let dir = tempdir().unwrap();
+        let runtime = |library: &str| {
+            format!(
+                r#"  <ItemDefinitionGroup Condition="'$(Configuration)|$(Platform)'=='Release|x64'">
+    <ClCompile>
+      <RuntimeLibrary>{library}</RuntimeLibrary>
+    </ClCompile>
+  </ItemDefinitionGroup>"#
+            )
+        };
+        write_vcxproj(
+            dir.path(),
+            "App",
+            "AAAAAAAA-0000-0000-0000-000000000001",
+            &format!(
+                r#"{}
+  <ItemGroup>
+    <ProjectReference Include="Lib.vcxproj">
+      <Project>{{AAAAAAAA-0000-0000-0000-000000000002}}</Project>
+    </ProjectReference>
+  </ItemGroup>"#,
+                runtime("MultiThreadedDLL")
+            ),
+        );
+        write_vcxproj(
+            dir.path(),
+            "Lib",
+            "AAAAAAAA-0000-0000-0000-000000000002",
+            &runtime("MultiThreaded"),
+        );
+        let sln = write_sln(
+            dir.path(),
+            &[
+                ("App", "AAAAAAAA-0000-0000-0000-000000000001"),
+                ("Lib", "AAAAAAAA-0000-0000-0000-000000000002"),
+            ],
+            "",
+        );
+
+        let solution = Solution::from_path(&sln).unwrap();
+        assert_eq!(
+            solution.runtime_library_report(&ConfigurationPlatform::new("Release", "x64")),
+            vec![
+                ("App".to_string(), Some("MultiThreadedDLL".to_string())),
+                ("Lib".to_string(), Some("MultiThreaded".to_string())),
+            ]
+        );
+        assert_eq!(
+            solution.runtime_library_report(&ConfigurationPlatform::new("Debug", "x64")),
+            vec![("App".to_string(), None), ("Lib".to_string(), None)]
+        );
+    }
+
+    #[test]
+    fn resolves_msbuild_macros_in_include_dirs() {
+        let dir = tempdir().unwrap();
+        let path = write_vcxproj(
+            dir.path(),
+            "Engine",
+            "AAAAAAAA-0000-0000-0000-000000000001",
+            r#"  <PropertyGroup Condition="'$(Configuration)|$(Platform)'=='Debug|x64'">
+    <OutDir>$(SolutionDir)bin\$(Platform)\$(Configuration)\</OutDir>
+  </PropertyGroup>
+  <ItemDefinitionGroup Condition="'$(Configuration)|$(Platform)'=='Debug|x64'">
+    <ClCompile>
+      <AdditionalIncludeDirectories>$(SolutionDir)include;$(ProjectDir)src;$(VcpkgRoot)\installed;..\common;%(AdditionalIncludeDirectories)</AdditionalIncludeDirectories>
+    </ClCompile>
+  </ItemDefinitionGroup>"#,
+        );
+        let debug = ConfigurationPlatform::new("Debug", "x64");
+        let project = VcxProject::from_path(&path).unwrap();
+        let project_dir = normalize_path(dir.path());
+
+        assert_eq!(
+            project.resolved_include_dirs(&debug),
+            vec![
+                project_dir.join("include"),
+                project_dir.join("src"),
+                PathBuf::from("$(VcpkgRoot)/installed"),
+                normalize_path(&dir.path().join("../common")),
+            ]
+        );
+
+        let macros = HashMap::from([("SolutionDir".to_string(), "/work/sln/".to_string())]);
+        let resolved = project.resolve_macros(&macros);
+        let settings = resolved.settings_for(&debug).unwrap();
+        assert_eq!(
+            settings.out_dir.as_deref(),
+            Some("/work/sln/bin\\x64\\Debug\\")
+        );
+        assert_eq!(
+            resolved.resolved_include_dirs(&debug),
+            vec![
+                PathBuf::from("/work/sln/include"),
+                project_dir.join("src"),
+                PathBuf::from("$(VcpkgRoot)/installed"),
+                normalize_path(&dir.path().join("../common")),
+            ]
+        );
+    }
+
+    #[test]
+    fn platform_toolset_global_is_fallback() {
+        let dir = tempdir().unwrap();
+        let per_config = write_vcxproj(
+            dir.path(),
+            "PerConfig",
+            "AAAAAAAA-0000-0000-0000-000000000001",
+            r#"  <PropertyGroup Condition="'$(Configuration)|$(Platform)'=='Debug|x64'" Label="Configuration">
+    <PlatformToolset>v142</PlatformToolset>
+  </PropertyGroup>
+  <PropertyGroup Condition="'$(Configuration)|$(Platform)'=='Release|x64'" Label="Configuration">
+    <PlatformToolset>v143</PlatformToolset>
+  </PropertyGroup>"#,
+        );
+        let project = VcxProject::from_path(&per_config).unwrap();
+        let debug = ConfigurationPlatform::new("Debug", "x64");
+        let release = ConfigurationPlatform::new("Release", "x64");
+        assert_eq!(
+            project
+                .settings_for(&debug)
+                .unwrap()
+                .platform_toolset
+                .as_deref(),
+            Some("v142")
+        );
+        assert_eq!(
+            project
+                .settings_for(&release)
+                .unwrap()
+                .platform_toolset
+                .as_deref(),
+            Some("v143")
+        );
+        assert_eq!(project.globals.platform_toolset.as_deref(), Some("v142"));
+
+        let shared = write_vcxproj(
+            dir.path(),
+            "Shared",
+            "AAAAAAAA-0000-0000-0000-000000000002",
+            r#"  <PropertyGroup Label="Configuration">
+    <PlatformToolset>v141</PlatformToolset>
+  </PropertyGroup>
+  <PropertyGroup Condition="'$(Configuration)|$(Platform)'=='Release|x64'" Label="Configuration">
+    <PlatformToolset>v143</PlatformToolset>
+  </PropertyGroup>"#,
+        );
+        let project = VcxProject::from_path(&shared).unwrap();
+        assert_eq!(project.globals.platform_toolset.as_deref(), Some("v141"));
+        let toolset = |config| project.effective_settings_for(config).platform_toolset;
+        assert_eq!(toolset(&debug).as_deref(), Some("v141"));
+        assert_eq!(toolset(&release).as_deref(), Some("v143"));
+    }
+
+    #[test]
+    fn parallel_build_levels_for_diamond() {
+        let dir = tempdir().unwrap();
+        let a = "AAAAAAAA-0000-0000-0000-00000000000A";
+        let b = "AAAAAAAA-0000-0000-0000-00000000000B";
+        let c = "AAAAAAAA-0000-0000-0000-00000000000C";
+        let d = "AAAAAAAA-0000-0000-0000-00000000000D";
+        let e = "AAAAAAAA-0000-0000-0000-00000000000E";
+        let reference = |target: &str, guid: &str| {
+            format!(
+                r#"    <ProjectReference Include="{target}.vcxproj">
+      <Project>{{{guid}}}</Project>
+    </ProjectReference>"#
+            )
+        };
+        write_vcxproj(
+            dir.path(),
+            "A",
+            a,
+            &format!("  <ItemGroup>\n{}\n  </ItemGroup>", reference("B", b)),
+        );
+        write_vcxproj(
+            dir.path(),
+            "B",
+            b,
+            &format!("  <ItemGroup>\n{}\n  </ItemGroup>", reference("D", d)),
+        );
+        write_vcxproj(dir.path(), "C", c, "");
+        write_vcxproj(dir.path(), "D", d, "");
+        write_vcxproj(dir.path(), "E", e, "");
+
+        // A -> C and C -> D are legacy solution-level dependencies; E does not
+        // build in Release.
+        let project = |name: &str, guid: &str, deps: &[&str]| {
+            let mut block = format!(
+                "Project(\"{{8BC9CEB8-8B4A-11D0-8D11-00A0C91BC942}}\") = \"{name}\", \"{name}.vcxproj\", \"{{{guid}}}\"\n"
+            );
+            if !deps.is_empty() {
+                block.push_str("\tProjectSection(ProjectDependencies) = postProject\n");
+                for dep in deps {
+                    block.push_str(&format!("\t\t{{{dep}}} = {{{dep}}}\n"));
+                }
+                block.push_str("\tEndProjectSection\n");
+            }
+            block.push_str("EndProject\n");
+            block
+        };
+        let mut sln = String::from("Microsoft Visual Studio Solution File, Format Version 12.00\n");
+        sln.push_str(&project("A", a, &[c]));
+        sln.push_str(&project("B", b, &[]));
+        sln.push_str(&project("C", c, &[d]));
+        sln.push_str(&project("D", d, &[]));
+        sln.push_str(&project("E", e, &[]));
+        sln.push_str(&format!(
+            "Global\n\tGlobalSection(ProjectConfigurationPlatforms) = postSolution\n\t\t{{{e}}}.Release|x64.ActiveCfg = Release|x64\n\tEndGlobalSection\nEndGlobal\n"
+        ));
+        let path = dir.path().join("diamond.sln");
+        fs::write(&path, sln).unwrap();
+
+        let solution = Solution::from_path(&path).unwrap();
+        assert_eq!(solution.projects[0].dependencies, vec![c.to_string()]);
+
+        let levels = solution.parallel_build_levels(&ConfigurationPlatform::new("Release", "x64"));
+        assert_eq!(
+            levels,
+            vec![
+                vec!["D".to_string()],
+                vec!["B".to_string(), "C".to_string()],
+                vec!["A".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn loads_vcxproj_filters() {
+        let dir = tempdir().unwrap();
+        let path = write_vcxproj(
+            dir.path(),
+            "App",
+            "AAAAAAAA-0000-0000-0000-000000000001",
+            r#"  <ItemGroup>
+    <ClCompile Include="src\net\socket.cpp" />
+    <ClCompile Include="src\main.cpp" />
+    <ClInclude Include="include\socket.h" />
+  </ItemGroup>"#,
+        );
+
+        let without = VcxProject::from_path(&path).unwrap();
+        assert!(without.files.iter().all(|item| item.filter.is_none()));
+
+        fs::write(
+            dir.path().join("App.vcxproj.filters"),
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<Project ToolsVersion="4.0" xmlns="http://schemas.microsoft.com/developer/msbuild/2003">
+  <ItemGroup>
+    <Filter Include="Source Files">
+      <UniqueIdentifier>{4FC737F1-C7A5-4376-A066-2A32D752A2FF}</UniqueIdentifier>
+    </Filter>
+    <Filter Include="Source Files\Net" />
+  </ItemGroup>
+  <ItemGroup>
+    <ClCompile Include="src\net\socket.cpp">
+      <Filter>Source Files\Net</Filter>
+    </ClCompile>
+    <ClCompile Include="src\main.cpp">
+      <Filter>Source Files</Filter>
+    </ClCompile>
+  </ItemGroup>
+</Project>
+"#,
+        )
+        .unwrap();
+
+        let project = VcxProject::from_path(&path).unwrap();
+        let filter = |include: &str| {
+            project
+                .files
+                .iter()
+                .find(|item| item.include == Path::new(include))
+                .and_then(|item| item.filter.clone())
+        };
+        assert_eq!(
+            filter("src/net/socket.cpp").as_deref(),
+            Some("Source Files/Net")
+        );
+        assert_eq!(filter("src/main.cpp").as_deref(), Some("Source Files"));
+        assert_eq!(filter("include/socket.h"), None);
+    }
+
+    #[test]
+    fn loads_mixed_cpp_and_csharp_solution() {
+        let dir = tempdir().unwrap();
+        write_vcxproj(
+            dir.path(),
+            "Native",
+            "AAAAAAAA-0000-0000-0000-000000000001",
+            r#"  <ItemGroup>
+    <ClCompile Include="native.cpp" />
+  </ItemGroup>"#,
+        );
+        let managed_dir = dir.path().join("Managed");
+        fs::create_dir_all(managed_dir.join("Services")).unwrap();
+        fs::create_dir_all(managed_dir.join("obj")).unwrap();
+        fs::write(managed_dir.join("Program.cs"), "").unwrap();
+        fs::write(managed_dir.join("Services/Worker.cs"), "").unwrap();
+        fs::write(managed_dir.join("Services/Legacy.cs"), "").unwrap();
+        fs::write(managed_dir.join("obj/AssemblyInfo.cs"), "").unwrap();
+        fs::write(
+            managed_dir.join("Managed.csproj"),
+            r#"<Project Sdk="Microsoft.NET.Sdk">
+  <PropertyGroup>
+    <OutputType>Exe</OutputType>
+    <TargetFramework>net8.0</TargetFramework>
+    <RootNamespace>Company.Managed</RootNamespace>
+  </PropertyGroup>
+  <ItemGroup>
+    <Compile Remove="Services\Legacy.cs" />
+    <PackageReference Include="Serilog" Version="3.1.1" />
+  </ItemGroup>
+</Project>"#,
+        )
+        .unwrap();
+        let sln = dir.path().join("Mixed.sln");
+        fs::write(
+            &sln,
+            format!(
+                "Project(\"{{{}}}\") = \"Native\", \"Native.vcxproj\", \"{{AAAAAAAA-0000-0000-0000-000000000001}}\"\nEndProject\n\
+                 Project(\"{{{}}}\") = \"Managed\", \"Managed\\Managed.csproj\", \"{{BBBBBBBB-0000-0000-0000-000000000002}}\"\nEndProject\n",
+                project_types::VCXPROJ,
+                project_types::CSPROJ
+            ),
+        )
+        .unwrap();
+
+        let solution = Solution::from_path(&sln).unwrap();
+        assert_eq!(solution.projects.len(), 2);
+
+        let native = &solution.projects[0];
+        assert!(native.project.is_some());
+        assert!(native.csproj.is_none());
+
+        let managed = &solution.projects[1];
+        assert!(managed.project.is_none());
+        assert!(managed.load_error.is_none());
+        let csproj = managed.csproj.as_ref().unwrap();
+        assert!(csproj.sdk_style);
+        assert_eq!(csproj.output_type.as_deref(), Some("Exe"));
+        assert_eq!(csproj.target_framework.as_deref(), Some("net8.0"));
+        assert_eq!(csproj.root_namespace.as_deref(), Some("Company.Managed"));
+        assert_eq!(csproj.package_references[0].name, "Serilog");
+        assert_eq!(
+            csproj.package_references[0].version.as_deref(),
+            Some("3.1.1")
+        );
+        let includes: Vec<_> = csproj.files.iter().map(|f| f.include.clone()).collect();
+        assert_eq!(
+            includes,
+            vec![
+                PathBuf::from("Program.cs"),
+                PathBuf::from("Services/Worker.cs")
+            ]
+        );
+    }
+
+    #[test]
+    fn rebases_project_and_solution_paths() {
+        let dir = tempdir().unwrap();
+        write_vcxproj(
+            dir.path(),
+            "App",
+            "AAAAAAAA-0000-0000-0000-000000000001",
+            r#"  <ItemGroup>
+    <ClCompile Include="src\main.cpp" />
+    <ClInclude Include="..\shared\api.h" />
+  </ItemGroup>
+  <ItemGroup>
+    <ProjectReference Include="..\Lib\Lib.vcxproj" />
+  </ItemGroup>"#,
+        );
+        let sln = write_sln(
+            dir.path(),
+            &[("App", "AAAAAAAA-0000-0000-0000-000000000001")],
+            "",
+        );
+        let mut solution = Solution::from_path(&sln).unwrap();
+        let includes_before: Vec<_> = solution.projects[0]
+            .project
+            .as_ref()
+            .unwrap()
+            .files
+            .iter()
+            .map(|item| item.include.clone())
+            .collect();
+
+        let moved = Path::new("/moved/work");
+        solution.rebase(moved);
+        assert_eq!(solution.path, moved.join("test.sln"));
+        let project = &solution.projects[0];
+        assert_eq!(project.absolute_path, moved.join("App.vcxproj"));
+
+        let vcx = project.project.as_ref().unwrap();
+        assert_eq!(vcx.path, moved.join("App.vcxproj"));
+        let includes_after: Vec<_> = vcx.files.iter().map(|item| item.include.clone()).collect();
+        assert_eq!(includes_after, includes_before);
+        let full_paths: Vec<_> = vcx
+            .files
+            .iter()
+            .map(|item| item.full_path.clone())
+            .collect();
+        assert_eq!(
+            full_paths,
+            vec![
+                PathBuf::from("/moved/shared/api.h"),
+                PathBuf::from("/moved/work/src/main.cpp")
+            ]
+        );
+        assert_eq!(
+            vcx.project_references[0].full_path,
+            PathBuf::from("/moved/Lib/Lib.vcxproj")
+        );
+        assert_eq!(
+            vcx.project_references[0].include,
+            PathBuf::from("../Lib/Lib.vcxproj")
+        );
+    }
+
+    #[test]
+    fn reads_utf16_solution_with_bom() {
+        let dir = tempdir().unwrap();
+        let text = "\r\nMicrosoft Visual Studio Solution File, Format Version 12.00\r\n\
+                    Global\r\n\
+                    \tGlobalSection(SolutionConfigurationPlatforms) = preSolution\r\n\
+                    \t\tDebug|x64 = Debug|x64\r\n\
+                    \t\tRelease|Win32 = Release|Win32\r\n\
+                    \tEndGlobalSection\r\n\
+                    EndGlobal\r\n";
+        let mut bytes = vec![0xFF, 0xFE];
+        bytes.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+        let path = dir.path().join("wide.sln");
+        fs::write(&path, bytes).unwrap();
+
+        let solution = Solution::from_path(&path).unwrap();
+        assert_eq!(
+            solution.configurations,
+            vec![
+                ConfigurationPlatform::new("Debug", "x64"),
+                ConfigurationPlatform::new("Release", "Win32")
+            ]
+        );
+
+        let mut bytes = vec![0xFE, 0xFF];
+        bytes.extend(text.encode_utf16().flat_map(u16::to_be_bytes));
+        fs::write(&path, bytes).unwrap();
+        assert_eq!(Solution::from_path(&path).unwrap().configurations.len(), 2);
+    }
+
+    #[test]
+    fn orders_projects_by_dependencies() {
+        let dir = tempdir().unwrap();
+        let reference = |name: &str, guid: &str| {
+            format!(
+                "  <ItemGroup>\n    <ProjectReference Include=\"{name}.vcxproj\">\n      <Project>{{{guid}}}</Project>\n    </ProjectReference>\n  </ItemGroup>"
+            )
+        };
+        let app = "AAAAAAAA-0000-0000-0000-000000000001";
+        let core = "AAAAAAAA-0000-0000-0000-000000000002";
+        let util = "AAAAAAAA-0000-0000-0000-000000000003";
+        write_vcxproj(dir.path(), "App", app, &reference("Core", core));
+        // Matched by path only.
+        write_vcxproj(
+            dir.path(),
+            "Core",
+            core,
+            "  <ItemGroup>\n    <ProjectReference Include=\"Util.vcxproj\" />\n  </ItemGroup>",
+        );
+        write_vcxproj(dir.path(), "Util", util, "");
+        let sln = write_sln(
+            dir.path(),
+            &[
+                ("App", app),
+                ("Missing", "AAAAAAAA-0000-0000-0000-000000000009"),
+                ("Core", core),
+                ("Util", util),
+            ],
+            "",
+        );
+
+        let solution = Solution::from_path(&sln).unwrap();
+        assert!(solution.projects[1].load_error.is_some());
+        let names: Vec<_> = solution
+            .build_order()
+            .unwrap()
+            .into_iter()
+            .map(|project| project.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["Missing", "Util", "Core", "App"]);
+
+        // Close the loop Util -> App.
+        write_vcxproj(dir.path(), "Util", util, &reference("App", app));
+        let solution = Solution::from_path(&sln).unwrap();
+        let mut cycle = solution.build_order().unwrap_err();
+        cycle.sort();
+        assert_eq!(cycle, vec![app, core, util]);
+    }
+
+    #[test]
+    fn reports_precompiled_header_usage() {
+        let dir = tempdir().unwrap();
+        let path = write_vcxproj(
+            dir.path(),
+            "App",
+            "AAAAAAAA-0000-0000-0000-000000000001",
+            r#"  <ItemDefinitionGroup>
+    <ClCompile>
+      <PrecompiledHeader>Use</PrecompiledHeader>
+      <PrecompiledHeaderFile>pch.h</PrecompiledHeaderFile>
+    </ClCompile>
+  </ItemDefinitionGroup>
+  <ItemGroup>
+    <ClCompile Include="pch.cpp">
+      <PrecompiledHeader>Create</PrecompiledHeader>
+    </ClCompile>
+    <ClCompile Include="main.cpp" />
+    <ClCompile Include="third_party\zlib.c">
+      <PrecompiledHeader>NotUsing</PrecompiledHeader>
+    </ClCompile>
+    <ClCompile Include="debug_only.cpp">
+      <PrecompiledHeader Condition="'$(Configuration)|$(Platform)'=='Debug|x64'">NotUsing</PrecompiledHeader>
+    </ClCompile>
+    <ClInclude Include="pch.h" />
+  </ItemGroup>"#,
+        );
+        let project = VcxProject::from_path(&path).unwrap();
+
+        let debug = project.pch_usage(&ConfigurationPlatform::new("Debug", "x64"));
+        assert_eq!(debug.header.as_deref(), Some("pch.h"));
+        assert_eq!(debug.creating_file, Some(dir.path().join("pch.cpp")));
+        assert_eq!(
+            debug.opted_out_files,
+            vec![
+                dir.path().join("debug_only.cpp"),
+                dir.path().join("third_party/zlib.c")
+            ]
+        );
+
+        let release = project.pch_usage(&ConfigurationPlatform::new("Release", "x64"));
+        assert_eq!(
+            release.opted_out_files,
+            vec![dir.path().join("third_party/zlib.c")]
+        );
+    }
+
+    #[test]
+    fn parses_nmake_settings() {
+        let dir = tempdir().unwrap();
+        let path = write_vcxproj(
+            dir.path(),
+            "Kernel",
+            "AAAAAAAA-0000-0000-0000-000000000001",
+            r#"  <PropertyGroup Condition="'$(Configuration)|$(Platform)'=='Debug|x64'" Label="Configuration">
+    <ConfigurationType>Makefile</ConfigurationType>
+  </PropertyGroup>
+  <PropertyGroup Condition="'$(Configuration)|$(Platform)'=='Release|x64'" Label="Configuration">
+    <ConfigurationType>Application</ConfigurationType>
+  </PropertyGroup>
+  <PropertyGroup Condition="'$(Configuration)|$(Platform)'=='Debug|x64'">
+    <NMakeBuildCommandLine>make -C $(ProjectDir) all</NMakeBuildCommandLine>
+    <NMakeCleanCommandLine>make -C $(ProjectDir) clean</NMakeCleanCommandLine>
+    <NMakeOutput>build\kernel.elf</NMakeOutput>
+    <NMakePreprocessorDefinitions>KERNEL;DEBUG=1;$(NMakePreprocessorDefinitions)</NMakePreprocessorDefinitions>
+    <NMakeIncludeSearchPath>include;arch\x86\include;$(NMakeIncludeSearchPath)</NMakeIncludeSearchPath>
+  </PropertyGroup>
+  <PropertyGroup Condition="'$(Configuration)|$(Platform)'=='Release|x64'">
+    <NMakeOutput>ignored.exe</NMakeOutput>
+  </PropertyGroup>"#,
+        );
+        let project = VcxProject::from_path(&path).unwrap();
+        let debug = ConfigurationPlatform::new("Debug", "x64");
+
+        let nmake = project
+            .settings_for(&debug)
+            .unwrap()
+            .nmake
+            .as_ref()
+            .unwrap();
+        assert_eq!(
+            nmake.build_command_line.as_deref(),
+            Some("make -C $(ProjectDir) all")
+        );
+        assert_eq!(nmake.output.as_deref(), Some("build\\kernel.elf"));
+        assert!(nmake.rebuild_command_line.is_none());
+        assert_eq!(nmake.preprocessor_definitions, vec!["KERNEL", "DEBUG=1"]);
+        assert_eq!(
+            project.resolved_include_dirs(&debug),
+            vec![
+                dir.path().join("include"),
+                dir.path().join("arch/x86/include")
+            ]
+        );
+
+        let release = ConfigurationPlatform::new("Release", "x64");
+        assert!(project.settings_for(&release).unwrap().nmake.is_none());
+    }
+
+    #[test]
+    fn parses_build_events() {
+        let dir = tempdir().unwrap();
+        let path = write_vcxproj(
+            dir.path(),
+            "App",
+            "AAAAAAAA-0000-0000-0000-000000000001",
+            r#"  <ItemDefinitionGroup Condition="'$(Configuration)|$(Platform)'=='Release|x64'">
+    <PostBuildEvent>
+      <Command>xcopy /y "$(OutDir)App.exe" "$(SolutionDir)dist\"</Command>
+      <Message>Copying to dist</Message>
+    </PostBuildEvent>
+  </ItemDefinitionGroup>"#,
+        );
+        let project = VcxProject::from_path(&path).unwrap();
+
+        let release = project
+            .settings_for(&ConfigurationPlatform::new("Release", "x64"))
+            .unwrap();
+        assert_eq!(
+            release.post_build_command.as_deref(),
+            Some(r#"xcopy /y "$(OutDir)App.exe" "$(SolutionDir)dist\""#)
+        );
+        assert_eq!(
+            release.post_build_message.as_deref(),
+            Some("Copying to dist")
+        );
+        assert!(release.pre_build_command.is_none());
+        assert!(
+            project
+                .effective_settings_for(&ConfigurationPlatform::new("Debug", "x64"))
+                .post_build_command
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn parses_subsystem_version() {
+        let dir = tempdir().unwrap();
+        let path = write_vcxproj(
+            dir.path(),
+            "App",
+            "AAAAAAAA-0000-0000-0000-000000000001",
+            r#"  <ItemDefinitionGroup Condition="'$(Configuration)|$(Platform)'=='Debug|x64'">
+    <Link>
+      <SubSystem>Windows</SubSystem>
+    </Link>
+  </ItemDefinitionGroup>
+  <ItemDefinitionGroup Condition="'$(Configuration)|$(Platform)'=='Release|x64'">
+    <Link>
+      <AdditionalOptions>/LARGEADDRESSAWARE /SUBSYSTEM:WINDOWS,5.01 %(AdditionalOptions)</AdditionalOptions>
+    </Link>
+  </ItemDefinitionGroup>"#,
+        );
+        let project = VcxProject::from_path(&path).unwrap();
+
+        let debug = project
+            .effective_settings_for(&ConfigurationPlatform::new("Debug", "x64"))
+            .linker;
+        assert_eq!(debug.subsystem.as_deref(), Some("Windows"));
+        assert_eq!(debug.subsystem_version, None);
+
+        let release = project
+            .effective_settings_for(&ConfigurationPlatform::new("Release", "x64"))
+            .linker;
+        assert_eq!(release.subsystem.as_deref(), Some("Windows"));
+        assert_eq!(release.subsystem_version.as_deref(), Some("5.01"));
+
+        assert_eq!(split_subsystem("Console,6.0"), ("Console", Some("6.0")));
+        assert_eq!(split_subsystem("Console"), ("Console", None));
+    }
+
+    #[test]
+    fn parses_project_capabilities() {
+        let dir = tempdir().unwrap();
+        let path = write_vcxproj(
+            dir.path(),
+            "App",
+            "AAAAAAAA-0000-0000-0000-000000000001",
+            r#"  <ItemGroup>
+    <ProjectCapability Include="VisualStudioWindowsDesktop" />
+    <ProjectCapability Include="PackageReferences" />
+    <ProjectCapability Include="VisualStudioWindowsDesktop" />
+  </ItemGroup>"#,
+        );
+        let project = VcxProject::from_path(&path).unwrap();
+
+        assert_eq!(
+            project.capabilities,
+            vec!["VisualStudioWindowsDesktop", "PackageReferences"]
+        );
+        assert!(project.has_capability("visualstudiowindowsdesktop"));
+        assert!(!project.has_capability("WindowsXaml"));
+    }
+
+    #[test]
+    fn parses_assembly_references() {
+        let dir = tempdir().unwrap();
+        let path = write_vcxproj(
+            dir.path(),
+            "Bridge",
+            "AAAAAAAA-0000-0000-0000-000000000001",
+            r#"  <ItemGroup>
+    <Reference Include="System" />
+    <Reference Include="Vendor.Sdk, Version=2.1.0.0, Culture=neutral, PublicKeyToken=0123456789abcdef">
+      <HintPath>..\libs\Vendor.Sdk.dll</HintPath>
+    </Reference>
+    <Reference Include="Generated">
+      <HintPath>$(OutDir)Generated.dll</HintPath>
+    </Reference>
+  </ItemGroup>"#,
+        );
+        let project = VcxProject::from_path(&path).unwrap();
+
+        assert_eq!(
+            project.assembly_references,
+            vec![
+                AssemblyReference {
+                    name: "System".to_string(),
+                    hint_path: None,
+                },
+                AssemblyReference {
+                    name: "Vendor.Sdk".to_string(),
+                    hint_path: Some(normalize_path(&dir.path().join("../libs/Vendor.Sdk.dll"))),
+                },
+                AssemblyReference {
+                    name: "Generated".to_string(),
+                    hint_path: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn lists_source_files_with_language() {
+        let dir = tempdir().unwrap();
+        let app = "AAAAAAAA-0000-0000-0000-000000000001";
+        write_vcxproj(
+            dir.path(),
+            "App",
+            app,
+            r#"  <ItemGroup>
+    <ClCompile Include="src\main.cpp" />
+    <ClInclude Include="include\app.h" />
+    <ResourceCompile Include="app.rc" />
+    <Image Include="icon.ico" />
+  </ItemGroup>"#,
+        );
+        let sln = write_sln(dir.path(), &[("App", app)], "");
+        let solution = Solution::from_path(&sln).unwrap();
+
+        let mut files: Vec<_> = solution.source_files_with_language().collect();
+        files.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            files,
+            vec![
+                (dir.path().join("include/app.h"), Language::CHeader),
+                (dir.path().join("src/main.cpp"), Language::Cpp),
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_projects_owning_a_file() {
+        let dir = tempdir().unwrap();
+        let app = "AAAAAAAA-0000-0000-0000-000000000001";
+        let lib = "AAAAAAAA-0000-0000-0000-000000000002";
+        write_vcxproj(
+            dir.path(),
+            "App",
+            app,
+            r#"  <ItemGroup>
+    <ClCompile Include="src\main.cpp" />
+    <ClInclude Include="include\shared.h" />
+  </ItemGroup>"#,
+        );
+        write_vcxproj(
+            dir.path(),
+            "Lib",
+            lib,
+            r#"  <ItemGroup>
+    <ClCompile Include="src\lib.cpp" />
+    <ClInclude Include="include\shared.h" />
+  </ItemGroup>"#,
+        );
+        let sln = write_sln(dir.path(), &[("App", app), ("Lib", lib)], "");
+        let solution = Solution::from_path(&sln).unwrap();
+
+        let shared = dir.path().join("src/../include/shared.h");
+        let owners: Vec<_> = solution
+            .projects_for_file(&shared)
+            .map(|project| project.name.as_str())
+            .collect();
+        assert_eq!(owners, vec!["App", "Lib"]);
+        assert_eq!(
+            solution.project_for_file(&shared).map(|p| p.name.as_str()),
+            Some("App")
+        );
+        assert_eq!(
+            solution
+                .project_for_file(&dir.path().join("src/lib.cpp"))
+                .map(|p| p.name.as_str()),
+            Some("Lib")
+        );
+        assert!(
+            solution
+                .project_for_file(&dir.path().join("src/other.cpp"))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn compares_paths_with_windows_semantics() {
+        assert!(paths_equal_ci(
+            Path::new("src\\Main.cpp"),
+            Path::new("src/main.cpp")
+        ));
+        assert!(paths_equal_ci(
+            Path::new("C:\\Proj\\A.H"),
+            Path::new("c:/proj/a.h")
+        ));
+        assert!(!paths_equal_ci(
+            Path::new("src/main.cpp"),
+            Path::new("src/main.c")
+        ));
+
+        let dir = tempdir().unwrap();
+        let app = "AAAAAAAA-0000-0000-0000-000000000001";
+        write_vcxproj(
+            dir.path(),
+            "App",
+            app,
+            r#"  <ItemGroup>
+    <ClCompile Include="src\Main.cpp" />
+    <ClCompile Include="src/main.cpp" />
+  </ItemGroup>"#,
+        );
+        // Referenced by path only, spelled differently from the solution.
+        let lib = "AAAAAAAA-0000-0000-0000-000000000002";
+        write_vcxproj(
+            dir.path(),
+            "Lib",
+            lib,
+            "  <ItemGroup>\n    <ProjectReference Include=\".\\APP.vcxproj\" />\n  </ItemGroup>",
+        );
+        let sln = write_sln(dir.path(), &[("Lib", lib), ("App", app)], "");
+        let solution = Solution::from_path(&sln).unwrap();
+
+        let vcx = solution.projects[1].project.as_ref().unwrap();
+        assert_eq!(vcx.files.len(), 1);
+        assert_eq!(
+            solution
+                .project_for_file(&dir.path().join("SRC/MAIN.CPP"))
+                .map(|p| p.name.as_str()),
+            Some("App")
+        );
+
+        let dependents: Vec<_> = solution
+            .dependents_of(app)
+            .into_iter()
+            .map(|project| project.name.as_str())
+            .collect();
+        assert_eq!(dependents, vec!["Lib"]);
+        let order: Vec<_> = solution
+            .build_order()
+            .unwrap()
+            .into_iter()
+            .map(|project| project.name.as_str())
+            .collect();
+        assert_eq!(order, vec!["App", "Lib"]);
+    }
+
+    #[test]
+    fn reports_configuration_drift() {
+        let dir = tempdir().unwrap();
+        let app = "AAAAAAAA-0000-0000-0000-000000000001";
+        let lib = "AAAAAAAA-0000-0000-0000-000000000002";
+        write_vcxproj(dir.path(), "App", app, "");
+        write_vcxproj(dir.path(), "Lib", lib, "");
+        let sln = write_sln(
+            dir.path(),
+            &[("App", app), ("Lib", lib)],
+            &format!(
+                "    GlobalSection(SolutionConfigurationPlatforms) = preSolution
+        Debug|x64 = Debug|x64
+        Release|x64 = Release|x64
+        Shipping|x64 = Shipping|x64
+    EndGlobalSection
+    GlobalSection(ProjectConfigurationPlatforms) = postSolution
+        {{{app}}}.Debug|x64.ActiveCfg = Debug|x64
+        {{{app}}}.Release|x64.ActiveCfg = Release|x64
+        {{{lib}}}.Debug|x64.ActiveCfg = Debug|x64
+    EndGlobalSection
+"
+            ),
+        );
+        let solution = Solution::from_path(&sln).unwrap();
+
+        assert_eq!(
+            solution.orphan_project_configurations(),
+            vec![(
+                "Lib".to_string(),
+                ConfigurationPlatform::new("Release", "x64")
+            )]
+        );
+        assert_eq!(
+            solution.unused_solution_configurations(),
+            vec![ConfigurationPlatform::new("Shipping", "x64")]
+        );
+    }
+
+    #[test]
+    fn reports_platform_mismatches() {
+        let dir = tempdir().unwrap();
+        let app = "AAAAAAAA-0000-0000-0000-000000000001";
+        let lib = "AAAAAAAA-0000-0000-0000-000000000002";
+        write_vcxproj(dir.path(), "App", app, "");
+        write_vcxproj(dir.path(), "Lib", lib, "");
+        let sln = write_sln(
+            dir.path(),
+            &[("App", app), ("Lib", lib)],
+            &format!(
+                "    GlobalSection(ProjectConfigurationPlatforms) = postSolution
+        {{{app}}}.Debug|x64.ActiveCfg = Debug|x64
+        {{{app}}}.Debug|x86.ActiveCfg = Debug|Win32
+        {{{lib}}}.Debug|x64.ActiveCfg = Debug|Win32
+        {{{lib}}}.Debug|x64.Build.0 = Debug|Win32
+        {{{lib}}}.Debug|Mixed Platforms.ActiveCfg = Debug|Win32
+    EndGlobalSection
+"
+            ),
+        );
+        let solution = Solution::from_path(&sln).unwrap();
+
+        assert_eq!(
+            solution.platform_mismatches(),
+            vec![PlatformMismatch {
+                project: "Lib".to_string(),
+                solution_config: ConfigurationPlatform::new("Debug", "x64"),
+                project_config: ConfigurationPlatform::new("Debug", "Win32"),
+                build: true,
+            }]
+        );
+    }
+
+    #[test]
+    fn queries_active_project_configuration() {
+        let dir = tempdir().unwrap();
+        let app = "AAAAAAAA-0000-0000-0000-000000000001";
+        write_vcxproj(dir.path(), "App", app, "");
+        let sln = write_sln(
+            dir.path(),
+            &[("App", app)],
+            &format!(
+                "    GlobalSection(ProjectConfigurationPlatforms) = postSolution
+        {{{app}}}.Debug|x64.ActiveCfg = Debug|x64
+        {{{app}}}.Release|x64.ActiveCfg = Release|Win32
+        {{{app}}}.Release|x64.Build.0 = Release|Win32
+    EndGlobalSection
+"
+            ),
+        );
+        let solution = Solution::from_path(&sln).unwrap();
+        let debug = ConfigurationPlatform::new("Debug", "x64");
+        let release = ConfigurationPlatform::new("Release", "x64");
+
+        let guid = format!("{{{}}}", app.to_ascii_lowercase());
+        let mapping = solution.active_project_config(&guid, &release).unwrap();
+        assert_eq!(
+            mapping.project_config,
+            ConfigurationPlatform::new("Release", "Win32")
+        );
+        assert!(mapping.build);
+        assert!(solution.builds_in(&guid, &release));
+        assert!(!solution.builds_in(app, &debug));
+
+        let unmapped = ConfigurationPlatform::new("Profile", "x64");
+        assert!(solution.active_project_config(app, &unmapped).is_none());
+        assert!(!solution.builds_in(app, &unmapped));
+        assert!(!solution.builds_in("FFFFFFFF-0000-0000-0000-000000000000", &release));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn exports_solution_as_json() {
+        let dir = tempdir().unwrap();
+        let app = "AAAAAAAA-0000-0000-0000-000000000001";
+        write_vcxproj(dir.path(), "App", app, "");
+        let sln = write_sln(
+            dir.path(),
+            &[("App", app)],
+            &format!(
+                "    GlobalSection(SolutionConfigurationPlatforms) = preSolution
+        Debug|x64 = Debug|x64
+        Release|x64 = Release|x64
+    EndGlobalSection
+    GlobalSection(ProjectConfigurationPlatforms) = postSolution
+        {{{app}}}.Debug|x64.ActiveCfg = Debug|x64
+        {{{app}}}.Debug|x64.Build.0 = Debug|x64
+    EndGlobalSection
+"
+            ),
+        );
+        let solution = Solution::from_path(&sln).unwrap();
+        let json = solution.to_json();
+
+        assert_eq!(
+            json["configurations"],
+            serde_json::json!(["Debug|x64", "Release|x64"])
+        );
+        let project = &json["projects"][0];
+        assert_eq!(project["name"], "App");
+        assert_eq!(project["path"], "App.vcxproj");
+        assert_eq!(
+            project["configurations"],
+            serde_json::json!(["Debug|x64", "Release|x64"])
+        );
+        let mapping = &json["mappings"][app][0];
+        assert_eq!(mapping["solution_config"], "Debug|x64");
+        assert_eq!(mapping["build"], true);
+
+        // The settings types serialize as well, paths as strings.
+        let vcx = serde_json::to_value(solution.projects[0].project.as_ref().unwrap()).unwrap();
+        assert_eq!(vcx["name"], "App");
+        assert!(vcx["path"].as_str().unwrap().ends_with("App.vcxproj"));
+    }
+
+    #[test]
+    fn finds_dependents_of_a_project() {
+        let dir = tempdir().unwrap();
+        let a = "AAAAAAAA-0000-0000-0000-000000000001";
+        let b = "AAAAAAAA-0000-0000-0000-000000000002";
+        let c = "AAAAAAAA-0000-0000-0000-000000000003";
+        write_vcxproj(
+            dir.path(),
+            "A",
+            a,
+            &format!(
+                "  <ItemGroup>\n    <ProjectReference Include=\"B.vcxproj\">\n      <Project>{{{}}}</Project>\n    </ProjectReference>\n  </ItemGroup>",
+                b.to_ascii_lowercase()
+            ),
+        );
+        write_vcxproj(dir.path(), "B", b, "");
+        // Matched by path only.
+        write_vcxproj(
+            dir.path(),
+            "C",
+            c,
+            "  <ItemGroup>\n    <ProjectReference Include=\"B.vcxproj\" />\n  </ItemGroup>",
+        );
+        let sln = write_sln(dir.path(), &[("A", a), ("B", b), ("C", c)], "");
+        let solution = Solution::from_path(&sln).unwrap();
+
+        let names: Vec<_> = solution
+            .dependents_of(b)
+            .into_iter()
+            .map(|project| project.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["A", "C"]);
+        assert_eq!(solution.dependents_of(&format!("{{{b}}}")).len(), 2);
+        assert!(solution.dependents_of(a).is_empty());
+        assert!(
+            solution
+                .dependents_of("FFFFFFFF-0000-0000-0000-000000000000")
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn parses_per_file_exclusions_and_defines() {
+        let dir = tempdir().unwrap();
+        let path = write_vcxproj(
+            dir.path(),
+            "App",
+            "AAAAAAAA-0000-0000-0000-000000000001",
+            r#"  <ItemGroup>
+    <ClCompile Include="main.cpp" />
+    <ClCompile Include="debug_overlay.cpp">
+      <ExcludedFromBuild Condition="'$(Configuration)|$(Platform)'=='Release|x64'">true</ExcludedFromBuild>
+      <PreprocessorDefinitions>OVERLAY=1;%(PreprocessorDefinitions)</PreprocessorDefinitions>
+    </ClCompile>
+    <ClCompile Include="unused.cpp">
+      <ExcludedFromBuild>true</ExcludedFromBuild>
+    </ClCompile>
+  </ItemGroup>"#,
+        );
+        let project = VcxProject::from_path(&path).unwrap();
+        let item = |include: &str| {
+            project
+                .files
+                .iter()
+                .find(|item| item.include == Path::new(include))
+                .unwrap()
+        };
+        let debug = ConfigurationPlatform::new("Debug", "x64");
+        let release = ConfigurationPlatform::new("Release", "x64");
+
+        let overlay = item("debug_overlay.cpp");
+        assert_eq!(overlay.excluded_configs, vec![release.clone()]);
+        assert_eq!(overlay.per_file_defines, vec!["OVERLAY=1".to_string()]);
+        assert!(overlay.is_built_in(&debug));
+        assert!(!overlay.is_built_in(&release));
+
+        let main = item("main.cpp");
+        assert!(main.is_built_in(&debug) && main.is_built_in(&release));
+        assert!(main.per_file_defines.is_empty());
+
+        let unused = item("unused.cpp");
+        assert!(!unused.is_built_in(&debug) && !unused.is_built_in(&release));
+    }
+
+    #[test]
+    fn parses_disabled_warnings_and_forced_includes() {
+        let dir = tempdir().unwrap();
+        let path = write_vcxproj(
+            dir.path(),
+            "App",
+            "AAAAAAAA-0000-0000-0000-000000000001",
+            r#"  <ItemDefinitionGroup Condition="'$(Configuration)|$(Platform)'=='Debug|x64'">
+    <ClCompile>
+      <DisableSpecificWarnings>4100;4996;%(DisableSpecificWarnings)</DisableSpecificWarnings>
+      <ForcedIncludeFiles>pch.h;config\platform.h;%(ForcedIncludeFiles)</ForcedIncludeFiles>
+    </ClCompile>
+  </ItemDefinitionGroup>"#,
+        );
+        let project = VcxProject::from_path(&path).unwrap();
+
+        let (_, settings) = project
+            .config_settings
+            .iter()
+            .find(|(key, _)| key.as_str() == "Debug|x64")
+            .unwrap();
+        assert_eq!(settings.compiler.disabled_warnings, vec!["4100", "4996"]);
+        assert_eq!(
+            settings.compiler.forced_includes,
+            vec!["pch.h", "config/platform.h"]
+        );
+    }
+
+    #[test]
+    fn merges_unconditioned_item_definitions() {
+        let dir = tempdir().unwrap();
+        let path = write_vcxproj(
+            dir.path(),
+            "App",
+            "AAAAAAAA-0000-0000-0000-000000000001",
+            r#"  <ItemDefinitionGroup>
+    <ClCompile>
+      <LanguageStandard>stdcpp20</LanguageStandard>
+      <PreprocessorDefinitions>WIN32;%(PreprocessorDefinitions)</PreprocessorDefinitions>
+    </ClCompile>
+  </ItemDefinitionGroup>
+  <ItemDefinitionGroup Condition="'$(Configuration)|$(Platform)'=='Debug|x64'">
+    <ClCompile>
+      <Optimization>Disabled</Optimization>
+      <PreprocessorDefinitions>_DEBUG;WIN32;%(PreprocessorDefinitions)</PreprocessorDefinitions>
+    </ClCompile>
+  </ItemDefinitionGroup>"#,
+        );
+        let project = VcxProject::from_path(&path).unwrap();
+        let debug = ConfigurationPlatform::new("Debug", "x64");
+        let release = ConfigurationPlatform::new("Release", "x64");
+
+        // The raw accessor only sees the configuration's own group.
+        assert_eq!(
+            project
+                .settings_for(&debug)
+                .unwrap()
+                .compiler
+                .language_standard,
+            None
+        );
+
+        let settings = project.effective_settings_for(&debug);
+        assert_eq!(
+            settings.compiler.language_standard.as_deref(),
+            Some("stdcpp20")
+        );
+        assert_eq!(settings.compiler.optimization.as_deref(), Some("Disabled"));
+        assert_eq!(
+            settings.compiler.preprocessor_definitions,
+            vec!["WIN32", "_DEBUG"]
+        );
+
+        let settings = project.effective_settings_for(&release);
+        assert_eq!(
+            settings.compiler.language_standard.as_deref(),
+            Some("stdcpp20")
+        );
+        assert_eq!(settings.compiler.optimization, None);
+    }
+
+    #[test]
+    fn detects_executable_per_configuration() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("Tool.vcxproj");
+        fs::write(
+            &path,
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<Project xmlns="http://schemas.microsoft.com/developer/msbuild/2003">
+  <ItemGroup Label="ProjectConfigurations">
+    <ProjectConfiguration Include="Release|x64" />
+    <ProjectConfiguration Include="Debug|x86" />
+  </ItemGroup>
+  <PropertyGroup Label="Configuration">
+    <ConfigurationType>Utility</ConfigurationType>
+  </PropertyGroup>
+  <PropertyGroup Condition="'$(Configuration)|$(Platform)'=='Debug|x86'" Label="Configuration">
+    <ConfigurationType>Application</ConfigurationType>
+  </PropertyGroup>
+</Project>"#,
+        )
+        .unwrap();
+        let project = VcxProject::from_path(&path).unwrap();
+
+        assert!(!project.produces_executable);
+        assert!(project.executable_in_any_config);
+        assert!(project.produces_executable_in(&ConfigurationPlatform::new("Debug", "x86")));
+        assert!(!project.produces_executable_in(&ConfigurationPlatform::new("Release", "x64")));
+
+        let sln = write_sln(
+            dir.path(),
+            &[("Tool", "AAAAAAAA-0000-0000-0000-000000000001")],
+            "",
+        );
+        let solution = Solution::from_path(&sln).unwrap();
+        assert_eq!(solution.executable_projects().count(), 1);
+    }
+
+    #[test]
+    fn aggregates_library_dependencies_and_dirs() {
+        let dir = tempdir().unwrap();
+        let link = |debug_libs: &str, release_libs: &str, lib_dirs: &str| {
+            format!(
+                r#"  <ItemDefinitionGroup Condition="'$(Configuration)|$(Platform)'=='Debug|x64'">
+    <Link>
+      <AdditionalDependencies>{debug_libs};%(AdditionalDependencies)</AdditionalDependencies>
+      <AdditionalLibraryDirectories>{lib_dirs};%(AdditionalLibraryDirectories)</AdditionalLibraryDirectories>
+    </Link>
+  </ItemDefinitionGroup>
+  <ItemDefinitionGroup Condition="'$(Configuration)|$(Platform)'=='Release|x64'">
+    <Link>
+      <AdditionalDependencies>{release_libs};%(AdditionalDependencies)</AdditionalDependencies>
+      <AdditionalLibraryDirectories>{lib_dirs};%(AdditionalLibraryDirectories)</AdditionalLibraryDirectories>
+    </Link>
+  </ItemDefinitionGroup>"#
+            )
+        };
+        write_vcxproj(
+            dir.path(),
+            "App",
+            "AAAAAAAA-0000-0000-0000-000000000001",
+            &link(
+                "zlibd.lib;ws2_32.lib",
+                "zlib.lib;ws2_32.lib",
+                "third_party\\lib\\$(Platform)",
+            ),
+        );
+        write_vcxproj(
+            dir.path(),
+            "Tool",
+            "AAAAAAAA-0000-0000-0000-000000000002",
+            &link(
+                "ws2_32.lib",
+                "ws2_32.lib;crypt32.lib",
+                "third_party\\lib\\x64;$(VcpkgRoot)\\lib",
+            ),
+        );
+        let sln = write_sln(
+            dir.path(),
+            &[
+                ("App", "AAAAAAAA-0000-0000-0000-000000000001"),
+                ("Tool", "AAAAAAAA-0000-0000-0000-000000000002"),
+            ],
+            "",
+        );
+        let solution = Solution::from_path(&sln).unwrap();
+
+        let libs: Vec<_> = solution.all_library_dependencies().into_iter().collect();
+        assert_eq!(
+            libs,
+            vec!["crypt32.lib", "ws2_32.lib", "zlib.lib", "zlibd.lib"]
+        );
+
+        let dirs: Vec<_> = solution.all_library_dirs().into_iter().collect();
+        assert_eq!(
+            dirs,
+            vec![
+                normalize_path(dir.path()).join("third_party/lib/x64"),
+                PathBuf::from("$(VcpkgRoot)/lib"),
+            ]
+        );
+    }
 }
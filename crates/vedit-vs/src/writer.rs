@@ -0,0 +1,310 @@
+//! Serialization of [`Solution`] back to `.sln` text.
+
+use crate::{Result, Solution, VisualStudioError, project_types};
+use std::fs;
+use std::path::Path;
+
+const LINE_ENDING: &str = "\r\n";
+
+impl Solution {
+    /// Render the solution as `.sln` text.
+    ///
+    /// Projects, solution folders, configurations, project configuration
+    /// mappings, nesting and source control bindings are regenerated from the
+    /// in-memory structures; anything the parser does not model is dropped.
+    /// GUIDs are written uppercase in braces and paths with backslashes, as
+    /// Visual Studio does.
+    pub fn to_sln_string(&self) -> String {
+        let mut out = SlnWriter::default();
+
+        out.line(0, "");
+        out.line(
+            0,
+            "Microsoft Visual Studio Solution File, Format Version 12.00",
+        );
+        if let Some(version) = &self.vs_version {
+            if let Some(major) = version.split('.').next().filter(|m| !m.is_empty()) {
+                out.line(0, &format!("# Visual Studio Version {major}"));
+            }
+            out.line(0, &format!("VisualStudioVersion = {version}"));
+        }
+        if let Some(version) = &self.minimum_vs_version {
+            out.line(0, &format!("MinimumVisualStudioVersion = {version}"));
+        }
+
+        for project in &self.projects {
+            let path = project.relative_path.to_string_lossy().replace('/', "\\");
+            out.project(
+                project
+                    .project_type_guid
+                    .as_deref()
+                    .unwrap_or(project_types::VCXPROJ),
+                &project.name,
+                &path,
+                project.project_guid.as_deref().unwrap_or(""),
+                &project.dependencies,
+            );
+        }
+        for folder in &self.folders {
+            out.project(
+                project_types::SOLUTION_FOLDER,
+                &folder.name,
+                &folder.name,
+                &folder.guid,
+                &[],
+            );
+        }
+
+        out.line(0, "Global");
+
+        if !self.configurations.is_empty() {
+            out.line(
+                1,
+                "GlobalSection(SolutionConfigurationPlatforms) = preSolution",
+            );
+            for config in &self.configurations {
+                out.line(2, &format!("{config} = {config}"));
+            }
+            out.line(1, "EndGlobalSection");
+        }
+
+        // Mapping keys are uppercase GUIDs; emit them in project order.
+        let mut keys: Vec<&String> = Vec::new();
+        let project_keys = self.projects.iter().filter_map(|project| {
+            let guid = project.project_guid.as_deref()?;
+            self.project_configurations
+                .keys()
+                .find(|key| key.eq_ignore_ascii_case(guid))
+        });
+        for key in project_keys {
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+        let mut orphans: Vec<&String> = self
+            .project_configurations
+            .keys()
+            .filter(|key| !keys.contains(key))
+            .collect();
+        orphans.sort();
+        keys.extend(orphans);
+
+        if !keys.is_empty() {
+            out.line(
+                1,
+                "GlobalSection(ProjectConfigurationPlatforms) = postSolution",
+            );
+            for key in keys {
+                let guid = braced_guid(key);
+                for mapping in &self.project_configurations[key] {
+                    let solution = &mapping.solution_config;
+                    let project = &mapping.project_config;
+                    out.line(2, &format!("{guid}.{solution}.ActiveCfg = {project}"));
+                    if mapping.build {
+                        out.line(2, &format!("{guid}.{solution}.Build.0 = {project}"));
+                    }
+                    if mapping.deploy {
+                        out.line(2, &format!("{guid}.{solution}.Deploy.0 = {project}"));
+                    }
+                }
+            }
+            out.line(1, "EndGlobalSection");
+        }
+
+        out.line(1, "GlobalSection(SolutionProperties) = preSolution");
+        out.line(2, "HideSolutionNode = FALSE");
+        out.line(1, "EndGlobalSection");
+
+        if self
+            .folders
+            .iter()
+            .any(|folder| !folder.children.is_empty())
+        {
+            out.line(1, "GlobalSection(NestedProjects) = preSolution");
+            for folder in &self.folders {
+                let parent = braced_guid(&folder.guid);
+                for child in &folder.children {
+                    out.line(2, &format!("{} = {parent}", braced_guid(child)));
+                }
+            }
+            out.line(1, "EndGlobalSection");
+        }
+
+        if let Some(bindings) = &self.source_control {
+            let mut entries: Vec<_> = bindings.iter().collect();
+            entries.sort();
+            out.line(1, "GlobalSection(SourceCodeControl) = preSolution");
+            for (key, value) in entries {
+                out.line(2, &format!("{key} = {value}"));
+            }
+            out.line(1, "EndGlobalSection");
+        }
+
+        out.line(0, "EndGlobal");
+        out.text
+    }
+
+    /// Write the solution to `path` as `.sln` text.
+    pub fn write_to_path(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        fs::write(path, self.to_sln_string()).map_err(|source| VisualStudioError::Io {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+}
+
+#[derive(Default)]
+struct SlnWriter {
+    text: String,
+}
+
+impl SlnWriter {
+    fn line(&mut self, indent: usize, line: &str) {
+        for _ in 0..indent {
+            self.text.push('\t');
+        }
+        self.text.push_str(line);
+        self.text.push_str(LINE_ENDING);
+    }
+
+    fn project(
+        &mut self,
+        type_guid: &str,
+        name: &str,
+        path: &str,
+        guid: &str,
+        dependencies: &[String],
+    ) {
+        let guid = if guid.is_empty() {
+            String::new()
+        } else {
+            braced_guid(guid)
+        };
+        let type_guid = braced_guid(type_guid);
+        self.line(
+            0,
+            &format!("Project(\"{type_guid}\") = \"{name}\", \"{path}\", \"{guid}\""),
+        );
+        if !dependencies.is_empty() {
+            self.line(1, "ProjectSection(ProjectDependencies) = postProject");
+            for dependency in dependencies {
+                let dependency = braced_guid(dependency);
+                self.line(2, &format!("{dependency} = {dependency}"));
+            }
+            self.line(1, "EndProjectSection");
+        }
+        self.line(0, "EndProject");
+    }
+}
+
+fn braced_guid(guid: &str) -> String {
+    let inner = guid.trim().trim_start_matches('{').trim_end_matches('}');
+    format!("{{{}}}", inner.to_uppercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ProjectConfigurationMapping;
+    use tempfile::tempdir;
+
+    const SOLUTION: &str = r#"
+Microsoft Visual Studio Solution File, Format Version 12.00
+# Visual Studio Version 17
+VisualStudioVersion = 17.5.33424.131
+MinimumVisualStudioVersion = 10.0.40219.1
+Project("{8BC9CEB8-8B4A-11D0-8D11-00A0C91BC942}") = "App", "src\App\App.vcxproj", "{11111111-1111-1111-1111-111111111111}"
+	ProjectSection(ProjectDependencies) = postProject
+		{33333333-3333-3333-3333-333333333333} = {33333333-3333-3333-3333-333333333333}
+	EndProjectSection
+EndProject
+Project("{2150E333-8FDC-42A3-9474-1A3956D46DE8}") = "Apps", "Apps", "{22222222-2222-2222-2222-222222222222}"
+EndProject
+Project("{8BC9CEB8-8B4A-11D0-8D11-00A0C91BC942}") = "Lib", "Lib.vcxproj", "{33333333-3333-3333-3333-333333333333}"
+EndProject
+Global
+	GlobalSection(SolutionConfigurationPlatforms) = preSolution
+		Debug|x64 = Debug|x64
+		Release|x64 = Release|x64
+	EndGlobalSection
+	GlobalSection(ProjectConfigurationPlatforms) = postSolution
+		{11111111-1111-1111-1111-111111111111}.Debug|x64.ActiveCfg = Debug|x64
+		{11111111-1111-1111-1111-111111111111}.Debug|x64.Build.0 = Debug|x64
+		{11111111-1111-1111-1111-111111111111}.Release|x64.ActiveCfg = Release|x64
+		{11111111-1111-1111-1111-111111111111}.Release|x64.Build.0 = Release|x64
+		{11111111-1111-1111-1111-111111111111}.Release|x64.Deploy.0 = Release|x64
+		{33333333-3333-3333-3333-333333333333}.Debug|x64.ActiveCfg = Debug|Win32
+		{33333333-3333-3333-3333-333333333333}.Release|x64.ActiveCfg = Release|Win32
+		{33333333-3333-3333-3333-333333333333}.Release|x64.Build.0 = Release|Win32
+	EndGlobalSection
+	GlobalSection(NestedProjects) = preSolution
+		{11111111-1111-1111-1111-111111111111} = {22222222-2222-2222-2222-222222222222}
+	EndGlobalSection
+EndGlobal
+"#;
+
+    fn sorted_mappings(solution: &Solution) -> Vec<(String, Vec<ProjectConfigurationMapping>)> {
+        let mut mappings: Vec<_> = solution
+            .project_configurations
+            .iter()
+            .map(|(guid, mappings)| (guid.clone(), mappings.clone()))
+            .collect();
+        mappings.sort_by(|a, b| a.0.cmp(&b.0));
+        mappings
+    }
+
+    #[test]
+    fn round_trips_parsed_solution() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("test.sln");
+        let original = Solution::parse(SOLUTION, &path).unwrap();
+
+        original.write_to_path(&path).unwrap();
+        let written = fs::read_to_string(&path).unwrap();
+        assert!(written.contains("\"src\\App\\App.vcxproj\""));
+        let reparsed = Solution::from_path(&path).unwrap();
+
+        let summary = |solution: &Solution| {
+            solution
+                .projects
+                .iter()
+                .map(|p| {
+                    (
+                        p.name.clone(),
+                        p.relative_path.clone(),
+                        p.project_type_guid.clone(),
+                        p.project_guid.clone(),
+                        p.dependencies.clone(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(summary(&reparsed), summary(&original));
+        assert_eq!(reparsed.projects[0].dependencies.len(), 1);
+        assert_eq!(reparsed.configurations, original.configurations);
+        assert_eq!(sorted_mappings(&reparsed), sorted_mappings(&original));
+        assert_eq!(reparsed.folders, original.folders);
+        assert_eq!(reparsed.vs_version, original.vs_version);
+        assert_eq!(reparsed.minimum_vs_version, original.minimum_vs_version);
+
+        // Serializing again is stable.
+        assert_eq!(reparsed.to_sln_string(), written);
+    }
+
+    #[test]
+    fn uppercases_and_braces_guids() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("lower.sln");
+        let solution = Solution::parse(
+            "Project(\"{8bc9ceb8-8b4a-11d0-8d11-00a0c91bc942}\") = \"App\", \"App.vcxproj\", \"{abcdef00-0000-0000-0000-000000000001}\"\nEndProject\n",
+            &path,
+        )
+        .unwrap();
+
+        let text = solution.to_sln_string();
+        assert!(text.contains(
+            "Project(\"{8BC9CEB8-8B4A-11D0-8D11-00A0C91BC942}\") = \"App\", \"App.vcxproj\", \"{ABCDEF00-0000-0000-0000-000000000001}\""
+        ));
+    }
+}
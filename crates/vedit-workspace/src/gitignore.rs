@@ -0,0 +1,167 @@
+//! Matching of root-level `.gitignore` patterns.
+
+use std::fs;
+use std::path::Path;
+
+/// Patterns from a `.gitignore` file.
+///
+/// Supports `*`, `?`, `**` and `[...]` globs, `!` negation, `/`-anchored
+/// patterns and directory-only patterns ending in `/`. Later patterns
+/// override earlier ones, as in git.
+#[derive(Debug, Clone, Default)]
+pub struct GitIgnore {
+    rules: Vec<Rule>,
+}
+
+#[derive(Debug, Clone)]
+struct Rule {
+    pattern: String,
+    negated: bool,
+    dir_only: bool,
+    /// Matched against the whole relative path instead of just the name.
+    anchored: bool,
+}
+
+impl GitIgnore {
+    /// The patterns of `root/.gitignore`; empty when there is none.
+    pub fn from_root(root: &Path) -> Self {
+        fs::read_to_string(root.join(".gitignore"))
+            .map(|contents| Self::parse(&contents))
+            .unwrap_or_default()
+    }
+
+    pub fn parse(contents: &str) -> Self {
+        let mut ignore = Self::default();
+        for line in contents.lines() {
+            ignore.add(line);
+        }
+        ignore
+    }
+
+    /// Adds one pattern line. Blank lines and `#` comments are skipped.
+    pub fn add(&mut self, line: &str) {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return;
+        }
+        let (negated, line) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line.strip_prefix('\\').unwrap_or(line)),
+        };
+        let (dir_only, line) = match line.strip_suffix('/') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let anchored = line.contains('/');
+        let pattern = line.trim_start_matches('/');
+        if pattern.is_empty() {
+            return;
+        }
+        self.rules.push(Rule {
+            pattern: pattern.to_string(),
+            negated,
+            dir_only,
+            anchored,
+        });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Whether the entry at `rel_path` (`/`-separated, relative to the root)
+    /// is ignored.
+    pub fn is_ignored(&self, rel_path: &str, is_dir: bool) -> bool {
+        let rel_path = rel_path.trim_matches('/');
+        let name = rel_path.rsplit('/').next().unwrap_or(rel_path);
+        let mut ignored = false;
+        for rule in &self.rules {
+            if rule.dir_only && !is_dir {
+                continue;
+            }
+            let subject = if rule.anchored { rel_path } else { name };
+            if glob_matches(rule.pattern.as_bytes(), subject.as_bytes()) {
+                ignored = !rule.negated;
+            }
+        }
+        ignored
+    }
+}
+
+/// Whether `text` matches the glob `pattern`. `*` and `?` stop at `/`;
+/// `**` crosses it, and `**/` skips whole path segments only.
+fn glob_matches(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern {
+        [] => text.is_empty(),
+        [b'*', b'*', b'/', rest @ ..] => (0..=text.len())
+            .filter(|&skip| skip == 0 || text[skip - 1] == b'/')
+            .any(|skip| glob_matches(rest, &text[skip..])),
+        [b'*', b'*', rest @ ..] => (0..=text.len()).any(|skip| glob_matches(rest, &text[skip..])),
+        [b'*', rest @ ..] => {
+            let segment = text.iter().position(|&b| b == b'/').unwrap_or(text.len());
+            (0..=segment).any(|skip| glob_matches(rest, &text[skip..]))
+        }
+        [b'?', rest @ ..] => {
+            matches!(text, [first, tail @ ..] if *first != b'/' && glob_matches(rest, tail))
+        }
+        [b'[', rest @ ..] => match (rest.iter().position(|&b| b == b']'), text) {
+            (Some(close), [first, tail @ ..]) if close > 0 => {
+                let class = &rest[..close];
+                let (negated, class) = match class {
+                    [b'!' | b'^', class @ ..] => (true, class),
+                    _ => (false, class),
+                };
+                let mut matched = false;
+                let mut index = 0;
+                while index < class.len() {
+                    if index + 2 < class.len() && class[index + 1] == b'-' {
+                        matched |= (class[index]..=class[index + 2]).contains(first);
+                        index += 3;
+                    } else {
+                        matched |= class[index] == *first;
+                        index += 1;
+                    }
+                }
+                matched != negated && *first != b'/' && glob_matches(&rest[close + 1..], tail)
+            }
+            _ => matches!(text, [b'[', tail @ ..] if glob_matches(rest, tail)),
+        },
+        [b'\\', literal, rest @ ..] | [literal, rest @ ..] => {
+            matches!(text, [first, tail @ ..] if first == literal && glob_matches(rest, tail))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_gitignore_patterns() {
+        let ignore = GitIgnore::parse(
+            "# build output\n*.log\n!keep.log\nbuild/\n/dist\ndocs/**/*.tmp\ncache[0-9]\n\
+             docs/**/bar\n**/foo\n",
+        );
+        assert!(ignore.is_ignored("debug.log", false));
+        assert!(ignore.is_ignored("src/nested/trace.log", false));
+        assert!(!ignore.is_ignored("logs/keep.log", false));
+        assert!(ignore.is_ignored("build", true));
+        assert!(ignore.is_ignored("src/build", true));
+        assert!(!ignore.is_ignored("build", false));
+        assert!(ignore.is_ignored("dist", true));
+        assert!(!ignore.is_ignored("src/dist", true));
+        assert!(ignore.is_ignored("docs/a/b/x.tmp", false));
+        assert!(ignore.is_ignored("docs/x.tmp", false));
+        assert!(!ignore.is_ignored("x.tmp", false));
+        assert!(ignore.is_ignored("cache7", true));
+        assert!(!ignore.is_ignored("cachex", true));
+        assert!(!ignore.is_ignored("main.rs", false));
+        assert!(ignore.is_ignored("docs/bar", false));
+        assert!(ignore.is_ignored("docs/a/bar", false));
+        assert!(!ignore.is_ignored("docs/foobar", false));
+        assert!(ignore.is_ignored("foo", false));
+        assert!(ignore.is_ignored("src/foo", false));
+        assert!(!ignore.is_ignored("xfoo", false));
+        assert!(!ignore.is_ignored("src/xfoo", false));
+    }
+}
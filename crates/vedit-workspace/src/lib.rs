@@ -1,10 +1,15 @@
 use slab::Slab;
-use std::collections::{BTreeSet, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
+use std::process::Command;
 use std::time::SystemTime;
 
+mod gitignore;
+
+pub use gitignore::GitIgnore;
+
 pub type NodeId = usize;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -27,6 +32,17 @@ pub struct Node {
     pub is_hidden: bool,
 }
 
+impl NodeKind {
+    /// Whether this is a folder or a symlink to one.
+    pub fn is_folder(&self) -> bool {
+        match self {
+            NodeKind::Folder => true,
+            NodeKind::File => false,
+            NodeKind::Symlink(target) => target.is_folder(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum GitStatus {
     Added,
@@ -46,6 +62,26 @@ pub struct FilterState {
     pub show_hidden: bool,
 }
 
+impl FilterState {
+    /// Whether `node` itself passes the query and kind filters. Hidden
+    /// nodes are handled by the tree, since they also hide their children.
+    pub fn matches(&self, node: &Node) -> bool {
+        let is_folder = node.kind.is_folder();
+        if (self.files_only && is_folder) || (self.folders_only && !is_folder) {
+            return false;
+        }
+        if self.query.is_empty() {
+            true
+        } else if self.match_case {
+            node.name.contains(&self.query)
+        } else {
+            node.name
+                .to_lowercase()
+                .contains(&self.query.to_lowercase())
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct WorkspaceTree {
     pub root: NodeId,
@@ -61,6 +97,12 @@ pub trait WorkspaceProvider {
     fn read_meta(&self, rel: &str) -> io::Result<FileMeta>;
     fn is_dir(&self, rel: &str) -> bool;
     fn rename(&mut self, from: &str, to: &str) -> io::Result<()>;
+    /// Moves `from` into the folder `to_dir`, keeping its name. Fails with
+    /// `AlreadyExists` instead of overwriting an entry of that name.
+    fn move_path(&mut self, from: &str, to_dir: &str) -> io::Result<()>;
+    /// Copies the file or folder (recursively) at `from` to `to`. Fails with
+    /// `AlreadyExists` when `to` exists.
+    fn copy(&mut self, from: &str, to: &str) -> io::Result<()>;
     fn create_file(&mut self, rel: &str) -> io::Result<()>;
     fn create_dir(&mut self, rel: &str) -> io::Result<()>;
     fn remove(&mut self, rel: &str) -> io::Result<()>;
@@ -68,11 +110,26 @@ pub trait WorkspaceProvider {
 
 pub struct FsWorkspaceProvider {
     root: PathBuf,
+    ignore: GitIgnore,
 }
 
 impl FsWorkspaceProvider {
     pub fn new(root: PathBuf) -> Self {
-        Self { root }
+        Self {
+            root,
+            ignore: GitIgnore::default(),
+        }
+    }
+
+    /// A provider whose `read_dir` skips the entries ignored by the root
+    /// `.gitignore`, plus any folder named in `extra_ignored`. Without a
+    /// `.gitignore` only `extra_ignored` applies.
+    pub fn with_gitignore(root: PathBuf, extra_ignored: &[&str]) -> Self {
+        let mut ignore = GitIgnore::from_root(&root);
+        for name in extra_ignored {
+            ignore.add(&format!("{name}/"));
+        }
+        Self { root, ignore }
     }
 }
 
@@ -113,6 +170,207 @@ impl FsWorkspaceProvider {
     }
 }
 
+impl WorkspaceTree {
+    /// Sets the `git` status of every loaded node from `statuses`, keyed by
+    /// `/`-separated path relative to the workspace root. Nodes without an
+    /// entry are reset to `None`.
+    pub fn apply_git_status(&mut self, statuses: &HashMap<String, GitStatus>) {
+        for (_, node) in self.nodes.iter_mut() {
+            let key = node.rel_path.replace('\\', "/");
+            node.git = statuses.get(&key).cloned();
+        }
+    }
+
+    /// The loaded node at `rel` (`/`-separated, relative to the root; empty
+    /// for the root itself).
+    pub fn node_by_rel_path(&self, rel: &str) -> Option<NodeId> {
+        let mut id = self.root;
+        for name in path_components(rel) {
+            id = self.child_named(id, name)?;
+        }
+        Some(id)
+    }
+
+    /// Like [`WorkspaceTree::node_by_rel_path`], but loads and expands each
+    /// ancestor folder of `rel` on the way, e.g. to reveal a file opened
+    /// elsewhere. Returns `None` when the path does not exist.
+    pub fn ensure_loaded(
+        &mut self,
+        provider: &FsWorkspaceProvider,
+        rel: &str,
+    ) -> io::Result<Option<NodeId>> {
+        let mut id = self.root;
+        for name in path_components(rel) {
+            if !self.nodes.get(id).is_some_and(|node| node.kind.is_folder()) {
+                return Ok(None);
+            }
+            provider.load_children(self, id)?;
+            self.expanded.insert(id);
+            match self.child_named(id, name) {
+                Some(child) => id = child,
+                None => return Ok(None),
+            }
+        }
+        Ok(Some(id))
+    }
+
+    fn child_named(&self, id: NodeId, name: &str) -> Option<NodeId> {
+        self.children_of(id)
+            .iter()
+            .copied()
+            .find(|&child| self.nodes.get(child).is_some_and(|node| node.name == name))
+    }
+
+    /// Collapses every folder, keeping only the root's expansion.
+    pub fn collapse_all(&mut self) {
+        let root = self.root;
+        self.expanded.retain(|&id| id == root);
+    }
+
+    /// Expands every folder less than `depth` levels below the root (the
+    /// root itself is level 0), loading their children as needed, and
+    /// collapses deeper ones. A `depth` of 0 is the same as
+    /// [`WorkspaceTree::collapse_all`].
+    pub fn expand_to_depth(
+        &mut self,
+        provider: &FsWorkspaceProvider,
+        depth: usize,
+    ) -> io::Result<()> {
+        self.collapse_all();
+        let mut level = vec![self.root];
+        for _ in 0..depth {
+            let mut next = Vec::new();
+            for id in level {
+                if !self.nodes.get(id).is_some_and(|node| node.kind.is_folder()) {
+                    continue;
+                }
+                provider.load_children(self, id)?;
+                self.expanded.insert(id);
+                next.extend(self.nodes[id].children.iter().flatten().copied());
+            }
+            level = next;
+        }
+        Ok(())
+    }
+
+    /// The nodes to display, in order: the descendants of the root reachable
+    /// through expanded folders that pass `filter`. Folders stay visible
+    /// while any loaded descendant matches, even when collapsed or when the
+    /// folder itself does not match.
+    pub fn visible_nodes(&self) -> Vec<NodeId> {
+        let mut visible = Vec::new();
+        if self.expanded.contains(&self.root) {
+            for &child in self.children_of(self.root) {
+                self.push_visible(child, &mut visible);
+            }
+        }
+        visible
+    }
+
+    /// Pushes `id` and its visible descendants, returning whether anything
+    /// in the subtree matched.
+    fn push_visible(&self, id: NodeId, visible: &mut Vec<NodeId>) -> bool {
+        let Some(node) = self.nodes.get(id) else {
+            return false;
+        };
+        if node.is_hidden && !self.filter.show_hidden {
+            return false;
+        }
+        let mark = visible.len();
+        visible.push(id);
+        let expanded = self.expanded.contains(&id);
+        let mut descendant_matches = false;
+        for &child in self.children_of(id) {
+            descendant_matches |= if expanded {
+                self.push_visible(child, visible)
+            } else {
+                self.subtree_matches(child)
+            };
+        }
+        if descendant_matches || self.filter.matches(node) {
+            true
+        } else {
+            visible.truncate(mark);
+            false
+        }
+    }
+
+    fn subtree_matches(&self, id: NodeId) -> bool {
+        let Some(node) = self.nodes.get(id) else {
+            return false;
+        };
+        if node.is_hidden && !self.filter.show_hidden {
+            return false;
+        }
+        self.filter.matches(node)
+            || self
+                .children_of(id)
+                .iter()
+                .any(|&child| self.subtree_matches(child))
+    }
+
+    fn children_of(&self, id: NodeId) -> &[NodeId] {
+        self.nodes
+            .get(id)
+            .and_then(|node| node.children.as_deref())
+            .unwrap_or_default()
+    }
+
+    /// Renames node `id` to `new_name` within its folder through `provider`,
+    /// then rewrites the `rel_path` of the node and of every loaded
+    /// descendant. Node ids, and with them selection and expansion, are kept.
+    pub fn rename_node(
+        &mut self,
+        provider: &mut dyn WorkspaceProvider,
+        id: NodeId,
+        new_name: &str,
+    ) -> io::Result<()> {
+        if new_name.is_empty() || new_name.contains(['/', '\\']) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid file name: {new_name:?}"),
+            ));
+        }
+        let node = self
+            .nodes
+            .get(id)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "unknown node"))?;
+        if id == self.root {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "cannot rename the workspace root",
+            ));
+        }
+        let old_path = node.rel_path.clone();
+        let new_path = Path::new(&old_path)
+            .with_file_name(new_name)
+            .to_string_lossy()
+            .to_string();
+        provider.rename(&old_path, &new_path)?;
+
+        let node = &mut self.nodes[id];
+        node.name = new_name.to_string();
+        node.rel_path = new_path.clone();
+        node.is_hidden = new_name.starts_with('.');
+
+        let mut pending: Vec<NodeId> = node.children.iter().flatten().copied().collect();
+        while let Some(child) = pending.pop() {
+            let Some(node) = self.nodes.get_mut(child) else {
+                continue;
+            };
+            if let Some(rest) = node
+                .rel_path
+                .strip_prefix(&old_path)
+                .filter(|rest| rest.starts_with(['/', '\\']))
+            {
+                node.rel_path = format!("{new_path}{rest}");
+            }
+            pending.extend(node.children.iter().flatten().copied());
+        }
+        Ok(())
+    }
+}
+
 impl WorkspaceProvider for FsWorkspaceProvider {
     fn read_dir(&self, rel: &str) -> io::Result<Vec<DirEntryMeta>> {
         let path = self.root.join(rel);
@@ -136,6 +394,12 @@ impl WorkspaceProvider for FsWorkspaceProvider {
                 None
             };
             let modified = metadata.modified().ok();
+            if self
+                .ignore
+                .is_ignored(&rel_path.replace('\\', "/"), metadata.is_dir())
+            {
+                continue;
+            }
             let is_hidden = name.starts_with('.');
             entries.push(DirEntryMeta {
                 name,
@@ -188,6 +452,50 @@ impl WorkspaceProvider for FsWorkspaceProvider {
         fs::rename(from_path, to_path)
     }
 
+    fn move_path(&mut self, from: &str, to_dir: &str) -> io::Result<()> {
+        let from_path = self.root.join(from);
+        let to_dir = self.root.join(to_dir);
+        let name = from_path.file_name().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, format!("cannot move {from:?}"))
+        })?;
+        if to_dir.starts_with(&from_path) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("cannot move {from:?} into itself"),
+            ));
+        }
+        let to_path = to_dir.join(name);
+        if to_path.symlink_metadata().is_ok() {
+            return Err(already_exists(&to_path));
+        }
+        match fs::rename(&from_path, &to_path) {
+            Err(err) if err.kind() == io::ErrorKind::CrossesDevices => {
+                copy_recursive(&from_path, &to_path)?;
+                if from_path.is_dir() {
+                    fs::remove_dir_all(from_path)
+                } else {
+                    fs::remove_file(from_path)
+                }
+            }
+            result => result,
+        }
+    }
+
+    fn copy(&mut self, from: &str, to: &str) -> io::Result<()> {
+        let from_path = self.root.join(from);
+        let to_path = self.root.join(to);
+        if to_path.symlink_metadata().is_ok() {
+            return Err(already_exists(&to_path));
+        }
+        if to_path.starts_with(&from_path) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("cannot copy {from:?} into itself"),
+            ));
+        }
+        copy_recursive(&from_path, &to_path)
+    }
+
     fn create_file(&mut self, rel: &str) -> io::Result<()> {
         let path = self.root.join(rel);
         fs::File::create(path)?;
@@ -209,6 +517,112 @@ impl WorkspaceProvider for FsWorkspaceProvider {
     }
 }
 
+fn path_components(rel: &str) -> impl Iterator<Item = &str> {
+    rel.split(['/', '\\'])
+        .filter(|name| !name.is_empty() && *name != ".")
+}
+
+fn already_exists(path: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::AlreadyExists,
+        format!("{} already exists", path.display()),
+    )
+}
+
+fn copy_recursive(from: &Path, to: &Path) -> io::Result<()> {
+    if fs::metadata(from)?.is_dir() {
+        fs::create_dir(to)?;
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            copy_recursive(&entry.path(), &to.join(entry.file_name()))?;
+        }
+        Ok(())
+    } else {
+        fs::copy(from, to).map(|_| ())
+    }
+}
+
+/// Reads the Git status of the files below a workspace root.
+#[derive(Debug, Clone)]
+pub struct GitStatusProvider {
+    root: PathBuf,
+    statuses: Option<HashMap<String, GitStatus>>,
+}
+
+impl GitStatusProvider {
+    pub fn new(root: PathBuf) -> Self {
+        Self {
+            root,
+            statuses: None,
+        }
+    }
+
+    /// Changed files keyed by path relative to the workspace root, from one
+    /// `git status` run that is cached until [`GitStatusProvider::refresh`].
+    /// Empty when git is not installed or the root is not in a repository.
+    pub fn statuses(&mut self) -> &HashMap<String, GitStatus> {
+        self.statuses
+            .get_or_insert_with(|| git_status(&self.root).unwrap_or_default())
+    }
+
+    /// Drops the cached statuses so the next call runs git again.
+    pub fn refresh(&mut self) {
+        self.statuses = None;
+    }
+}
+
+fn git_status(root: &Path) -> Option<HashMap<String, GitStatus>> {
+    let git = |args: &[&str]| {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(root)
+            .args(args)
+            .output()
+            .ok()?;
+        output.status.success().then_some(output.stdout)
+    };
+    // Porcelain paths are relative to the repository, not the workspace.
+    let prefix = String::from_utf8(git(&["rev-parse", "--show-prefix"])?).ok()?;
+    let output = git(&["status", "--porcelain=v1", "-z", "--", "."])?;
+    Some(parse_porcelain_status(
+        &output,
+        prefix.trim_end_matches('\n'),
+    ))
+}
+
+/// Parses `git status --porcelain=v1 -z` output, keeping the entries below
+/// `prefix` (a repository-relative folder ending in `/`, or empty) with the
+/// prefix stripped.
+fn parse_porcelain_status(output: &[u8], prefix: &str) -> HashMap<String, GitStatus> {
+    let mut statuses = HashMap::new();
+    let mut fields = output.split(|&byte| byte == 0);
+    while let Some(entry) = fields.next() {
+        let Some((&[x, y], path)) = entry
+            .split_first_chunk::<2>()
+            .and_then(|(code, rest)| Some((code, rest.strip_prefix(b" ")?)))
+        else {
+            continue;
+        };
+        // Renames and copies are followed by their source path.
+        if matches!(x, b'R' | b'C') {
+            fields.next();
+        }
+        let status = match (x, y) {
+            (b'D', b'D') | (b'A', b'A') | (b'U', _) | (_, b'U') => GitStatus::Unmerged,
+            (b'?', b'?') => GitStatus::Untracked,
+            (b'!', b'!') => GitStatus::Ignored,
+            (b'D', _) | (_, b'D') => GitStatus::Deleted,
+            (b'A' | b'R' | b'C', _) => GitStatus::Added,
+            _ => GitStatus::Modified,
+        };
+        let path = String::from_utf8_lossy(path);
+        if let Some(rel) = path.strip_prefix(prefix) {
+            statuses.insert(rel.trim_end_matches('/').to_string(), status);
+        }
+    }
+    statuses
+}
+
 #[derive(Debug, Clone)]
 pub struct DirEntryMeta {
     pub name: String,
@@ -364,6 +778,391 @@ mod tests {
         assert!(tree.selection.is_empty());
     }
 
+    #[test]
+    fn collapse_all_and_expand_to_depth() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("src/nested/deep")).unwrap();
+        fs::create_dir_all(dir.path().join("docs")).unwrap();
+        fs::write(dir.path().join("src/main.rs"), "").unwrap();
+        fs::write(dir.path().join("README.md"), "").unwrap();
+        let (provider, mut tree) = tree_for(dir.path());
+        let expanded_paths = |tree: &WorkspaceTree| {
+            let mut paths: Vec<_> = tree
+                .expanded
+                .iter()
+                .map(|&id| tree.nodes[id].rel_path.clone())
+                .collect();
+            paths.sort();
+            paths
+        };
+        let find = |tree: &WorkspaceTree, path: &str| {
+            tree.nodes
+                .iter()
+                .find(|(_, node)| node.rel_path == path)
+                .map(|(id, _)| id)
+        };
+
+        tree.expand_to_depth(&provider, 2).unwrap();
+        assert_eq!(expanded_paths(&tree), vec!["", "docs", "src"]);
+        let nested = find(&tree, "src/nested").unwrap();
+        assert!(tree.nodes[nested].children.is_none());
+        assert!(find(&tree, "src/main.rs").is_some());
+
+        tree.expand_to_depth(&provider, 3).unwrap();
+        assert_eq!(expanded_paths(&tree), vec!["", "docs", "src", "src/nested"]);
+        assert!(find(&tree, "src/nested/deep").is_some());
+
+        tree.collapse_all();
+        assert_eq!(expanded_paths(&tree), vec![""]);
+        // Loaded children are kept for the next expansion.
+        assert!(tree.nodes[nested].children.is_some());
+
+        tree.expand_to_depth(&provider, 0).unwrap();
+        assert_eq!(expanded_paths(&tree), vec![""]);
+    }
+
+    #[test]
+    fn rename_node_updates_descendants() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("src/nested")).unwrap();
+        fs::write(dir.path().join("src/main.rs"), "").unwrap();
+        fs::write(dir.path().join("src/nested/mod.rs"), "").unwrap();
+        fs::write(dir.path().join("srcfile.txt"), "").unwrap();
+        let (mut provider, mut tree) = tree_for(dir.path());
+        let root = tree.root;
+        tree.expand_to_depth(&provider, 3).unwrap();
+        let find = |tree: &WorkspaceTree, path: &str| {
+            tree.nodes
+                .iter()
+                .find(|(_, node)| node.rel_path == path)
+                .map(|(id, _)| id)
+        };
+        let src = find(&tree, "src").unwrap();
+        let nested_file = find(&tree, "src/nested/mod.rs").unwrap();
+        tree.selection.insert(nested_file);
+
+        tree.rename_node(&mut provider, src, "lib").unwrap();
+        assert!(dir.path().join("lib/nested/mod.rs").is_file());
+        assert_eq!(tree.nodes[src].name, "lib");
+        assert_eq!(tree.nodes[src].rel_path, "lib");
+        assert_eq!(tree.nodes[nested_file].rel_path, "lib/nested/mod.rs");
+        assert!(find(&tree, "lib/main.rs").is_some());
+        assert!(find(&tree, "lib/nested").is_some());
+        assert!(find(&tree, "srcfile.txt").is_some());
+        assert!(tree.selection.contains(&nested_file));
+        assert!(tree.expanded.contains(&src));
+
+        let err = tree.rename_node(&mut provider, src, "a/b").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert!(tree.rename_node(&mut provider, root, "other").is_err());
+    }
+
+    /// A tree with no nodes and default filter settings.
+    fn empty_tree() -> WorkspaceTree {
+        WorkspaceTree {
+            root: 0,
+            nodes: Slab::new(),
+            expanded: HashSet::new(),
+            selection: BTreeSet::new(),
+            cursor: None,
+            filter: FilterState {
+                query: String::new(),
+                match_case: false,
+                files_only: false,
+                folders_only: false,
+                show_hidden: false,
+            },
+        }
+    }
+
+    /// A provider for `dir` and a tree holding only its root folder, with
+    /// nothing loaded or expanded yet.
+    fn tree_for(dir: &Path) -> (FsWorkspaceProvider, WorkspaceTree) {
+        let mut tree = empty_tree();
+        tree.root = tree.nodes.insert(Node {
+            id: 0,
+            name: "root".to_string(),
+            rel_path: "".to_string(),
+            kind: NodeKind::Folder,
+            size: None,
+            modified: None,
+            children: None,
+            git: None,
+            is_hidden: false,
+        });
+        (FsWorkspaceProvider::new(dir.to_path_buf()), tree)
+    }
+
+    /// root/{src/{Main.rs, util/helpers.rs}, docs/guide.md, .git/config, main.txt},
+    /// with `root`, `src` and `.git` expanded and `util` collapsed.
+    fn sample_tree() -> WorkspaceTree {
+        let mut tree = empty_tree();
+        let mut add = |parent: Option<NodeId>, rel_path: &str, kind: NodeKind| {
+            let name = rel_path.rsplit('/').next().unwrap().to_string();
+            let id = tree.nodes.insert(Node {
+                id: 0,
+                is_hidden: name.starts_with('.'),
+                name,
+                rel_path: rel_path.to_string(),
+                children: kind.is_folder().then(Vec::new),
+                kind,
+                size: None,
+                modified: None,
+                git: None,
+            });
+            tree.nodes[id].id = id;
+            if let Some(parent) = parent {
+                tree.nodes[parent].children.as_mut().unwrap().push(id);
+            }
+            id
+        };
+        let root = add(None, "", NodeKind::Folder);
+        let src = add(Some(root), "src", NodeKind::Folder);
+        add(Some(src), "src/Main.rs", NodeKind::File);
+        let util = add(Some(src), "src/util", NodeKind::Folder);
+        add(Some(util), "src/util/helpers.rs", NodeKind::File);
+        let docs = add(Some(root), "docs", NodeKind::Folder);
+        add(Some(docs), "docs/guide.md", NodeKind::File);
+        let git = add(Some(root), ".git", NodeKind::Folder);
+        add(Some(git), ".git/config", NodeKind::File);
+        add(Some(root), "main.txt", NodeKind::File);
+        tree.root = root;
+        tree.expanded.extend([root, src, git]);
+
+        tree
+    }
+
+    fn visible_paths(tree: &WorkspaceTree) -> Vec<&str> {
+        tree.visible_nodes()
+            .into_iter()
+            .map(|id| tree.nodes[id].rel_path.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn visible_nodes_follow_expansion() {
+        let mut tree = sample_tree();
+        assert_eq!(
+            visible_paths(&tree),
+            vec!["src", "src/Main.rs", "src/util", "docs", "main.txt"]
+        );
+
+        tree.filter.show_hidden = true;
+        assert_eq!(
+            visible_paths(&tree),
+            vec![
+                "src",
+                "src/Main.rs",
+                "src/util",
+                "docs",
+                ".git",
+                ".git/config",
+                "main.txt"
+            ]
+        );
+
+        tree.collapse_all();
+        assert_eq!(
+            visible_paths(&tree),
+            vec!["src", "docs", ".git", "main.txt"]
+        );
+    }
+
+    #[test]
+    fn visible_nodes_apply_case_sensitive_query() {
+        let mut tree = sample_tree();
+        tree.filter.query = "main".to_string();
+        assert_eq!(visible_paths(&tree), vec!["src", "src/Main.rs", "main.txt"]);
+
+        tree.filter.match_case = true;
+        assert_eq!(visible_paths(&tree), vec!["main.txt"]);
+
+        // Matches inside collapsed folders keep the folder visible.
+        tree.filter.query = "helpers".to_string();
+        assert_eq!(visible_paths(&tree), vec!["src", "src/util"]);
+    }
+
+    #[test]
+    fn visible_nodes_apply_kind_filters() {
+        let mut tree = sample_tree();
+        tree.filter.files_only = true;
+        assert_eq!(
+            visible_paths(&tree),
+            vec!["src", "src/Main.rs", "src/util", "docs", "main.txt"]
+        );
+
+        tree.filter.query = "guide".to_string();
+        assert_eq!(visible_paths(&tree), vec!["docs"]);
+
+        tree.filter.query = "t".to_string();
+        tree.filter.files_only = false;
+        tree.filter.folders_only = true;
+        assert_eq!(visible_paths(&tree), vec!["src", "src/util"]);
+    }
+
+    #[test]
+    fn parses_porcelain_status() {
+        let output = b" M src/lib.rs\0A  src/new.rs\0?? notes/\0D  old.rs\0R  src/moved.rs\0src/orig.rs\0UU conflict.rs\0";
+        let statuses = parse_porcelain_status(output, "");
+        assert_eq!(statuses.get("src/lib.rs"), Some(&GitStatus::Modified));
+        assert_eq!(statuses.get("src/new.rs"), Some(&GitStatus::Added));
+        assert_eq!(statuses.get("notes"), Some(&GitStatus::Untracked));
+        assert_eq!(statuses.get("old.rs"), Some(&GitStatus::Deleted));
+        assert_eq!(statuses.get("src/moved.rs"), Some(&GitStatus::Added));
+        assert_eq!(statuses.get("conflict.rs"), Some(&GitStatus::Unmerged));
+        assert!(!statuses.contains_key("src/orig.rs"));
+
+        let statuses = parse_porcelain_status(b" M app/src/lib.rs\0?? top.rs\0", "app/");
+        assert_eq!(statuses.len(), 1);
+        assert_eq!(statuses.get("src/lib.rs"), Some(&GitStatus::Modified));
+    }
+
+    #[test]
+    fn applies_git_status_to_loaded_nodes() {
+        let mut tree = sample_tree();
+        let statuses = HashMap::from([
+            ("src/Main.rs".to_string(), GitStatus::Modified),
+            ("main.txt".to_string(), GitStatus::Untracked),
+            ("unloaded.rs".to_string(), GitStatus::Added),
+        ]);
+        tree.apply_git_status(&statuses);
+        let status_of = |tree: &WorkspaceTree, path: &str| {
+            tree.nodes
+                .iter()
+                .find(|(_, node)| node.rel_path == path)
+                .and_then(|(_, node)| node.git.clone())
+        };
+        assert_eq!(status_of(&tree, "src/Main.rs"), Some(GitStatus::Modified));
+        assert_eq!(status_of(&tree, "main.txt"), Some(GitStatus::Untracked));
+        assert_eq!(status_of(&tree, "docs/guide.md"), None);
+
+        tree.apply_git_status(&HashMap::new());
+        assert_eq!(status_of(&tree, "src/Main.rs"), None);
+
+        // Outside a repository (or without git) there is nothing to apply.
+        let dir = tempfile::tempdir().unwrap();
+        let mut provider = GitStatusProvider::new(dir.path().to_path_buf());
+        assert!(provider.statuses().is_empty());
+    }
+
+    #[test]
+    fn copies_directory_trees() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("src/nested")).unwrap();
+        fs::write(dir.path().join("src/main.rs"), "fn main() {}").unwrap();
+        fs::write(dir.path().join("src/nested/mod.rs"), "mod a;").unwrap();
+        let mut provider = FsWorkspaceProvider::new(dir.path().to_path_buf());
+
+        provider.copy("src", "backup").unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.path().join("backup/main.rs")).unwrap(),
+            "fn main() {}"
+        );
+        assert_eq!(
+            fs::read_to_string(dir.path().join("backup/nested/mod.rs")).unwrap(),
+            "mod a;"
+        );
+        assert!(dir.path().join("src/nested/mod.rs").is_file());
+
+        let err = provider.copy("src/main.rs", "backup/main.rs").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+        let err = provider.copy("src", "src/nested/src").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn moves_files_between_folders() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("a")).unwrap();
+        fs::create_dir_all(dir.path().join("b")).unwrap();
+        fs::write(dir.path().join("a/file.txt"), "one").unwrap();
+        fs::write(dir.path().join("a/taken.txt"), "two").unwrap();
+        fs::write(dir.path().join("b/taken.txt"), "three").unwrap();
+        let mut provider = FsWorkspaceProvider::new(dir.path().to_path_buf());
+
+        provider.move_path("a/file.txt", "b").unwrap();
+        assert!(!dir.path().join("a/file.txt").exists());
+        assert_eq!(
+            fs::read_to_string(dir.path().join("b/file.txt")).unwrap(),
+            "one"
+        );
+
+        let err = provider.move_path("a/taken.txt", "b").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(
+            fs::read_to_string(dir.path().join("b/taken.txt")).unwrap(),
+            "three"
+        );
+
+        let err = provider.move_path("a", "a").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn read_dir_respects_gitignore() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("build")).unwrap();
+        fs::create_dir_all(dir.path().join("src/build")).unwrap();
+        fs::create_dir_all(dir.path().join("node_modules")).unwrap();
+        fs::write(dir.path().join(".gitignore"), "*.log\n!keep.log\nbuild/\n").unwrap();
+        fs::write(dir.path().join("build.rs"), "").unwrap();
+        fs::write(dir.path().join("debug.log"), "").unwrap();
+        fs::write(dir.path().join("keep.log"), "").unwrap();
+        fs::write(dir.path().join("src/main.rs"), "").unwrap();
+        fs::write(dir.path().join("src/trace.log"), "").unwrap();
+        let names = |provider: &FsWorkspaceProvider, rel: &str| {
+            provider
+                .read_dir(rel)
+                .unwrap()
+                .into_iter()
+                .map(|entry| entry.name)
+                .collect::<Vec<_>>()
+        };
+
+        let provider =
+            FsWorkspaceProvider::with_gitignore(dir.path().to_path_buf(), &["node_modules"]);
+        assert_eq!(
+            names(&provider, ""),
+            vec!["src", ".gitignore", "build.rs", "keep.log"]
+        );
+        assert_eq!(names(&provider, "src"), vec!["main.rs"]);
+
+        let provider = FsWorkspaceProvider::new(dir.path().to_path_buf());
+        assert_eq!(names(&provider, "").len(), 7);
+    }
+
+    #[test]
+    fn ensure_loaded_reveals_deep_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("src/a/b")).unwrap();
+        fs::write(dir.path().join("src/a/b/deep.rs"), "").unwrap();
+        fs::write(dir.path().join("src/top.rs"), "").unwrap();
+        let (provider, mut tree) = tree_for(dir.path());
+        let root = tree.root;
+        assert_eq!(tree.node_by_rel_path(""), Some(root));
+        assert_eq!(tree.node_by_rel_path("src/a/b/deep.rs"), None);
+
+        let deep = tree
+            .ensure_loaded(&provider, "src/a/b/deep.rs")
+            .unwrap()
+            .unwrap();
+        assert_eq!(tree.nodes[deep].rel_path, "src/a/b/deep.rs");
+        assert_eq!(tree.node_by_rel_path("src/a/b/deep.rs"), Some(deep));
+        for ancestor in ["", "src", "src/a", "src/a/b"] {
+            let id = tree.node_by_rel_path(ancestor).unwrap();
+            assert!(tree.expanded.contains(&id), "{ancestor:?} not expanded");
+        }
+        assert!(tree.node_by_rel_path("src/top.rs").is_some());
+        assert_eq!(
+            tree.ensure_loaded(&provider, "src/missing.rs").unwrap(),
+            None
+        );
+        assert_eq!(
+            tree.ensure_loaded(&provider, "src/top.rs/inner").unwrap(),
+            None
+        );
+    }
+
     #[test]
     fn fs_workspace_provider_creation() {
         let path = PathBuf::from("/tmp");